@@ -0,0 +1,220 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A version of the batched `CurlP` hash using 256-bit AVX2 registers instead of machine words.
+
+use crate::ternary::{sponge::CurlPRounds, HASH_LENGTH};
+
+use bee_ternary::{
+    raw::{RawEncoding, RawEncodingBuf},
+    Btrit, TritBuf,
+};
+
+use std::{
+    arch::x86_64::{__m256i, _mm256_and_si256, _mm256_or_si256, _mm256_set1_epi64x, _mm256_xor_si256},
+    mem,
+};
+
+/// The number of inputs that can be processed in a single AVX2 batch.
+pub(crate) const BATCH_SIZE: usize = 8 * mem::size_of::<__m256i>();
+const WORDS: usize = mem::size_of::<__m256i>() / mem::size_of::<u64>();
+
+/// The low and high bits of a trit of each input of a batch, the input `N` being at bit `N % 64` of word `N / 64`.
+#[derive(Clone, Copy)]
+struct BcTrit(__m256i, __m256i);
+
+impl BcTrit {
+    fn from_words(lo: [u64; WORDS], hi: [u64; WORDS]) -> Self {
+        // `__m256i` and `[u64; WORDS]` have the same size and any bit pattern is valid for both.
+        unsafe {
+            Self(
+                mem::transmute::<[u64; WORDS], __m256i>(lo),
+                mem::transmute::<[u64; WORDS], __m256i>(hi),
+            )
+        }
+    }
+
+    fn to_words(self) -> ([u64; WORDS], [u64; WORDS]) {
+        unsafe {
+            (
+                mem::transmute::<__m256i, [u64; WORDS]>(self.0),
+                mem::transmute::<__m256i, [u64; WORDS]>(self.1),
+            )
+        }
+    }
+}
+
+/// Computes the trit of the new state from the trits at two successive indices of the scratch pad.
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn sbox(BcTrit(alpha, beta): BcTrit, temp: BcTrit, ones: __m256i) -> BcTrit {
+    let delta = _mm256_xor_si256(beta, temp.0);
+
+    BcTrit(
+        _mm256_xor_si256(_mm256_and_si256(delta, alpha), ones),
+        _mm256_or_si256(delta, _mm256_xor_si256(alpha, temp.1)),
+    )
+}
+
+struct BctCurlP {
+    rounds: CurlPRounds,
+    state: Vec<BcTrit>,
+    scratch_pad: Vec<BcTrit>,
+}
+
+impl BctCurlP {
+    #[allow(clippy::assertions_on_constants)]
+    fn new(rounds: CurlPRounds) -> Self {
+        // Ensure that changing the hash length will not cause undefined behaviour.
+        assert!(3 * HASH_LENGTH > 728);
+        let high_bits = BcTrit::from_words([u64::MAX; WORDS], [u64::MAX; WORDS]);
+        Self {
+            rounds,
+            state: vec![high_bits; 3 * HASH_LENGTH],
+            scratch_pad: vec![high_bits; 3 * HASH_LENGTH],
+        }
+    }
+
+    fn reset(&mut self) {
+        let high_bits = BcTrit::from_words([u64::MAX; WORDS], [u64::MAX; WORDS]);
+        self.state.iter_mut().for_each(|bc_trit| *bc_trit = high_bits);
+    }
+
+    /// The same transformation as the word-sized `BctCurlP`, on 256 lanes at once.
+    ///
+    /// The CPU has to support AVX2.
+    #[target_feature(enable = "avx2")]
+    unsafe fn transform(&mut self) {
+        let ones = _mm256_set1_epi64x(-1);
+        let mut scratch_pad_index = 0;
+
+        // All the unchecked accesses here are guaranteed to be safe by the assertion inside `new`.
+        for _round in 0..self.rounds as usize {
+            // Every trit of the state is overwritten, so the previous state can be used as the scratch pad as is.
+            mem::swap(&mut self.state, &mut self.scratch_pad);
+
+            let mut previous = *self.scratch_pad.get_unchecked(scratch_pad_index);
+
+            scratch_pad_index += 364;
+
+            let mut temp = *self.scratch_pad.get_unchecked(scratch_pad_index);
+
+            *self.state.get_unchecked_mut(0) = sbox(previous, temp, ones);
+
+            let mut state_index = 1;
+
+            while state_index < self.state.len() {
+                scratch_pad_index += 364;
+
+                previous = temp;
+                temp = *self.scratch_pad.get_unchecked(scratch_pad_index);
+
+                *self.state.get_unchecked_mut(state_index) = sbox(previous, temp, ones);
+
+                state_index += 1;
+
+                scratch_pad_index -= 365;
+
+                previous = temp;
+                temp = *self.scratch_pad.get_unchecked(scratch_pad_index);
+
+                *self.state.get_unchecked_mut(state_index) = sbox(previous, temp, ones);
+
+                state_index += 1;
+            }
+        }
+    }
+
+    /// The CPU has to support AVX2.
+    unsafe fn absorb(&mut self, bc_trits: &[BcTrit]) {
+        for chunk in bc_trits.chunks(HASH_LENGTH) {
+            self.state[..chunk.len()].copy_from_slice(chunk);
+            self.transform();
+        }
+    }
+
+    /// The CPU has to support AVX2.
+    unsafe fn squeeze_into(&mut self, result: &mut [BcTrit]) {
+        for chunk in result.chunks_mut(HASH_LENGTH) {
+            chunk.copy_from_slice(&self.state[..chunk.len()]);
+            self.transform();
+        }
+    }
+}
+
+/// A batched CurlP hasher for up to `BATCH_SIZE` inputs, only available if the CPU supports AVX2.
+pub(crate) struct Avx2BatchHasher {
+    /// An interleaved representation of the input trits.
+    bct_inputs: Vec<BcTrit>,
+    /// An interleaved representation of the output trits.
+    bct_hashes: Vec<BcTrit>,
+    /// The CurlP hasher for binary coded trits.
+    bct_curlp: BctCurlP,
+}
+
+impl Avx2BatchHasher {
+    /// Creates a new hasher if the CPU supports AVX2.
+    pub(crate) fn new(input_length: usize, rounds: CurlPRounds) -> Option<Self> {
+        if !is_x86_feature_detected!("avx2") {
+            return None;
+        }
+
+        let zeros = BcTrit::from_words([0; WORDS], [0; WORDS]);
+
+        Some(Self {
+            bct_inputs: vec![zeros; input_length],
+            bct_hashes: vec![zeros; HASH_LENGTH],
+            bct_curlp: BctCurlP::new(rounds),
+        })
+    }
+
+    /// Hashes the inputs, with the same encoding as the word-sized `BatchHasher::mux`.
+    pub(crate) fn hash<B>(&mut self, trit_inputs: &[TritBuf<B>])
+    where
+        B: RawEncodingBuf,
+        B::Slice: RawEncoding<Trit = Btrit>,
+    {
+        for (i, bc_trit) in self.bct_inputs.iter_mut().enumerate() {
+            let mut lo = [0; WORDS];
+            let mut hi = [0; WORDS];
+
+            for (j, input) in trit_inputs.iter().enumerate() {
+                // This is safe because the `BatchHasher::add` method guarantees that all the inputs have the same
+                // length as `self.bct_inputs`.
+                match unsafe { input.get_unchecked(i) } {
+                    Btrit::NegOne => lo[j / 64] |= 1 << (j % 64),
+                    Btrit::PlusOne => hi[j / 64] |= 1 << (j % 64),
+                    Btrit::Zero => {
+                        lo[j / 64] |= 1 << (j % 64);
+                        hi[j / 64] |= 1 << (j % 64);
+                    }
+                }
+            }
+
+            *bc_trit = BcTrit::from_words(lo, hi);
+        }
+
+        self.bct_curlp.reset();
+        // This is safe because the hasher is only created if the CPU supports AVX2.
+        unsafe {
+            self.bct_curlp.absorb(&self.bct_inputs);
+            self.bct_curlp.squeeze_into(&mut self.bct_hashes);
+        }
+    }
+
+    /// Writes the hash of the input with a specific index into `buf`, like the word-sized `BatchHasher::demux`.
+    pub(crate) fn demux(&self, index: usize, buf: &mut TritBuf) {
+        let (word, bit) = (index / 64, index % 64);
+
+        for (bc_trit, btrit) in self.bct_hashes.iter().zip(buf.iter_mut()) {
+            let (lo, hi) = bc_trit.to_words();
+
+            *btrit = match ((lo[word] >> bit) & 1, (hi[word] >> bit) & 1) {
+                (1, 0) => Btrit::NegOne,
+                (0, 1) => Btrit::PlusOne,
+                // This can only be `(0, 0)` or `(1, 1)`.
+                _ => Btrit::Zero,
+            };
+        }
+    }
+}
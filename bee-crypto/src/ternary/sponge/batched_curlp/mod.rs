@@ -3,11 +3,15 @@
 
 //! A batched version of the `CurlP` hash.
 
+#[cfg(target_arch = "x86_64")]
+mod avx2;
 mod bct;
 mod bct_curlp;
 
 use crate::ternary::sponge::{CurlP, CurlPRounds, Sponge, HASH_LENGTH};
 
+#[cfg(target_arch = "x86_64")]
+use avx2::Avx2BatchHasher;
 use bct::{BcTrit, BcTritBuf};
 use bct_curlp::BctCurlP;
 
@@ -16,7 +20,9 @@ use bee_ternary::{
     Btrit, T1B1Buf, TritBuf,
 };
 
-/// The number of inputs that can be processed in a single batch.
+/// The number of inputs that can be processed in a single batch on any CPU.
+///
+/// Some CPUs allow larger batches, see `BatchHasher::capacity`.
 pub const BATCH_SIZE: usize = 8 * std::mem::size_of::<usize>();
 const HIGH_BITS: usize = usize::max_value();
 
@@ -25,6 +31,9 @@ const HIGH_BITS: usize = usize::max_value();
 /// This hasher works by interleaving the trits of the inputs in each batch and hashing this
 /// interleaved representation. It is also able to fall back to the regular CurlP algorithm if
 /// required.
+///
+/// On `x86_64` CPUs supporting AVX2, detected at runtime, batches larger than `BATCH_SIZE` are
+/// interleaved into 256-bit registers instead of machine words, which hashes more inputs at once.
 pub struct BatchHasher<B: RawEncodingBuf> {
     /// The trits of the inputs before being interleaved.
     trit_inputs: Vec<TritBuf<B>>,
@@ -36,6 +45,12 @@ pub struct BatchHasher<B: RawEncodingBuf> {
     buf_demux: TritBuf,
     /// The CurlP hasher for binary coded trits.
     bct_curlp: BctCurlP,
+    /// The batched CurlP hasher for larger batches, if the CPU supports AVX2.
+    #[cfg(target_arch = "x86_64")]
+    avx2: Option<Avx2BatchHasher>,
+    /// Whether the current batch was hashed by the AVX2 hasher.
+    #[cfg(target_arch = "x86_64")]
+    avx2_batch: bool,
     /// The regular CurlP hasher.
     curlp: CurlP,
 }
@@ -56,20 +71,34 @@ where
             bct_hashes: BcTritBuf::zeros(HASH_LENGTH),
             buf_demux: TritBuf::zeros(HASH_LENGTH),
             bct_curlp: BctCurlP::new(rounds),
+            #[cfg(target_arch = "x86_64")]
+            avx2: Avx2BatchHasher::new(input_length, rounds),
+            #[cfg(target_arch = "x86_64")]
+            avx2_batch: false,
             curlp: CurlP::new(rounds),
         }
     }
 
     /// Add a new input to the batch.
     ///
-    /// It panics if the size of the batch exceeds `BATCH_SIZE` or if `input.len()` is not equal to
+    /// It panics if the size of the batch exceeds `capacity()` or if `input.len()` is not equal to
     /// the `input_length` parameter of the constructor.
     pub fn add(&mut self, input: TritBuf<B>) {
-        assert!(self.trit_inputs.len() < BATCH_SIZE, "Batch is full.");
+        assert!(self.trit_inputs.len() < self.capacity(), "Batch is full.");
         assert_eq!(input.len(), self.bct_inputs.len(), "Input has an incorrect size.");
         self.trit_inputs.push(input);
     }
 
+    /// Return the maximum length of a batch on this CPU, which is at least `BATCH_SIZE`.
+    pub fn capacity(&self) -> usize {
+        #[cfg(target_arch = "x86_64")]
+        if self.avx2.is_some() {
+            return avx2::BATCH_SIZE;
+        }
+
+        BATCH_SIZE
+    }
+
     /// Return the length of the current batch.
     pub fn len(&self) -> usize {
         self.trit_inputs.len()
@@ -126,6 +155,14 @@ where
     /// field is decoded into a trit using the same convention as the `mux` step with an additional
     /// rule for the `(0, 0)` pair of bits which is mapped to the `0` trit.
     fn demux(&mut self, index: usize) -> TritBuf {
+        #[cfg(target_arch = "x86_64")]
+        if self.avx2_batch {
+            if let Some(avx2) = &self.avx2 {
+                avx2.demux(index, &mut self.buf_demux);
+                return self.buf_demux.clone();
+            }
+        }
+
         for (bc_trit, btrit) in self.bct_hashes.iter().zip(self.buf_demux.iter_mut()) {
             let lo = (bc_trit.lo() >> index) & 1;
             let hi = (bc_trit.hi() >> index) & 1;
@@ -147,6 +184,24 @@ where
     /// batched CurlP hasher so it can be called at any time.
     pub fn hash_batched(&mut self) -> impl Iterator<Item = TritBuf> + '_ {
         let total = self.trit_inputs.len();
+        // Batches that fit in machine words are faster to hash that way.
+        #[cfg(target_arch = "x86_64")]
+        {
+            self.avx2_batch = false;
+
+            if total > BATCH_SIZE {
+                if let Some(avx2) = &mut self.avx2 {
+                    avx2.hash(&self.trit_inputs);
+                    self.trit_inputs.clear();
+                    self.avx2_batch = true;
+
+                    return BatchedHashes {
+                        hasher: self,
+                        range: 0..total,
+                    };
+                }
+            }
+        }
         // Reset batched CurlP hasher.
         self.bct_curlp.reset();
         // Multiplex the trits in `trits` and dump them into `inputs`
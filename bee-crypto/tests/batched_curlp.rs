@@ -2,13 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use bee_crypto::ternary::{
-    sponge::{BatchHasher, CurlPRounds, BATCH_SIZE},
+    sponge::{BatchHasher, CurlP81, CurlPRounds, Sponge, BATCH_SIZE},
     HASH_LENGTH,
 };
 
 use bee_ternary::{
     raw::{RawEncoding, RawEncodingBuf},
-    Btrit, T1B1Buf, T5B1Buf, TryteBuf, T1B1, T5B1,
+    Btrit, T1B1Buf, T5B1Buf, TritBuf, TryteBuf, T1B1, T5B1,
 };
 
 const INPUT_243: &str = "HHPELNTNJIOKLYDUW9NDULWPHCWFRPTDIUWLYUHQWWJVPAKKGKOAZFJPQJBLNDPALCVXGJLRBFSHATF9C";
@@ -352,3 +352,93 @@ fn batched_curlp_t1b1_different_input_8019() {
 fn batched_curlp_t5b1_different_input_8019() {
     batched_curlp_different_input::<T5B1Buf, T5B1>();
 }
+
+/// Returns distinct pseudo-random inputs, so that mixing up lanes can't go unnoticed.
+fn random_inputs(count: usize, length: usize) -> Vec<TritBuf<T1B1Buf>> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+
+    (0..count)
+        .map(|_| {
+            let mut input = TritBuf::<T1B1Buf>::with_capacity(length);
+
+            for _ in 0..length {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                input.push(match state % 3 {
+                    0 => Btrit::NegOne,
+                    1 => Btrit::Zero,
+                    _ => Btrit::PlusOne,
+                });
+            }
+
+            input
+        })
+        .collect()
+}
+
+fn batched_curlp_matches_curlp81(batch_hasher: &mut BatchHasher<T1B1Buf>, count: usize, length: usize) {
+    let inputs = random_inputs(count, length);
+
+    for input in inputs.iter() {
+        batch_hasher.add(input.clone());
+    }
+
+    let hashes = batch_hasher.hash_batched().collect::<Vec<_>>();
+
+    assert_eq!(hashes.len(), count);
+    for (index, (input, hash)) in inputs.iter().zip(hashes).enumerate() {
+        assert_eq!(CurlP81::new().digest(input).unwrap(), hash, "input {} failed", index);
+    }
+}
+
+#[test]
+fn batched_curlp_capacity() {
+    let batch_hasher = BatchHasher::<T1B1Buf>::new(HASH_LENGTH, CurlPRounds::Rounds81);
+
+    #[cfg(target_arch = "x86_64")]
+    if is_x86_feature_detected!("avx2") {
+        assert_eq!(batch_hasher.capacity(), 256);
+        return;
+    }
+
+    assert_eq!(batch_hasher.capacity(), BATCH_SIZE);
+}
+
+#[test]
+#[should_panic(expected = "Batch is full.")]
+fn batched_curlp_over_capacity() {
+    let mut batch_hasher = BatchHasher::<T1B1Buf>::new(HASH_LENGTH, CurlPRounds::Rounds81);
+
+    for input in random_inputs(batch_hasher.capacity() + 1, HASH_LENGTH) {
+        batch_hasher.add(input);
+    }
+}
+
+#[test]
+fn batched_curlp_full_capacity_matches_curlp81() {
+    let mut batch_hasher = BatchHasher::<T1B1Buf>::new(HASH_LENGTH, CurlPRounds::Rounds81);
+    let capacity = batch_hasher.capacity();
+
+    batched_curlp_matches_curlp81(&mut batch_hasher, capacity, HASH_LENGTH);
+}
+
+#[test]
+fn batched_curlp_large_batch_matches_curlp81_8019() {
+    let mut batch_hasher = BatchHasher::<T1B1Buf>::new(8019, CurlPRounds::Rounds81);
+    // Large enough to need more than machine words if the CPU allows it.
+    let count = (BATCH_SIZE + 1).min(batch_hasher.capacity());
+
+    batched_curlp_matches_curlp81(&mut batch_hasher, count, 8019);
+}
+
+#[test]
+fn batched_curlp_partial_batches_match_curlp81() {
+    let mut batch_hasher = BatchHasher::<T1B1Buf>::new(HASH_LENGTH, CurlPRounds::Rounds81);
+    let capacity = batch_hasher.capacity();
+
+    // Alternating between batch sizes checks that no state leaks from one batch to the next.
+    for count in [1, BATCH_SIZE + 1, BATCH_SIZE, capacity - 1, 2, capacity].iter() {
+        batched_curlp_matches_curlp81(&mut batch_hasher, (*count).min(capacity), HASH_LENGTH);
+    }
+}
@@ -6,11 +6,12 @@ use crate::{
     workers::{
         consensus::{metadata::WhiteFlagMetadata, state::validate_ledger_state, white_flag},
         error::Error,
-        event::{MilestoneConfirmed, OutputConsumed, OutputCreated},
+        event::{MilestoneConfirmed, OutputConsumed, OutputCreated, UnreferencedMessagesPruned},
         pruning::{
             condition::{should_prune, should_snapshot},
             config::PruningConfig,
             constants::{PRUNING_THRESHOLD, SOLID_ENTRY_POINT_THRESHOLD_FUTURE, SOLID_ENTRY_POINT_THRESHOLD_PAST},
+            prune::{prune_unreferenced_messages, remove_unreferenced_messages},
         },
         snapshot::{config::SnapshotConfig, worker::SnapshotWorker},
         storage::{self, StorageBackend},
@@ -214,6 +215,22 @@ where
             .await;
     }
 
+    remove_unreferenced_messages(
+        tangle,
+        storage,
+        metadata
+            .excluded_no_transaction_messages
+            .iter()
+            .chain(
+                metadata
+                    .excluded_conflicting_messages
+                    .iter()
+                    .map(|(message_id, _)| message_id),
+            )
+            .chain(metadata.included_messages.iter()),
+    )
+    .await?;
+
     info!(
         "Confirmed milestone {}: referenced {}, no transaction {}, conflicting {}, included {}, consumed {}, created {}, receipt {}.",
         milestone.essence().index(),
@@ -298,6 +315,9 @@ where
         // Unwrap is fine because ledger index was already in storage or just added by the snapshot worker.
         let mut ledger_index = storage::fetch_ledger_index(&*storage)?.unwrap();
         let mut receipt_migrated_at = MilestoneIndex(0);
+        // Unreferenced messages are pruned on their own, without advancing the pruning index of the tangle. Entries
+        // below this index that were already deleted are simply not found again after a restart.
+        let mut unreferenced_pruning_index = tangle.get_pruning_index();
 
         node.spawn::<Self, _, _>(|shutdown| async move {
             info!("Running.");
@@ -332,11 +352,32 @@ where
                             // }
                         }
 
-                        if should_prune(&tangle, MilestoneIndex(*ledger_index), delay, &pruning_config) {
-                            // TODO
-                            // if let Err(e) = prune_database(&tangle, MilestoneIndex(*event.index - delay)) {
-                            //     error!("Failed to prune database: {:?}.", e);
-                            // }
+                        if let Some(target_index) =
+                            should_prune(&tangle, MilestoneIndex(*ledger_index), delay, &pruning_config)
+                        {
+                            if target_index > unreferenced_pruning_index {
+                                match prune_unreferenced_messages(
+                                    &tangle,
+                                    &*storage,
+                                    MilestoneIndex(*unreferenced_pruning_index + 1),
+                                    target_index,
+                                )
+                                .await
+                                {
+                                    Ok(pruned) => {
+                                        info!(
+                                            "Pruned {} unreferenced messages up to milestone {}.",
+                                            pruned, *target_index
+                                        );
+                                        unreferenced_pruning_index = target_index;
+                                        bus.dispatch(UnreferencedMessagesPruned {
+                                            index: target_index,
+                                            pruned,
+                                        });
+                                    }
+                                    Err(e) => error!("Failed to prune unreferenced messages: {:?}.", e),
+                                }
+                            }
                         }
                     }
                     ConsensusWorkerCommand::FetchBalance(address, sender) => {
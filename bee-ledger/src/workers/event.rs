@@ -56,3 +56,11 @@ pub struct PrunedIndex {
     /// The pruned index.
     pub index: MilestoneIndex,
 }
+
+/// An event that indicates that the messages that stayed unreferenced up to an index were pruned.
+pub struct UnreferencedMessagesPruned {
+    /// The index up to which unreferenced messages were pruned.
+    pub index: MilestoneIndex,
+    /// The number of pruned messages.
+    pub pruned: usize,
+}
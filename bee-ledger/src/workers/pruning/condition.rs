@@ -38,40 +38,39 @@ pub(crate) fn should_snapshot<B: StorageBackend>(
     solid_index - (depth + snapshot_interval) >= snapshot_index
 }
 
+/// Returns the index up to which the database should be pruned, if it should be.
 pub(crate) fn should_prune<B: StorageBackend>(
     tangle: &MsTangle<B>,
-    mut index: MilestoneIndex,
+    index: MilestoneIndex,
     delay: u32,
     config: &PruningConfig,
-) -> bool {
+) -> Option<MilestoneIndex> {
     if !config.enabled() {
-        return false;
+        return None;
     }
 
     if *index <= delay {
-        return false;
+        return None;
     }
 
     // Pruning happens after creating the snapshot so the metadata should provide the latest index.
     if *tangle.get_snapshot_index() < SOLID_ENTRY_POINT_THRESHOLD_PAST + PRUNING_THRESHOLD + 1 {
-        return false;
+        return None;
     }
 
     let target_index_max =
         MilestoneIndex(*tangle.get_snapshot_index() - SOLID_ENTRY_POINT_THRESHOLD_PAST - PRUNING_THRESHOLD - 1);
 
-    if index > target_index_max {
-        index = target_index_max;
-    }
+    let target_index = MilestoneIndex(*index - delay).min(target_index_max);
 
-    if tangle.get_pruning_index() >= index {
-        return false;
+    if tangle.get_pruning_index() >= target_index {
+        return None;
     }
 
     // We prune in "PRUNING_THRESHOLD" steps to recalculate the solid_entry_points.
-    if *tangle.get_entry_point_index() + PRUNING_THRESHOLD + 1 > *index {
-        return false;
+    if *tangle.get_entry_point_index() + PRUNING_THRESHOLD + 1 > *target_index {
+        return None;
     }
 
-    true
+    Some(target_index)
 }
@@ -5,6 +5,7 @@
 #![allow(missing_docs)]
 
 pub mod config;
+pub mod prune;
 
 pub(crate) mod condition;
 pub(crate) mod constants;
@@ -0,0 +1,96 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Module containing the removal of the messages that were never referenced.
+//!
+//! This only ever touches unreferenced messages and is tracked by its own index: the pruning index of the tangle is
+//! left to the actual pruning of the database.
+
+use crate::workers::{
+    error::Error,
+    storage::{self, StorageBackend},
+};
+
+use bee_message::{milestone::MilestoneIndex, MessageId};
+use bee_storage::access::BatchBuilder;
+use bee_tangle::MsTangle;
+
+use log::debug;
+
+/// Stops tracking the given messages as unreferenced, typically because white flag just referenced them.
+pub async fn remove_unreferenced_messages<'a, B: StorageBackend>(
+    tangle: &MsTangle<B>,
+    storage: &B,
+    message_ids: impl IntoIterator<Item = &'a MessageId>,
+) -> Result<(), Error> {
+    let mut unreferenced_messages = Vec::new();
+
+    for message_id in message_ids {
+        if let Some(index) = tangle.remove_unreferenced_message(message_id).await {
+            unreferenced_messages.push((index, *message_id));
+        }
+    }
+
+    storage::delete_unreferenced_messages(storage, &unreferenced_messages)
+}
+
+/// Deletes the messages that were recorded as unreferenced at the given index and that are still unreferenced.
+/// Returns the number of pruned messages.
+async fn prune_unreferenced_messages_at<B: StorageBackend>(
+    tangle: &MsTangle<B>,
+    storage: &B,
+    index: MilestoneIndex,
+) -> Result<usize, Error> {
+    let unreferenced_messages = storage::fetch_unreferenced_messages(storage, index)?.unwrap_or_default();
+    let mut batch = B::batch_begin();
+    let mut pruned = 0;
+
+    for unreferenced_message in unreferenced_messages {
+        let message_id = unreferenced_message.message_id();
+
+        storage::delete_unreferenced_message_batch(storage, &mut batch, index, message_id)?;
+        tangle.remove_unreferenced_message(message_id).await;
+
+        // Messages that got referenced in the meantime are not pruned here, only their entry is removed.
+        let referenced = storage::fetch_message_metadata(storage, message_id)?
+            .map_or(true, |metadata| metadata.flags().is_referenced());
+
+        if referenced {
+            continue;
+        }
+
+        if let Some(message) = storage::fetch_message(storage, message_id)? {
+            storage::delete_message_batch(storage, &mut batch, message_id, &message)?;
+            tangle.remove_from_cache(message_id).await;
+            pruned += 1;
+        }
+    }
+
+    storage
+        .batch_commit(batch, true)
+        .map_err(|e| Error::Storage(Box::new(e)))?;
+
+    Ok(pruned)
+}
+
+/// Deletes the messages that were recorded as unreferenced from the start index up to the target index, both
+/// included, and that are still unreferenced. Returns the number of pruned messages.
+pub async fn prune_unreferenced_messages<B: StorageBackend>(
+    tangle: &MsTangle<B>,
+    storage: &B,
+    start_index: MilestoneIndex,
+    target_index: MilestoneIndex,
+) -> Result<usize, Error> {
+    let mut pruned = 0;
+
+    for index in *start_index..*target_index + 1 {
+        let index = MilestoneIndex(index);
+        let pruned_at = prune_unreferenced_messages_at(tangle, storage, index).await?;
+
+        debug!("Pruned {} unreferenced messages of milestone {}.", pruned_at, *index);
+
+        pruned += pruned_at;
+    }
+
+    Ok(pruned)
+}
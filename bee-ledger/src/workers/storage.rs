@@ -15,12 +15,15 @@ use bee_message::{
     address::{Address, Ed25519Address},
     milestone::MilestoneIndex,
     output::{Output, OutputId},
+    Message, MessageId,
 };
 use bee_storage::{
     access::{AsIterator, Batch, BatchBuilder, Exist, Fetch, Insert, Truncate},
     backend,
 };
-use bee_tangle::solid_entry_point::SolidEntryPoint;
+use bee_tangle::{
+    metadata::MessageMetadata, solid_entry_point::SolidEntryPoint, unreferenced_message::UnreferencedMessage,
+};
 
 use std::collections::HashMap;
 
@@ -37,6 +40,10 @@ pub trait StorageBackend:
     + Batch<Address, Balance>
     + Batch<(MilestoneIndex, Receipt), ()>
     + Batch<(bool, TreasuryOutput), ()>
+    + Batch<MessageId, Message>
+    + Batch<MessageId, MessageMetadata>
+    + Batch<(MessageId, MessageId), ()>
+    + Batch<(MilestoneIndex, UnreferencedMessage), ()>
     + Exist<Unspent, ()>
     + Fetch<(), SnapshotInfo>
     + Fetch<OutputId, CreatedOutput>
@@ -44,6 +51,7 @@ pub trait StorageBackend:
     + Fetch<Address, Balance>
     + Fetch<bool, Vec<TreasuryOutput>>
     + Fetch<Ed25519Address, Vec<OutputId>>
+    + Fetch<MilestoneIndex, Vec<UnreferencedMessage>>
     + Insert<(), SnapshotInfo>
     + Insert<(), LedgerIndex>
     + Insert<(bool, TreasuryOutput), ()>
@@ -67,6 +75,10 @@ impl<T> StorageBackend for T where
         + Batch<Address, Balance>
         + Batch<(MilestoneIndex, Receipt), ()>
         + Batch<(bool, TreasuryOutput), ()>
+        + Batch<MessageId, Message>
+        + Batch<MessageId, MessageMetadata>
+        + Batch<(MessageId, MessageId), ()>
+        + Batch<(MilestoneIndex, UnreferencedMessage), ()>
         + Exist<Unspent, ()>
         + Fetch<(), SnapshotInfo>
         + Fetch<OutputId, CreatedOutput>
@@ -74,6 +86,7 @@ impl<T> StorageBackend for T where
         + Fetch<Address, Balance>
         + Fetch<bool, Vec<TreasuryOutput>>
         + Fetch<Ed25519Address, Vec<OutputId>>
+        + Fetch<MilestoneIndex, Vec<UnreferencedMessage>>
         + Insert<(), SnapshotInfo>
         + Insert<(), LedgerIndex>
         + Insert<(bool, TreasuryOutput), ()>
@@ -441,6 +454,68 @@ pub(crate) fn unspend_treasury_output_batch<B: StorageBackend>(
         .map_err(|e| Error::Storage(Box::new(e)))
 }
 
+pub(crate) fn fetch_message<B: StorageBackend>(storage: &B, message_id: &MessageId) -> Result<Option<Message>, Error> {
+    Fetch::<MessageId, Message>::fetch(storage, message_id).map_err(|e| Error::Storage(Box::new(e)))
+}
+
+pub(crate) fn fetch_message_metadata<B: StorageBackend>(
+    storage: &B,
+    message_id: &MessageId,
+) -> Result<Option<MessageMetadata>, Error> {
+    Fetch::<MessageId, MessageMetadata>::fetch(storage, message_id).map_err(|e| Error::Storage(Box::new(e)))
+}
+
+pub(crate) fn delete_message_batch<B: StorageBackend>(
+    storage: &B,
+    batch: &mut <B as BatchBuilder>::Batch,
+    message_id: &MessageId,
+    message: &Message,
+) -> Result<(), Error> {
+    for parent in message.parents().iter() {
+        Batch::<(MessageId, MessageId), ()>::batch_delete(storage, batch, &(*parent, *message_id))
+            .map_err(|e| Error::Storage(Box::new(e)))?;
+    }
+    Batch::<MessageId, MessageMetadata>::batch_delete(storage, batch, message_id)
+        .map_err(|e| Error::Storage(Box::new(e)))?;
+    Batch::<MessageId, Message>::batch_delete(storage, batch, message_id).map_err(|e| Error::Storage(Box::new(e)))
+}
+
+pub(crate) fn fetch_unreferenced_messages<B: StorageBackend>(
+    storage: &B,
+    index: MilestoneIndex,
+) -> Result<Option<Vec<UnreferencedMessage>>, Error> {
+    Fetch::<MilestoneIndex, Vec<UnreferencedMessage>>::fetch(storage, &index).map_err(|e| Error::Storage(Box::new(e)))
+}
+
+pub(crate) fn delete_unreferenced_message_batch<B: StorageBackend>(
+    storage: &B,
+    batch: &mut <B as BatchBuilder>::Batch,
+    index: MilestoneIndex,
+    message_id: &MessageId,
+) -> Result<(), Error> {
+    Batch::<(MilestoneIndex, UnreferencedMessage), ()>::batch_delete(
+        storage,
+        batch,
+        &(index, UnreferencedMessage::new(*message_id)),
+    )
+    .map_err(|e| Error::Storage(Box::new(e)))
+}
+
+pub(crate) fn delete_unreferenced_messages<B: StorageBackend>(
+    storage: &B,
+    unreferenced_messages: &[(MilestoneIndex, MessageId)],
+) -> Result<(), Error> {
+    let mut batch = B::batch_begin();
+
+    for (index, message_id) in unreferenced_messages {
+        delete_unreferenced_message_batch(storage, &mut batch, *index, message_id)?;
+    }
+
+    storage
+        .batch_commit(batch, true)
+        .map_err(|e| Error::Storage(Box::new(e)))
+}
+
 /// Fetches the unspent treasury output from the storage.
 pub fn fetch_unspent_treasury_output<B: StorageBackend>(storage: &B) -> Result<TreasuryOutput, Error> {
     if let Some(outputs) =
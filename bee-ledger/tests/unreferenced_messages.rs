@@ -0,0 +1,96 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "workers")]
+
+use bee_ledger::workers::pruning::prune::{prune_unreferenced_messages, remove_unreferenced_messages};
+use bee_message::{milestone::MilestoneIndex, Message, MessageId};
+use bee_runtime::resource::ResourceHandle;
+use bee_storage::{access::Fetch, backend::StorageBackend};
+use bee_storage_sled::{config::SledConfigBuilder, storage::Storage};
+use bee_tangle::{
+    config::TangleConfig, metadata::MessageMetadata, unreferenced_message::UnreferencedMessage, MsTangle,
+};
+use bee_test::rand::message::{rand_message, rand_message_id};
+
+fn storage(name: &str) -> ResourceHandle<Storage> {
+    let path = String::from("./tests/database/") + name;
+    let _ = std::fs::remove_dir_all(&path);
+
+    let config = SledConfigBuilder::default()
+        .with_path(path)
+        .with_temporary(true)
+        .finish();
+
+    ResourceHandle::new(Storage::start(config).unwrap())
+}
+
+fn fetch_unreferenced_messages(storage: &Storage, index: MilestoneIndex) -> Vec<UnreferencedMessage> {
+    Fetch::<MilestoneIndex, Vec<UnreferencedMessage>>::fetch(storage, &index)
+        .unwrap()
+        .unwrap_or_default()
+}
+
+async fn insert_unreferenced_message(tangle: &MsTangle<Storage>) -> MessageId {
+    let message_id = rand_message_id();
+    tangle
+        .insert(rand_message(), message_id, MessageMetadata::arrived())
+        .await;
+    message_id
+}
+
+#[tokio::test]
+async fn referenced_messages_are_not_tracked_anymore() {
+    let storage = storage("referenced_messages_are_not_tracked_anymore");
+    let tangle = MsTangle::new(TangleConfig::build().finish(), storage.clone());
+    tangle.update_latest_milestone_index(MilestoneIndex(1));
+
+    let referenced = insert_unreferenced_message(&tangle).await;
+    let unreferenced = insert_unreferenced_message(&tangle).await;
+
+    remove_unreferenced_messages(&tangle, &*storage, &[referenced])
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fetch_unreferenced_messages(&storage, MilestoneIndex(1)),
+        vec![UnreferencedMessage::new(unreferenced)]
+    );
+    assert_eq!(tangle.remove_unreferenced_message(&referenced).await, None);
+}
+
+#[tokio::test]
+async fn stale_unreferenced_messages_are_pruned() {
+    let storage = storage("stale_unreferenced_messages_are_pruned");
+    let tangle = MsTangle::new(TangleConfig::build().finish(), storage.clone());
+
+    tangle.update_latest_milestone_index(MilestoneIndex(1));
+    let stale = insert_unreferenced_message(&tangle).await;
+    // Referenced by a milestone whose confirmation did not know about the entry, e.g. after a restart.
+    let referenced = insert_unreferenced_message(&tangle).await;
+    tangle
+        .update_metadata(&referenced, |metadata| metadata.reference(0))
+        .await;
+
+    tangle.update_latest_milestone_index(MilestoneIndex(2));
+    let recent = insert_unreferenced_message(&tangle).await;
+
+    let pruned = prune_unreferenced_messages(&tangle, &*storage, MilestoneIndex(1), MilestoneIndex(1))
+        .await
+        .unwrap();
+
+    assert_eq!(pruned, 1);
+    assert!(fetch_unreferenced_messages(&storage, MilestoneIndex(1)).is_empty());
+    assert!(Fetch::<MessageId, Message>::fetch(&*storage, &stale).unwrap().is_none());
+    assert!(Fetch::<MessageId, Message>::fetch(&*storage, &referenced)
+        .unwrap()
+        .is_some());
+    assert!(!tangle.contains(&stale).await);
+
+    // Entries past the target index are left untouched.
+    assert_eq!(
+        fetch_unreferenced_messages(&storage, MilestoneIndex(2)),
+        vec![UnreferencedMessage::new(recent)]
+    );
+    assert!(tangle.contains(&recent).await);
+}
@@ -0,0 +1,76 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_network::{
+    bench::{channel, write_gossip, Limits, WriteEnd},
+    Bandwidth, CoalescingConfig, OverflowPolicy,
+};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures::io::{AllowStdIo, BufWriter};
+use tokio::runtime::Runtime;
+
+use std::{
+    io,
+    net::{TcpListener, TcpStream},
+    thread,
+};
+
+const IO_BUFFER_LEN: usize = 32 * 1024;
+const NUM_MESSAGES: usize = 2_000;
+
+/// Connects to a local TCP listener that discards everything it receives, so that every flush costs a real syscall.
+fn connect() -> TcpStream {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        io::copy(&mut stream, &mut io::sink()).unwrap();
+    });
+
+    let stream = TcpStream::connect(addr).unwrap();
+    stream.set_nodelay(true).unwrap();
+    stream
+}
+
+async fn write_burst(stream: &TcpStream, message_len: usize, coalescing: &CoalescingConfig) {
+    let (tx, mut rx) = channel(NUM_MESSAGES, OverflowPolicy::Block, || {});
+
+    for _ in 0..NUM_MESSAGES {
+        tx.try_send(vec![0u8; message_len]).unwrap();
+    }
+    drop(tx);
+
+    let mut writer = BufWriter::with_capacity(IO_BUFFER_LEN, AllowStdIo::new(stream.try_clone().unwrap()));
+    let end = write_gossip(&mut writer, &mut rx, &Bandwidth::new(), &Limits::default(), coalescing).await;
+
+    assert_eq!(end, WriteEnd::Closed);
+}
+
+fn write_coalescing(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let stream = connect();
+
+    let configs = [
+        ("disabled", CoalescingConfig::default()),
+        ("enabled", CoalescingConfig::build().with_enabled(true).finish()),
+    ];
+
+    let mut group = c.benchmark_group("write_coalescing");
+
+    for message_len in [64usize, 512, 4096].iter() {
+        group.throughput(Throughput::Bytes((NUM_MESSAGES * message_len) as u64));
+
+        for (name, config) in configs.iter() {
+            group.bench_with_input(BenchmarkId::new(*name, message_len), message_len, |b, &message_len| {
+                b.iter(|| rt.block_on(write_burst(&stream, message_len, config)))
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, write_coalescing);
+criterion_main!(benches);
@@ -99,7 +99,7 @@ async fn main() {
                             stdin().read_line(&mut msg).unwrap();
                             let msg = msg.trim_end().to_string();
 
-                            gossip_out.send(msg.into_bytes()).expect("send message");
+                            gossip_out.try_send(msg.into_bytes()).expect("send message");
                         }
                     });
 
@@ -3,12 +3,21 @@
 
 #![cfg(feature = "full")]
 
-use crate::alias;
+use crate::{
+    alias,
+    peer::{capabilities::GossipVersion, info::PeerRelation},
+    swarm::protocols::iota_gossip::OverflowPolicy,
+};
 
+use ipnet::IpNet;
 use libp2p::{multiaddr::Protocol, Multiaddr, PeerId};
 use serde::Deserialize;
 
-use std::{borrow::Cow, collections::HashSet};
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
 const DEFAULT_BIND_MULTIADDR: &str = "/ip4/0.0.0.0/tcp/15600";
 
@@ -17,6 +26,40 @@ const MIN_RECONNECT_INTERVAL_SECS: u64 = 1;
 
 pub const DEFAULT_MAX_UNKOWN_PEERS: usize = 4;
 
+pub const DEFAULT_MAX_DISCOVERED_PEERS: usize = 4;
+
+pub const DEFAULT_DISCOVERY_INTERVAL_SECS: u64 = 60;
+const MIN_DISCOVERY_INTERVAL_SECS: u64 = 1;
+
+pub const DEFAULT_GOSSIP_QUEUE_CAPACITY: usize = 1024;
+
+/// Networks that are not routable on the public internet.
+const BOGON_NETWORKS: &[&str] = &[
+    "0.0.0.0/8",
+    "10.0.0.0/8",
+    "100.64.0.0/10",
+    "127.0.0.0/8",
+    "169.254.0.0/16",
+    "172.16.0.0/12",
+    "192.0.0.0/24",
+    "192.0.2.0/24",
+    "192.168.0.0/16",
+    "198.18.0.0/15",
+    "198.51.100.0/24",
+    "203.0.113.0/24",
+    "224.0.0.0/4",
+    "240.0.0.0/4",
+    "::/128",
+    "::1/128",
+    "2001:db8::/32",
+    "fc00::/7",
+    "fe80::/10",
+    "ff00::/8",
+];
+
+pub const DEFAULT_COALESCING_MAX_BYTES: usize = 16 * 1024;
+pub const DEFAULT_COALESCING_MAX_DELAY_MILLIS: u64 = 5;
+
 /// [`NetworkConfigBuilder`] errors.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -55,15 +98,26 @@ pub enum Error {
     /// The provided [`Multiaddr`] lacks the P2p [`Protocol`].
     #[error("Invalid P2p Multiaddr. Did you forget to add '.../p2p/12D3Koo...'?")]
     MissingP2pProtocol,
+
+    /// Parsing of an IP network in CIDR notation failed.
+    #[error("Parsing of '{}' to an IP network failed.", .0)]
+    InvalidIpNetwork(String),
 }
 
 /// The network configuration.
 #[derive(Clone)]
 pub struct NetworkConfig {
     pub(crate) bind_multiaddr: Multiaddr,
+    pub(crate) extra_bind_multiaddrs: Vec<Multiaddr>,
+    pub(crate) transports: HashSet<TransportKind>,
     pub(crate) reconnect_interval_secs: u64,
     pub(crate) max_unknown_peers: usize,
     pub(crate) static_peers: HashSet<Peer>,
+    pub(crate) discovery: DiscoveryConfig,
+    pub(crate) peer_store_path: Option<PathBuf>,
+    pub(crate) bandwidth: BandwidthConfig,
+    pub(crate) gossip: GossipConfig,
+    pub(crate) connection_gate: ConnectionGateConfig,
 }
 
 impl NetworkConfig {
@@ -78,7 +132,6 @@ impl NetworkConfig {
     }
 
     /// Returns an in-memory config builder to construct a [`NetworkConfig`] iteratively.
-    #[cfg(test)]
     pub fn build_in_memory() -> InMemoryNetworkConfigBuilder {
         InMemoryNetworkConfigBuilder::new()
     }
@@ -95,14 +148,19 @@ impl NetworkConfig {
             addr = resolve_dns_multiaddr(dns)?;
         }
 
-        // Panic:
-        // The builder ensures that the following unwraps are fine.
-        let port = self.bind_multiaddr.pop().unwrap();
-
-        let _ = self.bind_multiaddr.pop().unwrap();
+        // In-memory addresses have no IP address to replace.
+        if matches!(self.bind_multiaddr.iter().next(), Some(Protocol::Memory(_))) {
+            return Err(Error::InvalidAddressProtocol);
+        }
 
-        self.bind_multiaddr.push(addr);
-        self.bind_multiaddr.push(port);
+        // The builder ensures that the address is the first protocol.
+        let bind_multiaddr = self
+            .bind_multiaddr
+            .iter()
+            .enumerate()
+            .map(|(i, p)| if i == 0 { addr.clone() } else { p })
+            .collect();
+        self.bind_multiaddr = bind_multiaddr;
 
         Ok(())
     }
@@ -115,8 +173,13 @@ impl NetworkConfig {
             return Err(Error::InvalidPortProtocol);
         }
 
-        self.bind_multiaddr.pop();
-        self.bind_multiaddr.push(port);
+        // Keep a trailing `/ws` of WebSocket addresses.
+        let bind_multiaddr = self
+            .bind_multiaddr
+            .iter()
+            .map(|p| if matches!(p, Protocol::Tcp(_)) { port.clone() } else { p })
+            .collect();
+        self.bind_multiaddr = bind_multiaddr;
 
         Ok(())
     }
@@ -144,6 +207,16 @@ impl NetworkConfig {
         &self.bind_multiaddr
     }
 
+    /// Returns the additional bind addresses the node listens on.
+    pub fn extra_bind_multiaddrs(&self) -> &[Multiaddr] {
+        &self.extra_bind_multiaddrs
+    }
+
+    /// Returns the transports the node uses to listen and dial.
+    pub fn transports(&self) -> &HashSet<TransportKind> {
+        &self.transports
+    }
+
     /// Returns the number of seconds at which reconnect attempts occur.
     pub fn reconnect_interval_secs(&self) -> u64 {
         self.reconnect_interval_secs
@@ -158,6 +231,102 @@ impl NetworkConfig {
     pub fn static_peers(&self) -> &HashSet<Peer> {
         &self.static_peers
     }
+
+    /// Returns the peer discovery configuration.
+    pub fn discovery(&self) -> &DiscoveryConfig {
+        &self.discovery
+    }
+
+    /// Returns the path of the file the peers and bans are persisted in, if any.
+    pub fn peer_store_path(&self) -> Option<&Path> {
+        self.peer_store_path.as_deref()
+    }
+
+    /// Returns the bandwidth limits of the gossip traffic.
+    pub fn bandwidth(&self) -> &BandwidthConfig {
+        &self.bandwidth
+    }
+
+    /// Returns the configuration of the gossip queues.
+    pub fn gossip(&self) -> &GossipConfig {
+        &self.gossip
+    }
+
+    /// Returns the rules which addresses the node accepts connections from and dials.
+    pub fn connection_gate(&self) -> &ConnectionGateConfig {
+        &self.connection_gate
+    }
+}
+
+/// Validates a bind address, and resolves its domain name if necessary.
+fn validate_bind_multiaddr(mut multiaddr: Multiaddr) -> Result<Multiaddr, Error> {
+    let mut valid = false;
+    let mut is_dns = false;
+    let mut is_memory = false;
+    let mut is_ws = false;
+
+    for (i, p) in multiaddr.iter().enumerate() {
+        match i {
+            0 => match p {
+                Protocol::Memory(_) => {
+                    is_memory = true;
+                    valid = true;
+                }
+                Protocol::Ip4(_) | Protocol::Ip6(_) => {}
+                Protocol::Dns(_) => is_dns = true,
+                _ => return Err(Error::InvalidProtocol(0)),
+            },
+            1 if !is_memory => {
+                if !matches!(p, Protocol::Tcp(_)) {
+                    return Err(Error::InvalidProtocol(1));
+                }
+                valid = true;
+            }
+            2 if !is_memory => {
+                if !matches!(p, Protocol::Ws(_)) {
+                    return Err(Error::InvalidProtocol(2));
+                }
+                is_ws = true;
+            }
+            _ => return Err(Error::MultiaddrOverspecified),
+        }
+    }
+    if !valid {
+        return Err(Error::MultiaddrUnderspecified);
+    }
+
+    if is_dns {
+        // Panic:
+        // We know at this point, that `multiaddr` is valid, so unwrapping is fine.
+        let ws = if is_ws { multiaddr.pop() } else { None };
+        let port = if let Protocol::Tcp(port) = multiaddr.pop().unwrap() {
+            port
+        } else {
+            unreachable!("already checked");
+        };
+        let ip = if let Protocol::Dns(dns) = multiaddr.pop().unwrap() {
+            let socket_dns = {
+                let mut socket_addr = String::with_capacity(16);
+                socket_addr.push_str(&dns);
+                socket_addr.push(':');
+                socket_addr.push_str(&port.to_string());
+                socket_addr
+            };
+
+            resolve_dns_multiaddr(socket_dns.into())?
+        } else {
+            unreachable!("already checked");
+        };
+
+        multiaddr.push(ip);
+        multiaddr.push(Protocol::Tcp(port));
+
+        if let Some(ws) = ws {
+            multiaddr.push(ws);
+        }
+    }
+
+    Ok(multiaddr)
 }
 
 fn resolve_dns_multiaddr(dns: Cow<'_, str>) -> Result<Protocol, Error> {
@@ -181,9 +350,16 @@ impl Default for NetworkConfig {
             // Panic:
             // Unwrapping is fine, because we made sure that the default is parsable.
             bind_multiaddr: DEFAULT_BIND_MULTIADDR.parse().unwrap(),
+            extra_bind_multiaddrs: Vec::new(),
+            transports: vec![TransportKind::Tcp].into_iter().collect(),
             reconnect_interval_secs: DEFAULT_RECONNECT_INTERVAL_SECS,
             max_unknown_peers: DEFAULT_MAX_UNKOWN_PEERS,
             static_peers: Default::default(),
+            discovery: Default::default(),
+            peer_store_path: None,
+            bandwidth: Default::default(),
+            gossip: Default::default(),
+            connection_gate: Default::default(),
         }
     }
 }
@@ -193,9 +369,21 @@ impl Default for NetworkConfig {
 pub struct NetworkConfigBuilder {
     #[serde(rename = "bind_address")]
     bind_multiaddr: Option<Multiaddr>,
+    #[serde(default, rename = "extra_bind_addresses")]
+    extra_bind_multiaddrs: Vec<Multiaddr>,
+    transports: Option<Vec<TransportKind>>,
     reconnect_interval_secs: Option<u64>,
     max_unknown_peers: Option<usize>,
     peering: PeeringConfigBuilder,
+    #[serde(default)]
+    discovery: DiscoveryConfigBuilder,
+    peer_store_path: Option<PathBuf>,
+    #[serde(default)]
+    bandwidth: BandwidthConfigBuilder,
+    #[serde(default)]
+    gossip: GossipConfigBuilder,
+    #[serde(default)]
+    connection_gate: ConnectionGateConfigBuilder,
 }
 
 impl NetworkConfigBuilder {
@@ -204,66 +392,29 @@ impl NetworkConfigBuilder {
         Self::default()
     }
 
-    /// Specifies the bind addresses.
-    pub fn with_bind_multiaddr(mut self, mut multiaddr: Multiaddr) -> Result<Self, Error> {
-        let mut valid = false;
-        let mut is_dns = false;
-
-        for (i, p) in multiaddr.iter().enumerate() {
-            match i {
-                0 => {
-                    if !matches!(p, Protocol::Ip4(_) | Protocol::Ip6(_) | Protocol::Dns(_)) {
-                        return Err(Error::InvalidProtocol(0));
-                    }
-
-                    if matches!(p, Protocol::Dns(_)) {
-                        is_dns = true;
-                    }
-                }
-                1 => {
-                    if !matches!(p, Protocol::Tcp(_)) {
-                        return Err(Error::InvalidProtocol(1));
-                    }
-                    valid = true;
-                }
-                _ => return Err(Error::MultiaddrOverspecified),
-            }
-        }
-        if !valid {
-            return Err(Error::MultiaddrUnderspecified);
-        }
-
-        if is_dns {
-            let port = multiaddr.pop().unwrap();
-            let port = if let Protocol::Tcp(port) = port {
-                port
-            } else {
-                unreachable!("already checked");
-            };
-            // Panic:
-            // We know at this point, that `multiaddr` is valid, so unwrapping is fine.
-            let ip = if let Protocol::Dns(dns) = multiaddr.pop().unwrap() {
-                let socket_dns = {
-                    let mut socket_addr = String::with_capacity(16);
-                    socket_addr.push_str(&dns);
-                    socket_addr.push(':');
-                    socket_addr.push_str(&port.to_string());
-                    socket_addr
-                };
-
-                resolve_dns_multiaddr(socket_dns.into())?
-            } else {
-                unreachable!("already checked");
-            };
-
-            multiaddr.push(ip);
-            multiaddr.push(Protocol::Tcp(port));
-        }
+    /// Specifies the bind address.
+    ///
+    /// Supported are TCP (`/ip4/0.0.0.0/tcp/15600`), WebSocket (`/ip4/0.0.0.0/tcp/15601/ws`) and in-memory
+    /// (`/memory/1337`) addresses.
+    pub fn with_bind_multiaddr(mut self, multiaddr: Multiaddr) -> Result<Self, Error> {
+        self.bind_multiaddr.replace(validate_bind_multiaddr(multiaddr)?);
+        Ok(self)
+    }
 
-        self.bind_multiaddr.replace(multiaddr);
+    /// Adds another bind address, so that the node listens on several addresses, e.g. for TCP and WebSocket, at once.
+    pub fn with_extra_bind_multiaddr(mut self, multiaddr: Multiaddr) -> Result<Self, Error> {
+        self.extra_bind_multiaddrs.push(validate_bind_multiaddr(multiaddr)?);
         Ok(self)
     }
 
+    /// Adds a transport the node uses to dial, even if it doesn't listen on an address of that transport.
+    ///
+    /// The transports of the bind addresses are always added.
+    pub fn with_transport(mut self, transport: TransportKind) -> Self {
+        self.transports.get_or_insert_with(Vec::new).push(transport);
+        self
+    }
+
     /// Specifies the interval (in seconds) at which known peers are automatically reconnected if possible.
     ///
     /// The allowed minimum value for the `secs` argument is `1`.
@@ -279,29 +430,75 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Specifies the peer discovery configuration.
+    pub fn with_discovery(mut self, discovery: DiscoveryConfigBuilder) -> Self {
+        self.discovery = discovery;
+        self
+    }
+
+    /// Specifies the path of the file the peers and bans are persisted in.
+    pub fn with_peer_store_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.peer_store_path.replace(path.into());
+        self
+    }
+
+    /// Specifies the bandwidth limits of the gossip traffic.
+    pub fn with_bandwidth(mut self, bandwidth: BandwidthConfigBuilder) -> Self {
+        self.bandwidth = bandwidth;
+        self
+    }
+
+    /// Specifies the configuration of the gossip queues.
+    pub fn with_gossip(mut self, gossip: GossipConfigBuilder) -> Self {
+        self.gossip = gossip;
+        self
+    }
+
+    /// Specifies the rules which addresses the node accepts connections from and dials.
+    pub fn with_connection_gate(mut self, connection_gate: ConnectionGateConfigBuilder) -> Self {
+        self.connection_gate = connection_gate;
+        self
+    }
+
     /// Builds the network config.
     pub fn finish(self) -> Result<NetworkConfig, Error> {
+        let bind_multiaddr = self
+            .bind_multiaddr
+            // Panic:
+            // We made sure that the default is parsable.
+            .unwrap_or_else(|| DEFAULT_BIND_MULTIADDR.parse().unwrap());
+
+        let mut transports = self.transports.unwrap_or_default().into_iter().collect::<HashSet<_>>();
+        transports.extend(
+            std::iter::once(&bind_multiaddr)
+                .chain(self.extra_bind_multiaddrs.iter())
+                .filter_map(TransportKind::of),
+        );
+
         Ok(NetworkConfig {
-            bind_multiaddr: self
-                .bind_multiaddr
-                // Panic:
-                // We made sure that the default is parsable.
-                .unwrap_or_else(|| DEFAULT_BIND_MULTIADDR.parse().unwrap()),
+            bind_multiaddr,
+            extra_bind_multiaddrs: self.extra_bind_multiaddrs,
+            transports,
             reconnect_interval_secs: self.reconnect_interval_secs.unwrap_or(DEFAULT_RECONNECT_INTERVAL_SECS),
             max_unknown_peers: self.max_unknown_peers.unwrap_or(DEFAULT_MAX_UNKOWN_PEERS),
             static_peers: self.peering.finish()?.peers,
+            discovery: self.discovery.finish()?,
+            peer_store_path: self.peer_store_path,
+            bandwidth: self.bandwidth.finish(),
+            gossip: self.gossip.finish(),
+            connection_gate: self.connection_gate.finish()?,
         })
     }
 }
 
 /// An in-memory network config builder, that becomes useful as part of integration testing.
-#[cfg(test)]
 #[derive(Default)]
 pub struct InMemoryNetworkConfigBuilder {
     bind_multiaddr: Option<Multiaddr>,
+    discovery: Option<DiscoveryConfig>,
+    gossip: Option<GossipConfig>,
 }
 
-#[cfg(test)]
 impl InMemoryNetworkConfigBuilder {
     /// Creates a new default builder.
     pub fn new() -> Self {
@@ -324,6 +521,18 @@ impl InMemoryNetworkConfigBuilder {
         self
     }
 
+    /// Specifies the peer discovery configuration.
+    pub fn with_discovery(mut self, discovery: DiscoveryConfig) -> Self {
+        self.discovery.replace(discovery);
+        self
+    }
+
+    /// Specifies the configuration of the gossip queues.
+    pub fn with_gossip(mut self, gossip: GossipConfig) -> Self {
+        self.gossip.replace(gossip);
+        self
+    }
+
     /// Builds the in-memory network config.
     pub fn finish(self) -> NetworkConfig {
         const DEFAULT_BIND_MULTIADDR_MEM: &str = "/memory/0";
@@ -332,22 +541,44 @@ impl InMemoryNetworkConfigBuilder {
             bind_multiaddr: self
                 .bind_multiaddr
                 .unwrap_or_else(|| DEFAULT_BIND_MULTIADDR_MEM.parse().unwrap()),
+            extra_bind_multiaddrs: Vec::new(),
+            transports: vec![TransportKind::Memory].into_iter().collect(),
             reconnect_interval_secs: DEFAULT_RECONNECT_INTERVAL_SECS,
             max_unknown_peers: DEFAULT_MAX_UNKOWN_PEERS,
             static_peers: Default::default(),
+            discovery: self.discovery.unwrap_or_default(),
+            peer_store_path: None,
+            bandwidth: Default::default(),
+            gossip: self.gossip.unwrap_or_default(),
+            connection_gate: Default::default(),
         }
     }
 }
 
-#[derive(Clone)]
+/// The statically configured peers, which can be applied at runtime with [`Command::ReloadPeering`].
+///
+/// [`Command::ReloadPeering`]: crate::Command::ReloadPeering
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct PeeringConfig {
+    /// The static peers.
     pub peers: HashSet<Peer>,
 }
 
-#[derive(Clone)]
+impl PeeringConfig {
+    /// Returns a [`PeeringConfigBuilder`] to construct a [`PeeringConfig`] iteratively.
+    pub fn build() -> PeeringConfigBuilder {
+        PeeringConfigBuilder::default()
+    }
+}
+
+/// A statically configured peer. Peers are identified by their id only.
+#[derive(Clone, Debug)]
 pub struct Peer {
+    /// The peer's id.
     pub peer_id: PeerId,
+    /// The peer's address.
     pub multiaddr: Multiaddr,
+    /// The peer's optional alias.
     pub alias: Option<String>,
 }
 
@@ -363,12 +594,23 @@ impl std::hash::Hash for Peer {
     }
 }
 
+/// A peering configuration builder.
 #[derive(Default, Deserialize)]
 pub struct PeeringConfigBuilder {
-    pub peers: Option<Vec<PeerBuilder>>,
+    peers: Option<Vec<PeerBuilder>>,
 }
 
 impl PeeringConfigBuilder {
+    /// Adds a static peer by its address, which must end with its `/p2p/<peer id>`.
+    pub fn with_peer(mut self, multiaddr: impl Into<String>, alias: Option<String>) -> Self {
+        self.peers.get_or_insert_with(Vec::new).push(PeerBuilder {
+            multiaddr: multiaddr.into(),
+            alias,
+        });
+        self
+    }
+
+    /// Builds the peering config.
     pub fn finish(self) -> Result<PeeringConfig, Error> {
         let peers = match self.peers {
             None => Default::default(),
@@ -418,6 +660,607 @@ pub struct PeerBuilder {
     alias: Option<String>,
 }
 
+/// The peer discovery configuration.
+#[derive(Clone)]
+pub struct DiscoveryConfig {
+    pub(crate) enabled: bool,
+    pub(crate) entry_nodes: Vec<(PeerId, Multiaddr)>,
+    pub(crate) max_discovered_peers: usize,
+    pub(crate) interval_secs: u64,
+}
+
+impl DiscoveryConfig {
+    /// Returns a [`DiscoveryConfigBuilder`] to construct a [`DiscoveryConfig`] iteratively.
+    pub fn build() -> DiscoveryConfigBuilder {
+        DiscoveryConfigBuilder::new()
+    }
+
+    /// Returns whether peer discovery is enabled.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the entry nodes used to bootstrap the peer discovery.
+    pub fn entry_nodes(&self) -> &[(PeerId, Multiaddr)] {
+        &self.entry_nodes
+    }
+
+    /// Returns the maximum number of peers that are automatically chosen by the peer discovery.
+    pub fn max_discovered_peers(&self) -> usize {
+        self.max_discovered_peers
+    }
+
+    /// Returns the number of seconds between two peer discovery queries.
+    pub fn interval_secs(&self) -> u64 {
+        self.interval_secs
+    }
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            entry_nodes: Vec::new(),
+            max_discovered_peers: DEFAULT_MAX_DISCOVERED_PEERS,
+            interval_secs: DEFAULT_DISCOVERY_INTERVAL_SECS,
+        }
+    }
+}
+
+/// A peer discovery configuration builder.
+#[derive(Default, Deserialize)]
+pub struct DiscoveryConfigBuilder {
+    enabled: Option<bool>,
+    entry_nodes: Option<Vec<String>>,
+    max_discovered_peers: Option<usize>,
+    interval_secs: Option<u64>,
+}
+
+impl DiscoveryConfigBuilder {
+    /// Creates a new default builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specifies whether peer discovery is enabled.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled.replace(enabled);
+        self
+    }
+
+    /// Adds an entry node given as a `Multiaddr` string ending with the P2p protocol, e.g.
+    /// `/ip4/127.0.0.1/tcp/15600/p2p/12D3Koo...`.
+    pub fn with_entry_node(mut self, entry_node: impl Into<String>) -> Self {
+        self.entry_nodes.get_or_insert_with(Vec::new).push(entry_node.into());
+        self
+    }
+
+    /// Specifies the maximum number of peers that are automatically chosen by the peer discovery.
+    pub fn with_max_discovered_peers(mut self, n: usize) -> Self {
+        self.max_discovered_peers.replace(n);
+        self
+    }
+
+    /// Specifies the interval (in seconds) at which the peer discovery queries the network for new candidates.
+    ///
+    /// The allowed minimum value for the `secs` argument is `1`.
+    pub fn with_interval_secs(mut self, secs: u64) -> Self {
+        let secs = secs.max(MIN_DISCOVERY_INTERVAL_SECS);
+        self.interval_secs.replace(secs);
+        self
+    }
+
+    /// Builds the peer discovery config.
+    pub fn finish(self) -> Result<DiscoveryConfig, Error> {
+        let entry_nodes = self
+            .entry_nodes
+            .unwrap_or_default()
+            .iter()
+            .map(|entry_node| split_multiaddr(entry_node).map(|(multiaddr, peer_id)| (peer_id, multiaddr)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(DiscoveryConfig {
+            enabled: self.enabled.unwrap_or(false),
+            entry_nodes,
+            max_discovered_peers: self.max_discovered_peers.unwrap_or(DEFAULT_MAX_DISCOVERED_PEERS),
+            interval_secs: self.interval_secs.unwrap_or(DEFAULT_DISCOVERY_INTERVAL_SECS),
+        })
+    }
+}
+
+/// The rules which addresses the node accepts connections from and dials.
+///
+/// Deny rules take precedence over allow rules, and empty allow lists allow everything.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConnectionGateConfig {
+    pub(crate) allow_networks: Vec<IpNet>,
+    pub(crate) deny_networks: Vec<IpNet>,
+    pub(crate) allow_transports: HashSet<TransportKind>,
+    pub(crate) deny_transports: HashSet<TransportKind>,
+    pub(crate) max_connections_per_ip: Option<usize>,
+}
+
+impl ConnectionGateConfig {
+    /// Returns a [`ConnectionGateConfigBuilder`] to construct a [`ConnectionGateConfig`] iteratively.
+    pub fn build() -> ConnectionGateConfigBuilder {
+        ConnectionGateConfigBuilder::new()
+    }
+
+    /// Returns the IP networks connections are restricted to. Empty means all networks are allowed.
+    pub fn allow_networks(&self) -> &[IpNet] {
+        &self.allow_networks
+    }
+
+    /// Returns the IP networks connections are refused from and to.
+    pub fn deny_networks(&self) -> &[IpNet] {
+        &self.deny_networks
+    }
+
+    /// Returns the transports connections are restricted to. Empty means all transports are allowed.
+    pub fn allow_transports(&self) -> &HashSet<TransportKind> {
+        &self.allow_transports
+    }
+
+    /// Returns the transports connections are refused on.
+    pub fn deny_transports(&self) -> &HashSet<TransportKind> {
+        &self.deny_transports
+    }
+
+    /// Returns the maximum number of concurrent connections with peers sharing the same IP address, if any.
+    pub fn max_connections_per_ip(&self) -> Option<usize> {
+        self.max_connections_per_ip
+    }
+}
+
+/// A connection gate configuration builder.
+#[derive(Default, Deserialize)]
+pub struct ConnectionGateConfigBuilder {
+    allow: Option<Vec<String>>,
+    deny: Option<Vec<String>>,
+    deny_bogons: Option<bool>,
+    allow_transports: Option<Vec<TransportKind>>,
+    deny_transports: Option<Vec<TransportKind>>,
+    max_connections_per_ip: Option<usize>,
+}
+
+impl ConnectionGateConfigBuilder {
+    /// Creates a new default builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an IP network in CIDR notation, e.g. `10.0.0.0/8`, that connections are restricted to.
+    pub fn with_allowed_network(mut self, network: impl Into<String>) -> Self {
+        self.allow.get_or_insert_with(Vec::new).push(network.into());
+        self
+    }
+
+    /// Adds an IP network in CIDR notation, e.g. `192.168.0.0/16`, that connections are refused from and to.
+    pub fn with_denied_network(mut self, network: impl Into<String>) -> Self {
+        self.deny.get_or_insert_with(Vec::new).push(network.into());
+        self
+    }
+
+    /// Specifies whether connections from and to private, loopback, link-local, reserved and documentation
+    /// networks are refused.
+    pub fn with_deny_bogons(mut self, deny_bogons: bool) -> Self {
+        self.deny_bogons.replace(deny_bogons);
+        self
+    }
+
+    /// Adds a transport that connections are restricted to.
+    pub fn with_allowed_transport(mut self, transport: TransportKind) -> Self {
+        self.allow_transports.get_or_insert_with(Vec::new).push(transport);
+        self
+    }
+
+    /// Adds a transport that connections are refused on.
+    pub fn with_denied_transport(mut self, transport: TransportKind) -> Self {
+        self.deny_transports.get_or_insert_with(Vec::new).push(transport);
+        self
+    }
+
+    /// Specifies the maximum number of concurrent connections with peers sharing the same IP address.
+    pub fn with_max_connections_per_ip(mut self, n: usize) -> Self {
+        self.max_connections_per_ip.replace(n);
+        self
+    }
+
+    /// Builds the connection gate config.
+    pub fn finish(self) -> Result<ConnectionGateConfig, Error> {
+        let parse = |networks: Option<Vec<String>>| {
+            networks
+                .unwrap_or_default()
+                .iter()
+                .map(|network| {
+                    network
+                        .parse::<IpNet>()
+                        .map_err(|_| Error::InvalidIpNetwork(network.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        let allow_networks = parse(self.allow)?;
+        let mut deny_networks = parse(self.deny)?;
+
+        if self.deny_bogons.unwrap_or(false) {
+            // Panic:
+            // We made sure that the bogon networks are parsable.
+            deny_networks.extend(BOGON_NETWORKS.iter().map(|network| network.parse::<IpNet>().unwrap()));
+        }
+
+        Ok(ConnectionGateConfig {
+            allow_networks,
+            deny_networks,
+            allow_transports: self.allow_transports.unwrap_or_default().into_iter().collect(),
+            deny_transports: self.deny_transports.unwrap_or_default().into_iter().collect(),
+            max_connections_per_ip: self.max_connections_per_ip,
+        })
+    }
+}
+
+/// Token bucket limits, in bytes per second, of the inbound and outbound gossip traffic. `None` means unlimited.
+///
+/// A bucket holds at most one second worth of traffic, which is the largest burst it allows.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize)]
+pub struct TrafficLimits {
+    #[serde(rename = "inbound_bytes_per_sec")]
+    pub(crate) inbound: Option<u64>,
+    #[serde(rename = "outbound_bytes_per_sec")]
+    pub(crate) outbound: Option<u64>,
+}
+
+impl TrafficLimits {
+    /// Creates new [`TrafficLimits`].
+    pub fn new(inbound: Option<u64>, outbound: Option<u64>) -> Self {
+        Self { inbound, outbound }
+    }
+
+    /// Returns the inbound limit in bytes per second, if any.
+    pub fn inbound(&self) -> Option<u64> {
+        self.inbound
+    }
+
+    /// Returns the outbound limit in bytes per second, if any.
+    pub fn outbound(&self) -> Option<u64> {
+        self.outbound
+    }
+}
+
+/// The bandwidth limits of the gossip traffic.
+///
+/// The global limits are shared by all peers, while the limits per relation apply to each peer of that relation
+/// individually.
+#[derive(Clone, Debug, Default)]
+pub struct BandwidthConfig {
+    pub(crate) global: TrafficLimits,
+    pub(crate) known: TrafficLimits,
+    pub(crate) unknown: TrafficLimits,
+    pub(crate) discovered: TrafficLimits,
+}
+
+impl BandwidthConfig {
+    /// Returns a [`BandwidthConfigBuilder`] to construct a [`BandwidthConfig`] iteratively.
+    pub fn build() -> BandwidthConfigBuilder {
+        BandwidthConfigBuilder::new()
+    }
+
+    /// Returns the limits shared by all peers.
+    pub fn global(&self) -> TrafficLimits {
+        self.global
+    }
+
+    /// Returns the limits of each peer with the given relation.
+    pub fn relation(&self, relation: PeerRelation) -> TrafficLimits {
+        match relation {
+            PeerRelation::Known => self.known,
+            PeerRelation::Unknown => self.unknown,
+            PeerRelation::Discovered => self.discovered,
+        }
+    }
+}
+
+/// A bandwidth configuration builder.
+#[derive(Default, Deserialize)]
+pub struct BandwidthConfigBuilder {
+    #[serde(default)]
+    global: TrafficLimits,
+    #[serde(default)]
+    known: TrafficLimits,
+    #[serde(default)]
+    unknown: TrafficLimits,
+    #[serde(default)]
+    discovered: TrafficLimits,
+}
+
+impl BandwidthConfigBuilder {
+    /// Creates a new default builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specifies the limits shared by all peers.
+    pub fn with_global(mut self, limits: TrafficLimits) -> Self {
+        self.global = limits;
+        self
+    }
+
+    /// Specifies the limits of each peer with the given relation.
+    pub fn with_relation(mut self, relation: PeerRelation, limits: TrafficLimits) -> Self {
+        match relation {
+            PeerRelation::Known => self.known = limits,
+            PeerRelation::Unknown => self.unknown = limits,
+            PeerRelation::Discovered => self.discovered = limits,
+        }
+        self
+    }
+
+    /// Builds the bandwidth config.
+    pub fn finish(self) -> BandwidthConfig {
+        BandwidthConfig {
+            global: self.global,
+            known: self.known,
+            unknown: self.unknown,
+            discovered: self.discovered,
+        }
+    }
+}
+
+/// The transports a node can listen on and dial with.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportKind {
+    /// TCP, e.g. `/ip4/0.0.0.0/tcp/15600`.
+    Tcp,
+    /// In-memory, e.g. `/memory/1337`. Useful to run several nodes within one process.
+    Memory,
+    /// WebSocket on top of TCP, e.g. `/ip4/0.0.0.0/tcp/15601/ws`.
+    WebSocket,
+}
+
+impl TransportKind {
+    /// Returns the transport needed to listen on or dial the given address, if any.
+    pub fn of(multiaddr: &Multiaddr) -> Option<Self> {
+        let mut kind = None;
+
+        for p in multiaddr.iter() {
+            match p {
+                Protocol::Memory(_) => return Some(Self::Memory),
+                Protocol::Ws(_) | Protocol::Wss(_) => return Some(Self::WebSocket),
+                Protocol::Tcp(_) => kind = Some(Self::Tcp),
+                _ => {}
+            }
+        }
+
+        kind
+    }
+}
+
+/// The capacity, in messages, and the overflow policy of a gossip queue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize)]
+pub struct GossipQueueConfig {
+    pub(crate) capacity: usize,
+    #[serde(rename = "overflow_policy")]
+    pub(crate) policy: OverflowPolicy,
+}
+
+impl GossipQueueConfig {
+    /// Creates a new [`GossipQueueConfig`].
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self { capacity, policy }
+    }
+
+    /// Returns the maximum number of queued messages.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns what happens to messages sent into the full queue.
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+}
+
+/// The configuration of the gossip queues of each peer.
+///
+/// The inbound queue holds the messages received from a peer until the protocol processes them, the outbound queue
+/// holds the messages for a peer until they are written to its stream.
+#[derive(Clone, Debug)]
+pub struct GossipConfig {
+    pub(crate) inbound: GossipQueueConfig,
+    pub(crate) outbound: GossipQueueConfig,
+    pub(crate) coalescing: CoalescingConfig,
+    pub(crate) versions: Vec<GossipVersion>,
+}
+
+impl GossipConfig {
+    /// Returns a [`GossipConfigBuilder`] to construct a [`GossipConfig`] iteratively.
+    pub fn build() -> GossipConfigBuilder {
+        GossipConfigBuilder::new()
+    }
+
+    /// Returns the configuration of the inbound queue.
+    pub fn inbound(&self) -> GossipQueueConfig {
+        self.inbound
+    }
+
+    /// Returns the configuration of the outbound queue.
+    pub fn outbound(&self) -> GossipQueueConfig {
+        self.outbound
+    }
+
+    /// Returns the write coalescing configuration of the gossip writer.
+    pub fn coalescing(&self) -> CoalescingConfig {
+        self.coalescing
+    }
+
+    /// Returns the supported gossip protocol versions, ordered from the highest to the lowest version.
+    pub fn versions(&self) -> &[GossipVersion] {
+        &self.versions
+    }
+}
+
+impl Default for GossipConfig {
+    fn default() -> Self {
+        Self {
+            // Blocking the reader lets the transport apply backpressure on the remote peer.
+            inbound: GossipQueueConfig::new(DEFAULT_GOSSIP_QUEUE_CAPACITY, OverflowPolicy::Block),
+            // A slow peer must not block the protocol, and fresh gossip is worth more than stale gossip.
+            outbound: GossipQueueConfig::new(DEFAULT_GOSSIP_QUEUE_CAPACITY, OverflowPolicy::DropOldest),
+            coalescing: CoalescingConfig::default(),
+            versions: vec![GossipVersion::V1],
+        }
+    }
+}
+
+/// A gossip queue configuration builder.
+#[derive(Default, Deserialize)]
+pub struct GossipConfigBuilder {
+    inbound: Option<GossipQueueConfig>,
+    outbound: Option<GossipQueueConfig>,
+    #[serde(default)]
+    coalescing: CoalescingConfigBuilder,
+    versions: Option<Vec<GossipVersion>>,
+}
+
+impl GossipConfigBuilder {
+    /// Creates a new default builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specifies the configuration of the inbound queue.
+    pub fn with_inbound(mut self, inbound: GossipQueueConfig) -> Self {
+        self.inbound.replace(inbound);
+        self
+    }
+
+    /// Specifies the configuration of the outbound queue.
+    pub fn with_outbound(mut self, outbound: GossipQueueConfig) -> Self {
+        self.outbound.replace(outbound);
+        self
+    }
+
+    /// Specifies the write coalescing configuration of the gossip writer.
+    pub fn with_coalescing(mut self, coalescing: CoalescingConfigBuilder) -> Self {
+        self.coalescing = coalescing;
+        self
+    }
+
+    /// Adds a supported gossip protocol version. The highest version supported by both sides is negotiated with each
+    /// peer.
+    pub fn with_version(mut self, version: GossipVersion) -> Self {
+        self.versions.get_or_insert_with(Vec::new).push(version);
+        self
+    }
+
+    /// Builds the gossip queue config.
+    pub fn finish(self) -> GossipConfig {
+        let default = GossipConfig::default();
+
+        let versions = match self.versions {
+            Some(mut versions) if !versions.is_empty() => {
+                versions.sort_unstable_by(|a, b| b.cmp(a));
+                versions.dedup();
+                versions
+            }
+            _ => default.versions,
+        };
+
+        GossipConfig {
+            inbound: self.inbound.unwrap_or(default.inbound),
+            outbound: self.outbound.unwrap_or(default.outbound),
+            coalescing: self.coalescing.finish(),
+            versions,
+        }
+    }
+}
+
+/// The write coalescing configuration of the gossip writer.
+///
+/// If enabled, the writer batches queued messages and flushes once the batch reaches `max_bytes` or the oldest
+/// unflushed message waited for `max_delay_millis`, instead of flushing every single message.
+#[derive(Clone, Copy, Debug)]
+pub struct CoalescingConfig {
+    pub(crate) enabled: bool,
+    pub(crate) max_bytes: usize,
+    pub(crate) max_delay_millis: u64,
+}
+
+impl CoalescingConfig {
+    /// Returns a [`CoalescingConfigBuilder`] to construct a [`CoalescingConfig`] iteratively.
+    pub fn build() -> CoalescingConfigBuilder {
+        CoalescingConfigBuilder::new()
+    }
+
+    /// Returns whether write coalescing is enabled.
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns the number of bytes after which a batch is flushed.
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Returns the number of milliseconds after which a batch is flushed.
+    pub fn max_delay_millis(&self) -> u64 {
+        self.max_delay_millis
+    }
+}
+
+impl Default for CoalescingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_bytes: DEFAULT_COALESCING_MAX_BYTES,
+            max_delay_millis: DEFAULT_COALESCING_MAX_DELAY_MILLIS,
+        }
+    }
+}
+
+/// A write coalescing configuration builder.
+#[derive(Default, Deserialize)]
+pub struct CoalescingConfigBuilder {
+    enabled: Option<bool>,
+    max_bytes: Option<usize>,
+    max_delay_millis: Option<u64>,
+}
+
+impl CoalescingConfigBuilder {
+    /// Creates a new default builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Specifies whether write coalescing is enabled.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled.replace(enabled);
+        self
+    }
+
+    /// Specifies the number of bytes after which a batch is flushed.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes.replace(max_bytes);
+        self
+    }
+
+    /// Specifies the number of milliseconds after which a batch is flushed.
+    pub fn with_max_delay_millis(mut self, max_delay_millis: u64) -> Self {
+        self.max_delay_millis.replace(max_delay_millis);
+        self
+    }
+
+    /// Builds the write coalescing config.
+    pub fn finish(self) -> CoalescingConfig {
+        CoalescingConfig {
+            enabled: self.enabled.unwrap_or(false),
+            max_bytes: self.max_bytes.unwrap_or(DEFAULT_COALESCING_MAX_BYTES),
+            max_delay_millis: self.max_delay_millis.unwrap_or(DEFAULT_COALESCING_MAX_DELAY_MILLIS),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -470,6 +1313,66 @@ mod tests {
             .finish();
     }
 
+    #[test]
+    fn create_with_builder_and_websocket_and_memory_bind_addresses() {
+        let config = NetworkConfig::build()
+            .with_bind_multiaddr("/dns/localhost/tcp/1337/ws".parse().unwrap())
+            .unwrap()
+            .with_extra_bind_multiaddr("/memory/1337".parse().unwrap())
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        // The domain is resolved, but the port and `/ws` are kept.
+        let protocols = config.bind_multiaddr().iter().skip(1).collect::<Vec<_>>();
+        assert_eq!(protocols, vec![Protocol::Tcp(1337), Protocol::Ws("/".into())]);
+        assert_eq!(
+            config.transports(),
+            &vec![TransportKind::WebSocket, TransportKind::Memory]
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn replace_port_keeps_websocket() {
+        let mut config = NetworkConfig::build()
+            .with_bind_multiaddr("/ip4/127.0.0.1/tcp/1337/ws".parse().unwrap())
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        config.replace_port(Protocol::Tcp(1338)).unwrap();
+
+        assert_eq!(
+            config.bind_multiaddr(),
+            &"/ip4/127.0.0.1/tcp/1338/ws".parse::<Multiaddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn create_discovery_config_with_entry_node() {
+        let config = DiscoveryConfig::build()
+            .with_enabled(true)
+            .with_entry_node("/ip4/127.0.0.1/tcp/15600/p2p/12D3KooWJWEKvSFbben74C7H4YtKjhPMTDxd7gP7zxWSUEeF27st")
+            .finish()
+            .unwrap();
+
+        assert!(config.enabled());
+        assert_eq!(config.entry_nodes().len(), 1);
+        assert_eq!(config.max_discovered_peers(), DEFAULT_MAX_DISCOVERED_PEERS);
+    }
+
+    #[test]
+    fn create_discovery_config_with_invalid_entry_node() {
+        assert!(matches!(
+            DiscoveryConfig::build()
+                .with_entry_node("/ip4/127.0.0.1/tcp/15600")
+                .finish(),
+            Err(Error::MissingP2pProtocol)
+        ));
+    }
+
     #[test]
     #[should_panic]
     fn create_with_mem_builder_and_non_mem_multiaddr() {
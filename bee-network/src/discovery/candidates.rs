@@ -0,0 +1,93 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reason::SelectionReason;
+
+use hashbrown::{HashMap, HashSet};
+use libp2p::{Multiaddr, PeerId};
+
+/// A peer that was found by the peer discovery and verified to speak the gossip protocol of the same network.
+#[derive(Clone, Debug)]
+pub struct Candidate {
+    pub peer_id: PeerId,
+    pub peer_addr: Multiaddr,
+    pub reason: SelectionReason,
+}
+
+/// Keeps track of the peers learned by the peer discovery until they are both routable and verified.
+#[derive(Debug, Default)]
+pub struct Candidates {
+    entry_nodes: HashSet<PeerId>,
+    addrs: HashMap<PeerId, Multiaddr>,
+    verified: HashSet<PeerId>,
+}
+
+impl Candidates {
+    pub fn new(entry_nodes: impl IntoIterator<Item = PeerId>) -> Self {
+        Self {
+            entry_nodes: entry_nodes.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Records a routable address of a peer and returns the resulting candidate if the peer was already verified.
+    pub fn insert_addr(&mut self, peer_id: PeerId, peer_addr: Multiaddr) -> Option<Candidate> {
+        self.addrs.insert(peer_id, peer_addr);
+        self.get(&peer_id)
+    }
+
+    /// Records the verification result of a peer and returns the resulting candidate if the peer is routable.
+    pub fn verify(&mut self, peer_id: PeerId, verified: bool) -> Option<Candidate> {
+        if verified {
+            self.verified.insert(peer_id);
+            self.get(&peer_id)
+        } else {
+            self.remove(&peer_id);
+            None
+        }
+    }
+
+    /// Returns the candidate of a peer that is both routable and verified.
+    pub fn get(&self, peer_id: &PeerId) -> Option<Candidate> {
+        if !self.verified.contains(peer_id) {
+            return None;
+        }
+
+        self.addrs.get(peer_id).map(|peer_addr| Candidate {
+            peer_id: *peer_id,
+            peer_addr: peer_addr.clone(),
+            reason: if self.entry_nodes.contains(peer_id) {
+                SelectionReason::EntryNode
+            } else {
+                SelectionReason::Query
+            },
+        })
+    }
+
+    pub fn remove(&mut self, peer_id: &PeerId) {
+        self.addrs.remove(peer_id);
+        self.verified.remove(peer_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::identity::ed25519::Keypair;
+
+    #[test]
+    fn candidate_requires_addr_and_verification() {
+        let peer_id = PeerId::from_public_key(libp2p_core::PublicKey::Ed25519(Keypair::generate().public()));
+        let peer_addr: Multiaddr = "/memory/1337".parse().unwrap();
+        let mut candidates = Candidates::new(vec![peer_id]);
+
+        assert!(candidates.verify(peer_id, true).is_none());
+
+        let candidate = candidates.insert_addr(peer_id, peer_addr).unwrap();
+        assert_eq!(candidate.peer_id, peer_id);
+        assert_eq!(candidate.reason, SelectionReason::EntryNode);
+
+        assert!(candidates.verify(peer_id, false).is_none());
+        assert!(candidates.get(&peer_id).is_none());
+    }
+}
@@ -0,0 +1,16 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "full")]
+
+pub mod candidates;
+pub mod reason;
+
+const IOTA_DISCOVERY_NAME: &str = "iota-discovery";
+const IOTA_DISCOVERY_VERSION: &str = "1.0.0";
+
+/// Returns the name of the Kademlia protocol used for peer discovery. Since it contains the network id, nodes of
+/// different networks don't end up in each other's routing tables.
+pub fn protocol_name(network_id: u64) -> String {
+    format!("/{}/{}/{}", IOTA_DISCOVERY_NAME, network_id, IOTA_DISCOVERY_VERSION)
+}
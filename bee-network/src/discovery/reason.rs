@@ -0,0 +1,40 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+
+/// Describes why a peer was selected by the peer discovery.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SelectionReason {
+    /// The peer is one of the configured entry nodes and also speaks the gossip protocol.
+    EntryNode,
+    /// The peer was returned by a discovery query and verified.
+    Query,
+}
+
+impl fmt::Display for SelectionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SelectionReason::EntryNode => f.write_str("entry node"),
+            SelectionReason::Query => f.write_str("query"),
+        }
+    }
+}
+
+/// Describes why a peer selected by the peer discovery was dropped.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DropReason {
+    /// The gossip connection with the peer was closed.
+    Disconnected,
+    /// The peer could not be dialed.
+    Unreachable,
+}
+
+impl fmt::Display for DropReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DropReason::Disconnected => f.write_str("disconnected"),
+            DropReason::Unreachable => f.write_str("unreachable"),
+        }
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "full")]
+
+use libp2p::identity::ed25519::{Keypair, SecretKey};
+use log::info;
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+const PEM_TAG: &str = "PRIVATE KEY";
+
+/// The DER prefix of a PKCS#8 encoded ed25519 private key (RFC 8410), followed by the 32 bytes of the secret key.
+const PKCS8_ED25519_PREFIX: [u8; 16] = [
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20,
+];
+const SECRET_KEY_LENGTH: usize = 32;
+const KEYPAIR_LENGTH: usize = 64;
+
+/// [`IdentityFile`] errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Reading or writing the identity file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The identity file is not a valid PEM file.
+    #[error("Invalid PEM file: {0}")]
+    InvalidPem(#[from] pem::PemError),
+
+    /// The identity file doesn't contain a PKCS#8 encoded ed25519 private key.
+    #[error("Invalid private key.")]
+    InvalidKey,
+
+    /// The given string is not a hex encoded secret key or keypair.
+    #[error("Invalid hex encoded key: {0}")]
+    InvalidHex(String),
+
+    /// The identity file already exists and is never overwritten.
+    #[error("Identity file already exists: {0}")]
+    AlreadyExists(PathBuf),
+
+    /// The identity file can be read by anyone.
+    #[error("Identity file is world-readable: {0}")]
+    WorldReadable(PathBuf),
+}
+
+/// Stores the ed25519 keypair, and hence the `PeerId`, of the local node in a PEM file.
+///
+/// The file is created with permissions that only allow the owner to read and write it, and files that are readable
+/// by anyone are refused.
+#[derive(Clone, Debug)]
+pub struct IdentityFile {
+    path: PathBuf,
+}
+
+impl IdentityFile {
+    /// Creates a new identity file handle. Nothing is read or written until one of the methods is called.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the identity file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the keypair from the identity file, or generates and stores a new one if the file doesn't exist yet.
+    pub fn load_or_generate(&self) -> Result<Keypair, Error> {
+        if self.path.exists() {
+            return self.load();
+        }
+
+        let keypair = Keypair::generate();
+        self.store(&keypair)?;
+
+        info!("Generated a new identity in {}.", self.path.display());
+
+        Ok(keypair)
+    }
+
+    /// Loads the keypair from the identity file.
+    pub fn load(&self) -> Result<Keypair, Error> {
+        check_permissions(&self.path)?;
+
+        let pem = pem::parse(fs::read(&self.path)?)?;
+
+        if pem.tag != PEM_TAG
+            || pem.contents.len() != PKCS8_ED25519_PREFIX.len() + SECRET_KEY_LENGTH
+            || pem.contents[..PKCS8_ED25519_PREFIX.len()] != PKCS8_ED25519_PREFIX
+        {
+            return Err(Error::InvalidKey);
+        }
+
+        let mut secret = pem.contents[PKCS8_ED25519_PREFIX.len()..].to_vec();
+        let secret = SecretKey::from_bytes(&mut secret).map_err(|_| Error::InvalidKey)?;
+
+        Ok(secret.into())
+    }
+
+    /// Stores the keypair in the identity file. An existing identity file is never overwritten.
+    pub fn store(&self, keypair: &Keypair) -> Result<(), Error> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut contents = PKCS8_ED25519_PREFIX.to_vec();
+        contents.extend_from_slice(keypair.secret().as_ref());
+
+        let pem = pem::encode(&pem::Pem {
+            tag: PEM_TAG.to_string(),
+            contents,
+        });
+
+        let mut file = open_new(&self.path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => Error::AlreadyExists(self.path.clone()),
+            _ => Error::Io(e),
+        })?;
+        file.write_all(pem.as_bytes())?;
+        file.sync_all()?;
+
+        Ok(())
+    }
+
+    /// Imports a hex encoded secret key (32 bytes) or keypair (64 bytes) and stores it in the identity file.
+    pub fn import_hex(&self, key: &str) -> Result<Keypair, Error> {
+        let keypair = keypair_from_hex(key)?;
+        self.store(&keypair)?;
+
+        Ok(keypair)
+    }
+}
+
+/// Decodes a hex encoded secret key (32 bytes) or keypair (64 bytes).
+///
+/// The keypair is always derived from the secret key, the public key of a keypair has to match it.
+pub fn keypair_from_hex(key: &str) -> Result<Keypair, Error> {
+    let mut bytes = hex::decode(key.trim()).map_err(|e| Error::InvalidHex(e.to_string()))?;
+
+    let public = match bytes.len() {
+        SECRET_KEY_LENGTH => None,
+        KEYPAIR_LENGTH => Some(bytes.split_off(SECRET_KEY_LENGTH)),
+        len => {
+            return Err(Error::InvalidHex(format!(
+                "expected {} or {} bytes, got {}",
+                SECRET_KEY_LENGTH, KEYPAIR_LENGTH, len
+            )));
+        }
+    };
+
+    let keypair: Keypair = SecretKey::from_bytes(&mut bytes)
+        .map_err(|e| Error::InvalidHex(e.to_string()))?
+        .into();
+
+    match public {
+        Some(public) if public[..] != keypair.public().encode()[..] => Err(Error::InvalidHex(
+            "the public key doesn't match the secret key".to_string(),
+        )),
+        _ => Ok(keypair),
+    }
+}
+
+#[cfg(unix)]
+fn open_new(path: &Path) -> std::io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn open_new(path: &Path) -> std::io::Result<fs::File> {
+    fs::OpenOptions::new().write(true).create_new(true).open(path)
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<(), Error> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if fs::metadata(path)?.permissions().mode() & 0o004 != 0 {
+        return Err(Error::WorldReadable(path.to_path_buf()));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_: &Path) -> Result<(), Error> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("identity_{}_{}.pem", name, std::process::id()))
+    }
+
+    #[test]
+    fn generate_then_load() {
+        let path = temp_path("generate");
+        let _ = fs::remove_file(&path);
+        let identity = IdentityFile::new(&path);
+
+        let generated = identity.load_or_generate().unwrap();
+        let loaded = identity.load_or_generate().unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(generated.encode().to_vec(), loaded.encode().to_vec());
+    }
+
+    #[test]
+    fn import_hex_and_never_overwrite() {
+        let path = temp_path("import");
+        let _ = fs::remove_file(&path);
+        let identity = IdentityFile::new(&path);
+
+        let imported = identity
+            .import_hex("41dbc921b157fe001bcaf7f1f8b97f6eddf8f29e8888afc2ff089d544b9baf45")
+            .unwrap();
+        let overwritten = identity.import_hex("41dbc921b157fe001bcaf7f1f8b97f6eddf8f29e8888afc2ff089d544b9baf46");
+        let loaded = identity.load().unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert!(matches!(overwritten, Err(Error::AlreadyExists(_))));
+        assert_eq!(imported.public().encode(), loaded.public().encode());
+    }
+
+    #[test]
+    fn import_keypair_hex() {
+        let path = temp_path("import_keypair");
+        let _ = fs::remove_file(&path);
+        let identity = IdentityFile::new(&path);
+        let keypair = Keypair::generate();
+
+        let imported = identity.import_hex(&hex::encode(keypair.encode())).unwrap();
+        let loaded = identity.load().unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(imported.encode().to_vec(), keypair.encode().to_vec());
+        assert_eq!(loaded.encode().to_vec(), keypair.encode().to_vec());
+    }
+
+    #[test]
+    fn mismatching_public_key() {
+        let mut key = Keypair::generate().encode().to_vec();
+        key[SECRET_KEY_LENGTH..].copy_from_slice(&Keypair::generate().public().encode());
+
+        assert!(matches!(keypair_from_hex(&hex::encode(key)), Err(Error::InvalidHex(_))));
+    }
+
+    #[test]
+    fn invalid_hex() {
+        assert!(matches!(keypair_from_hex("41db"), Err(Error::InvalidHex(_))));
+        assert!(matches!(keypair_from_hex("not hex"), Err(Error::InvalidHex(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuse_world_readable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_path("readable");
+        let _ = fs::remove_file(&path);
+        let identity = IdentityFile::new(&path);
+
+        identity.load_or_generate().unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        let loaded = identity.load();
+        let _ = fs::remove_file(&path);
+
+        assert!(matches!(loaded, Err(Error::WorldReadable(_))));
+    }
+}
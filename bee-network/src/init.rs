@@ -7,25 +7,24 @@ use super::{
     config::NetworkConfig,
     error::Error,
     peer::{
-        info::{PeerInfo, PeerRelation},
         list::{PeerList, PeerListWrapper},
+        store::{PeerStore, PeerStoreWrapper},
     },
     service::{
-        command::{command_channel, NetworkCommandSender},
+        command::{command_channel, Command, NetworkCommandSender},
         event::{event_channel, Event, InternalEvent, NetworkEventReceiver},
     },
     Keypair, PeerId,
 };
 
 use crate::{
-    alias,
     network::host::NetworkHostConfig,
     service::host::{self, ServiceHostConfig},
-    swarm::builder::build_swarm,
+    swarm::{builder::build_swarm, protocols::iota_gossip::RateLimiter},
 };
 
 use libp2p::identity;
-use log::info;
+use log::{info, warn};
 use once_cell::sync::OnceCell;
 
 pub mod global {
@@ -34,6 +33,7 @@ pub mod global {
     static RECONNECT_INTERVAL_SECS: OnceCell<u64> = OnceCell::new();
     static NETWORK_ID: OnceCell<u64> = OnceCell::new();
     static MAX_UNKNOWN_PEERS: OnceCell<usize> = OnceCell::new();
+    static MAX_DISCOVERED_PEERS: OnceCell<usize> = OnceCell::new();
 
     pub fn set_reconnect_interval_secs(reconnect_interval_secs: u64) {
         if cfg!(test) {
@@ -69,6 +69,17 @@ pub mod global {
     pub fn max_unknown_peers() -> usize {
         *MAX_UNKNOWN_PEERS.get().expect("oncecell get")
     }
+
+    pub fn set_max_discovered_peers(max_discovered_peers: usize) {
+        if cfg!(test) {
+            let _ = MAX_DISCOVERED_PEERS.set(max_discovered_peers);
+        } else {
+            MAX_DISCOVERED_PEERS.set(max_discovered_peers).expect("oncecell set");
+        }
+    }
+    pub fn max_discovered_peers() -> usize {
+        *MAX_DISCOVERED_PEERS.get().expect("oncecell get")
+    }
 }
 
 /// Initializes a "standalone" version of the network layer.
@@ -93,15 +104,19 @@ pub mod standalone {
         let (shutdown_signal_tx1, shutdown_signal_rx1) = oneshot::channel::<()>();
         let (shutdown_signal_tx2, shutdown_signal_rx2) = oneshot::channel::<()>();
 
+        ServiceHost::new(shutdown_signal_rx1).start(service_config).await;
+        let network_host = NetworkHost::new(shutdown_signal_rx2).start(network_config).await;
+
         tokio::spawn(async move {
             shutdown.await;
 
-            shutdown_signal_tx1.send(()).expect("sending shutdown signal");
+            // The network host is stopped first, so the gossip tasks of its closed connections can still report to the
+            // service, and the event channel is only closed once the listeners are released.
             shutdown_signal_tx2.send(()).expect("sending shutdown signal");
-        });
+            let _ = network_host.await;
 
-        ServiceHost::new(shutdown_signal_rx1).start(service_config).await;
-        NetworkHost::new(shutdown_signal_rx2).start(network_config).await;
+            shutdown_signal_tx1.send(()).expect("sending shutdown signal");
+        });
 
         Ok((network_command_sender, network_event_receiver))
     }
@@ -148,14 +163,22 @@ fn init(
 > {
     let NetworkConfig {
         bind_multiaddr,
+        extra_bind_multiaddrs,
+        transports,
         reconnect_interval_secs,
         max_unknown_peers,
         static_peers: peers,
+        discovery,
+        peer_store_path,
+        bandwidth,
+        gossip,
+        connection_gate,
     } = config;
 
     global::set_reconnect_interval_secs(reconnect_interval_secs);
     global::set_network_id(network_id);
     global::set_max_unknown_peers(max_unknown_peers);
+    global::set_max_discovered_peers(discovery.max_discovered_peers);
 
     let (command_sender, command_receiver) = command_channel();
     let (internal_command_sender, internal_command_receiver) = command_channel();
@@ -172,24 +195,82 @@ fn init(
         .send(Event::LocalIdCreated { local_id })
         .map_err(|_| Error::LocalIdAnnouncementFailed)?;
 
-    let peerlist = PeerListWrapper::new(PeerList::from_peers(local_id, peers.iter().cloned().collect()));
+    let mut peerlist = PeerList::from_peers(local_id, peers.iter().cloned().collect());
+    peerlist.set_connection_gate(connection_gate);
 
     for peer in peers.into_iter() {
         let peer_id = peer.peer_id;
+        // Announce the info stored in the peerlist, so that its bandwidth counters are shared with the event consumers.
+        // Panic:
+        // The peerlist was just created from the static peers, hence unwrapping is fine.
+        let info = peerlist.info(&peer_id).unwrap();
+
         event_sender
-            .send(Event::PeerAdded {
-                peer_id,
-                info: PeerInfo {
-                    address: peer.multiaddr,
-                    alias: peer.alias.unwrap_or_else(|| alias!(peer_id).into()),
-                    relation: PeerRelation::Known,
-                },
-            })
+            .send(Event::PeerAdded { peer_id, info })
             .map_err(|_| Error::StaticPeersAnnouncementFailed)?;
     }
 
+    let peer_store = if let Some(path) = peer_store_path {
+        let mut peer_store = PeerStore::new(path);
+
+        match peer_store.load() {
+            Ok(loaded) => {
+                info!(
+                    "Loaded {} peers and {} bans from the peer store.",
+                    loaded.peers.len(),
+                    loaded.banned_peers.len() + loaded.banned_addrs.len()
+                );
+
+                for (peer_id, expiry) in loaded.banned_peers {
+                    let _ = peerlist.ban_peer(peer_id, expiry);
+                }
+                for (address, expiry) in loaded.banned_addrs {
+                    let _ = peerlist.ban_address(address, expiry);
+                }
+
+                for (peer_id, info) in loaded.peers {
+                    // Static peers take precedence over persisted ones, and discovered peers must still fit the quota.
+                    if info.relation.is_discovered()
+                        && peerlist.accepts_discovered_peer(&peer_id, &info.address).is_err()
+                    {
+                        continue;
+                    }
+
+                    if peerlist.insert_peer(peer_id, info.clone()).is_ok() {
+                        // Known peers are reconnected by the peer state checker, discovered peers are dialed once.
+                        if info.relation.is_discovered() {
+                            let _ = internal_command_sender.send(Command::DialPeer { peer_id });
+                        }
+
+                        event_sender
+                            .send(Event::PeerAdded { peer_id, info })
+                            .map_err(|_| Error::StaticPeersAnnouncementFailed)?;
+                    }
+                }
+            }
+            Err(e) => warn!(
+                "Loading the peer store from {} failed. Cause: {}",
+                peer_store.path().display(),
+                e
+            ),
+        }
+
+        Some(PeerStoreWrapper::new(peer_store))
+    } else {
+        None
+    };
+
+    let peerlist = PeerListWrapper::new(peerlist);
+
     // Create the transport layer
-    let swarm = build_swarm(&local_keys, internal_event_sender.clone()).map_err(|_| Error::CreatingTransportFailed)?;
+    let swarm = build_swarm(
+        &local_keys,
+        internal_event_sender.clone(),
+        &discovery,
+        &transports,
+        gossip.versions(),
+    )
+    .map_err(|_| Error::CreatingTransportFailed)?;
 
     let network_host_config = NetworkHostConfig {
         internal_event_sender: internal_event_sender.clone(),
@@ -197,6 +278,8 @@ fn init(
         peerlist: peerlist.clone(),
         swarm,
         bind_multiaddr,
+        extra_bind_multiaddrs,
+        discovery_interval_secs: discovery.interval_secs,
     };
 
     let service_host_config = ServiceHostConfig {
@@ -211,6 +294,9 @@ fn init(
             internal_events: internal_event_receiver,
         },
         peerlist,
+        peer_store,
+        rate_limiter: RateLimiter::new(bandwidth),
+        gossip,
     };
 
     let network_command_sender = NetworkCommandSender::new(command_sender);
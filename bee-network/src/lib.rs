@@ -7,7 +7,9 @@
 
 mod alias;
 mod config;
+mod discovery;
 mod error;
+mod identity;
 mod init;
 mod network;
 mod peer;
@@ -18,7 +20,11 @@ mod swarm;
 mod tests;
 
 // Always exported
-pub use self::peer::info::{PeerInfo, PeerRelation};
+pub use self::peer::{
+    bandwidth::{Bandwidth, BandwidthStats},
+    capabilities::{GossipVersion, ParseGossipVersionError, PeerCapabilities},
+    info::{PeerInfo, PeerRelation},
+};
 #[doc(inline)]
 pub use libp2p_core::{
     multiaddr::{Multiaddr, Protocol},
@@ -32,8 +38,15 @@ pub use libp2p::core::identity::{ed25519::Keypair, PublicKey};
 
 #[cfg(feature = "full")]
 pub use crate::{
-    config::{NetworkConfig, NetworkConfigBuilder},
+    config::{
+        BandwidthConfig, BandwidthConfigBuilder, CoalescingConfig, CoalescingConfigBuilder, ConnectionGateConfig,
+        ConnectionGateConfigBuilder, DiscoveryConfig, DiscoveryConfigBuilder, GossipConfig, GossipConfigBuilder,
+        GossipQueueConfig, InMemoryNetworkConfigBuilder, NetworkConfig, NetworkConfigBuilder, Peer, PeeringConfig,
+        PeeringConfigBuilder, TrafficLimits, TransportKind,
+    },
+    discovery::reason::{DropReason, SelectionReason},
     error::Error,
+    identity::{keypair_from_hex, Error as IdentityError, IdentityFile},
     init::{integrated, standalone},
     network::host::integrated::NetworkHost,
     network::origin::Origin,
@@ -42,5 +55,14 @@ pub use crate::{
         event::{Event, NetworkEventReceiver},
         host::integrated::ServiceHost,
     },
-    swarm::protocols::iota_gossip::{GossipReceiver, GossipSender},
+    swarm::protocols::iota_gossip::{
+        GossipControl, GossipError, GossipItem, GossipReceiver, GossipSender, OverflowPolicy,
+    },
 };
+
+/// Internals that are only exported to benchmark the gossip I/O.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::swarm::protocols::iota_gossip::{channel, write_gossip, Limits, WriteEnd};
+}
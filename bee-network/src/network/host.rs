@@ -13,9 +13,11 @@ use crate::{
     swarm::behavior::SwarmBehavior,
 };
 
-use futures::channel::oneshot;
+use futures::{channel::oneshot, StreamExt};
 use libp2p::{swarm::SwarmEvent, Multiaddr, PeerId, Swarm};
 use log::*;
+use tokio::time::{self, Duration};
+use tokio_stream::wrappers::IntervalStream;
 
 pub struct NetworkHostConfig {
     pub internal_event_sender: InternalEventSender,
@@ -23,6 +25,8 @@ pub struct NetworkHostConfig {
     pub peerlist: PeerList,
     pub swarm: Swarm<SwarmBehavior>,
     pub bind_multiaddr: Multiaddr,
+    pub extra_bind_multiaddrs: Vec<Multiaddr>,
+    pub discovery_interval_secs: u64,
 }
 
 pub mod integrated {
@@ -69,6 +73,8 @@ pub mod integrated {
 pub mod standalone {
     use super::*;
 
+    use tokio::task::JoinHandle;
+
     pub struct NetworkHost {
         pub shutdown: oneshot::Receiver<()>,
     }
@@ -78,10 +84,11 @@ pub mod standalone {
             Self { shutdown }
         }
 
-        pub async fn start(self, config: NetworkHostConfig) {
+        /// Starts the network host. The returned handle completes once it stopped, and released its listeners.
+        pub async fn start(self, config: NetworkHostConfig) -> JoinHandle<()> {
             let NetworkHost { shutdown } = self;
 
-            tokio::spawn(async move {
+            let handle = tokio::spawn(async move {
                 network_host_processor(config, shutdown)
                     .await
                     .expect("network host processor");
@@ -90,6 +97,8 @@ pub mod standalone {
             });
 
             info!("Network Host started.");
+
+            handle
         }
     }
 }
@@ -104,11 +113,21 @@ async fn network_host_processor(
         peerlist,
         mut swarm,
         bind_multiaddr,
+        extra_bind_multiaddrs,
+        discovery_interval_secs,
     } = config;
 
-    // Try binding to the configured bind address.
-    info!("Binding to: {}", bind_multiaddr);
-    let _listener_id = Swarm::listen_on(&mut swarm, bind_multiaddr).map_err(|_| crate::Error::BindingAddressFailed)?;
+    // Try binding to the configured bind addresses.
+    for bind_multiaddr in std::iter::once(bind_multiaddr).chain(extra_bind_multiaddrs) {
+        info!("Binding to: {}", bind_multiaddr);
+        let _listener_id =
+            Swarm::listen_on(&mut swarm, bind_multiaddr).map_err(|_| crate::Error::BindingAddressFailed)?;
+    }
+
+    // Join the discovery network, if peer discovery is enabled. Otherwise this is a no-op.
+    swarm.behaviour_mut().bootstrap();
+
+    let mut discovery_interval = IntervalStream::new(time::interval(Duration::from_secs(discovery_interval_secs)));
 
     loop {
         let swarm_next_event = Swarm::next_event(&mut swarm);
@@ -116,6 +135,9 @@ async fn network_host_processor(
 
         tokio::select! {
             _ = &mut shutdown => break,
+            _ = discovery_interval.next() => {
+                swarm.behaviour_mut().discover();
+            }
             event = swarm_next_event => {
                 process_swarm_event(event, &internal_event_sender, &peerlist).await;
             }
@@ -168,6 +190,20 @@ async fn process_swarm_event(
         SwarmEvent::IncomingConnection { send_back_addr, .. } => {
             debug!("Swarm event: being dialed from {}.", send_back_addr);
         }
+        SwarmEvent::UnreachableAddr {
+            peer_id,
+            address,
+            attempts_remaining,
+            ..
+        } => {
+            debug!("Swarm event: unreachable address {} of {}.", address, alias!(peer_id));
+
+            if attempts_remaining == 0 {
+                internal_event_sender
+                    .send(InternalEvent::PeerUnreachable { peer_id })
+                    .expect("send error");
+            }
+        }
         _ => {}
     }
 }
@@ -0,0 +1,110 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// A snapshot of the gossip traffic exchanged with a peer.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BandwidthStats {
+    /// The number of bytes received from the peer.
+    pub bytes_received: u64,
+    /// The number of bytes sent to the peer.
+    pub bytes_sent: u64,
+    /// The number of chunks read from the gossip stream of the peer.
+    pub frames_received: u64,
+    /// The number of messages written to the gossip stream of the peer.
+    pub frames_sent: u64,
+    /// The number of chunks received from the peer that were dropped because the inbound gossip queue was full.
+    pub frames_dropped_inbound: u64,
+    /// The number of messages for the peer that were dropped because the outbound gossip queue was full.
+    pub frames_dropped_outbound: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    frames_received: AtomicU64,
+    frames_sent: AtomicU64,
+    frames_dropped_inbound: AtomicU64,
+    frames_dropped_outbound: AtomicU64,
+}
+
+/// Live counters of the gossip traffic exchanged with a peer.
+///
+/// Clones share the same counters, so every copy of a [`PeerInfo`](crate::PeerInfo) observes the traffic as it
+/// happens.
+#[derive(Clone, Debug, Default)]
+pub struct Bandwidth(Arc<Counters>);
+
+impl Bandwidth {
+    /// Creates new zeroed counters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a frame of `bytes` received from the peer.
+    pub fn record_received(&self, bytes: usize) {
+        self.0.bytes_received.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.0.frames_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a frame of `bytes` sent to the peer.
+    pub fn record_sent(&self, bytes: usize) {
+        self.0.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+        self.0.frames_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a frame received from the peer that was dropped due to a full inbound gossip queue.
+    pub fn record_dropped_inbound(&self) {
+        self.0.frames_dropped_inbound.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a frame for the peer that was dropped due to a full outbound gossip queue.
+    pub fn record_dropped_outbound(&self) {
+        self.0.frames_dropped_outbound.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the counters.
+    pub fn stats(&self) -> BandwidthStats {
+        BandwidthStats {
+            bytes_received: self.0.bytes_received.load(Ordering::Relaxed),
+            bytes_sent: self.0.bytes_sent.load(Ordering::Relaxed),
+            frames_received: self.0.frames_received.load(Ordering::Relaxed),
+            frames_sent: self.0.frames_sent.load(Ordering::Relaxed),
+            frames_dropped_inbound: self.0.frames_dropped_inbound.load(Ordering::Relaxed),
+            frames_dropped_outbound: self.0.frames_dropped_outbound.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_counters() {
+        let bandwidth = Bandwidth::new();
+        let clone = bandwidth.clone();
+
+        bandwidth.record_received(100);
+        clone.record_sent(40);
+        clone.record_sent(2);
+        clone.record_dropped_outbound();
+
+        assert_eq!(
+            bandwidth.stats(),
+            BandwidthStats {
+                bytes_received: 100,
+                bytes_sent: 42,
+                frames_received: 1,
+                frames_sent: 2,
+                frames_dropped_inbound: 0,
+                frames_dropped_outbound: 1,
+            }
+        );
+    }
+}
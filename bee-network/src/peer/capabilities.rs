@@ -0,0 +1,107 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{fmt, str::FromStr};
+
+/// A version of the gossip protocol, e.g. `1.0.0`.
+///
+/// Nodes advertise all the versions they support, and the highest version supported by both sides is negotiated, so
+/// packet formats can evolve without all nodes having to upgrade at the same time.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct GossipVersion {
+    /// Incremented for incompatible changes.
+    pub major: u16,
+    /// Incremented for backwards compatible additions.
+    pub minor: u16,
+    /// Incremented for backwards compatible fixes.
+    pub patch: u16,
+}
+
+impl GossipVersion {
+    /// The first version of the gossip protocol.
+    pub const V1: Self = Self::new(1, 0, 0);
+
+    /// Creates a new [`GossipVersion`].
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self { major, minor, patch }
+    }
+}
+
+impl Default for GossipVersion {
+    fn default() -> Self {
+        Self::V1
+    }
+}
+
+impl fmt::Display for GossipVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Error returned when parsing a [`GossipVersion`] fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseGossipVersionError(String);
+
+impl fmt::Display for ParseGossipVersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid gossip version '{}', expected '<major>.<minor>.<patch>'",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseGossipVersionError {}
+
+impl FromStr for GossipVersion {
+    type Err = ParseGossipVersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.').map(str::parse::<u16>);
+
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => Ok(Self::new(major, minor, patch)),
+            _ => Err(ParseGossipVersionError(s.to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+impl<'de> serde::Deserialize<'de> for GossipVersion {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// What a peer reported about itself, and the gossip protocol version negotiated with it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PeerCapabilities {
+    /// The peer's agent version, i.e. the name and version of its node software, once it identified itself.
+    pub agent_version: Option<String>,
+    /// The protocols supported by the peer, once it identified itself.
+    pub protocols: Vec<String>,
+    /// The gossip protocol version negotiated with the peer, once connected.
+    pub gossip_version: Option<GossipVersion>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        let version = "1.2.3".parse::<GossipVersion>().unwrap();
+
+        assert_eq!(version, GossipVersion::new(1, 2, 3));
+        assert_eq!(version.to_string(), "1.2.3");
+        assert!(version > GossipVersion::V1);
+
+        assert!("1.2".parse::<GossipVersion>().is_err());
+        assert!("1.2.3.4".parse::<GossipVersion>().is_err());
+        assert!("1.x.3".parse::<GossipVersion>().is_err());
+    }
+}
@@ -5,6 +5,8 @@
 
 use libp2p::{Multiaddr, PeerId};
 
+use std::net::IpAddr;
+
 /// [`PeerList`] errors.
 #[derive(Clone, Debug, thiserror::Error)]
 pub enum Error {
@@ -55,4 +57,20 @@ pub enum Error {
     /// A failure due to hitting the maximum number of allowed unknown peers.
     #[error("Tried to add more unknown peers than defined in the config ({0}).")]
     ExceedsUnknownPeerLimit(usize),
+
+    /// A failure due to an address being denied by the connection gate.
+    #[error("Address is denied by the connection gate: {0}")]
+    AddressIsDenied(Multiaddr),
+
+    /// A failure due to the transport of an address being denied by the connection gate.
+    #[error("Transport is denied by the connection gate: {0}")]
+    TransportIsDenied(Multiaddr),
+
+    /// A failure due to hitting the maximum number of allowed connections per IP address.
+    #[error("Tried to exceed the connection limit per IP address ({1}): {0}")]
+    ExceedsConnectionsPerIpLimit(IpAddr, usize),
+
+    /// A failure due to hitting the maximum number of allowed discovered peers.
+    #[error("Tried to add more discovered peers than defined in the config ({0}).")]
+    ExceedsDiscoveredPeerLimit(usize),
 }
@@ -0,0 +1,161 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "full")]
+
+use super::error::Error;
+
+use crate::config::{ConnectionGateConfig, TransportKind};
+
+use libp2p::{multiaddr::Protocol, Multiaddr};
+
+use std::net::IpAddr;
+
+/// Decides, based on IP networks and transports, which addresses the node accepts connections from and dials.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionGate {
+    config: ConnectionGateConfig,
+}
+
+impl ConnectionGate {
+    pub fn new(config: ConnectionGateConfig) -> Self {
+        Self { config }
+    }
+
+    /// Checks whether the IP network and the transport of an address are allowed.
+    pub fn check_addr(&self, addr: &Multiaddr) -> Result<(), Error> {
+        let ConnectionGateConfig {
+            allow_networks,
+            deny_networks,
+            allow_transports,
+            deny_transports,
+            ..
+        } = &self.config;
+
+        let transport = TransportKind::of(addr);
+
+        if transport.map_or(false, |t| deny_transports.contains(&t))
+            || (!allow_transports.is_empty() && !transport.map_or(false, |t| allow_transports.contains(&t)))
+        {
+            return Err(Error::TransportIsDenied(addr.clone()));
+        }
+
+        match ip_of(addr) {
+            Some(ip) => {
+                if deny_networks.iter().any(|net| net.contains(&ip))
+                    || (!allow_networks.is_empty() && !allow_networks.iter().any(|net| net.contains(&ip)))
+                {
+                    return Err(Error::AddressIsDenied(addr.clone()));
+                }
+            }
+            // Addresses without IP, e.g. domain names, can't be verified to be part of an allowed network.
+            None => {
+                if !allow_networks.is_empty() {
+                    return Err(Error::AddressIsDenied(addr.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether another connection with the IP address of `addr` is allowed, given the number of existing
+    /// connections with that IP address.
+    pub fn check_connections_per_ip(&self, addr: &Multiaddr, connections: usize) -> Result<(), Error> {
+        match (ip_of(addr), self.config.max_connections_per_ip) {
+            (Some(ip), Some(max)) if connections >= max => Err(Error::ExceedsConnectionsPerIpLimit(ip, max)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns the IP address of a `Multiaddr`, if it has one.
+pub fn ip_of(addr: &Multiaddr) -> Option<IpAddr> {
+    addr.iter().find_map(|p| match p {
+        Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+        Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_gate(builder: crate::config::ConnectionGateConfigBuilder) -> ConnectionGate {
+        ConnectionGate::new(builder.finish().unwrap())
+    }
+
+    #[test]
+    fn default_allows_everything() {
+        let gate = ConnectionGate::default();
+
+        assert!(gate.check_addr(&"/ip4/10.0.0.1/tcp/15600".parse().unwrap()).is_ok());
+        assert!(gate.check_addr(&"/memory/1337".parse().unwrap()).is_ok());
+        assert!(gate
+            .check_connections_per_ip(&"/ip4/10.0.0.1/tcp/15600".parse().unwrap(), 100)
+            .is_ok());
+    }
+
+    #[test]
+    fn allow_and_deny_networks() {
+        let gate = build_gate(
+            ConnectionGateConfig::build()
+                .with_allowed_network("10.0.0.0/8")
+                .with_denied_network("10.1.0.0/16"),
+        );
+
+        assert!(gate.check_addr(&"/ip4/10.0.0.1/tcp/15600".parse().unwrap()).is_ok());
+        assert!(matches!(
+            gate.check_addr(&"/ip4/10.1.0.1/tcp/15600".parse().unwrap()),
+            Err(Error::AddressIsDenied(_))
+        ));
+        assert!(matches!(
+            gate.check_addr(&"/ip4/192.168.0.1/tcp/15600".parse().unwrap()),
+            Err(Error::AddressIsDenied(_))
+        ));
+        assert!(matches!(
+            gate.check_addr(&"/dns/localhost/tcp/15600".parse().unwrap()),
+            Err(Error::AddressIsDenied(_))
+        ));
+    }
+
+    #[test]
+    fn deny_bogons() {
+        let gate = build_gate(ConnectionGateConfig::build().with_deny_bogons(true));
+
+        assert!(gate.check_addr(&"/ip4/192.168.0.1/tcp/15600".parse().unwrap()).is_err());
+        assert!(gate.check_addr(&"/ip6/::1/tcp/15600".parse().unwrap()).is_err());
+        assert!(gate.check_addr(&"/ip4/1.1.1.1/tcp/15600".parse().unwrap()).is_ok());
+    }
+
+    #[test]
+    fn allow_and_deny_transports() {
+        let gate = build_gate(ConnectionGateConfig::build().with_allowed_transport(TransportKind::Tcp));
+
+        assert!(gate.check_addr(&"/ip4/1.1.1.1/tcp/15600".parse().unwrap()).is_ok());
+        assert!(matches!(
+            gate.check_addr(&"/ip4/1.1.1.1/tcp/15600/ws".parse().unwrap()),
+            Err(Error::TransportIsDenied(_))
+        ));
+
+        let gate = build_gate(ConnectionGateConfig::build().with_denied_transport(TransportKind::Memory));
+
+        assert!(matches!(
+            gate.check_addr(&"/memory/1337".parse().unwrap()),
+            Err(Error::TransportIsDenied(_))
+        ));
+    }
+
+    #[test]
+    fn connections_per_ip() {
+        let gate = build_gate(ConnectionGateConfig::build().with_max_connections_per_ip(2));
+        let addr = "/ip4/1.1.1.1/tcp/15600".parse().unwrap();
+
+        assert!(gate.check_connections_per_ip(&addr, 1).is_ok());
+        assert!(matches!(
+            gate.check_connections_per_ip(&addr, 2),
+            Err(Error::ExceedsConnectionsPerIpLimit(_, 2))
+        ));
+    }
+}
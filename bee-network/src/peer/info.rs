@@ -1,10 +1,15 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use super::{bandwidth::Bandwidth, capabilities::PeerCapabilities};
+
 use libp2p_core::Multiaddr;
 
 /// Additional information about a peer.
+///
+/// More information may be added in the future, hence it is created with [`PeerInfo::new`].
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct PeerInfo {
     /// The peer's address.
     pub address: Multiaddr,
@@ -12,6 +17,23 @@ pub struct PeerInfo {
     pub alias: String,
     /// The type of relation regarding this peer.
     pub relation: PeerRelation,
+    /// The peer's gossip traffic counters.
+    pub bandwidth: Bandwidth,
+    /// What the peer reported about itself, and the negotiated gossip protocol version.
+    pub capabilities: PeerCapabilities,
+}
+
+impl PeerInfo {
+    /// Creates a new `PeerInfo`, with no traffic and no capabilities reported yet.
+    pub fn new(address: Multiaddr, alias: String, relation: PeerRelation) -> Self {
+        Self {
+            address,
+            alias,
+            relation,
+            bandwidth: Bandwidth::new(),
+            capabilities: PeerCapabilities::default(),
+        }
+    }
 }
 
 /// Describes the relation with a peer.
@@ -21,6 +43,9 @@ pub enum PeerRelation {
     Known,
     /// Represents an ephemeral peer. If the connection to such a peer drops, the network won't try to reconnect.
     Unknown,
+    /// Represents a peer that was automatically chosen by the discovery. If the connection to such a peer drops, it
+    /// is dropped and its slot is freed for another discovered peer.
+    Discovered,
 }
 
 impl PeerRelation {
@@ -34,6 +59,11 @@ impl PeerRelation {
         matches!(self, Self::Unknown)
     }
 
+    /// Returns whether the peer is discovered.
+    pub fn is_discovered(&self) -> bool {
+        matches!(self, Self::Discovered)
+    }
+
     /// Sets the relation to `PeerRelation::Known`.
     pub fn set_known(&mut self) {
         *self = Self::Known;
@@ -43,6 +73,11 @@ impl PeerRelation {
     pub fn set_unknown(&mut self) {
         *self = Self::Unknown;
     }
+
+    /// Sets the relation to `PeerRelation::Discovered`.
+    pub fn set_discovered(&mut self) {
+        *self = Self::Discovered;
+    }
 }
 
 #[cfg(test)]
@@ -59,5 +94,8 @@ mod tests {
 
         pr.set_unknown();
         assert!(pr.is_unknown());
+
+        pr.set_discovered();
+        assert!(pr.is_discovered());
     }
 }
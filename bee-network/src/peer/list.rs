@@ -4,20 +4,28 @@
 #![cfg(feature = "full")]
 
 use super::{
+    capabilities::PeerCapabilities,
     error::Error,
+    gate::{self, ConnectionGate},
     info::{PeerInfo, PeerRelation},
 };
 
-use crate::{alias, config::Peer, init::global, swarm::protocols::iota_gossip::GossipSender};
+use crate::{
+    alias,
+    config::{ConnectionGateConfig, Peer},
+    init::global,
+    swarm::protocols::iota_gossip::GossipSender,
+};
 
 use hashbrown::{HashMap, HashSet};
 use libp2p::{Multiaddr, PeerId};
 use tokio::sync::RwLock;
 
-use std::{mem::take, sync::Arc};
+use std::{mem::take, sync::Arc, time::SystemTime};
 
 const REMOTE_PEERS_INITIAL_CAP: usize = 8;
 const LOCAL_ADDRS_INITIAL_CAP: usize = 4;
+const MAX_IDENTIFIED_PEERS: usize = 64;
 
 /// A thread-safe wrapper around a [`PeerList`].
 #[derive(Debug, Clone)]
@@ -34,8 +42,11 @@ pub struct PeerList {
     local_id: PeerId,
     local_addrs: HashSet<Multiaddr>,
     peers: HashMap<PeerId, (PeerInfo, PeerState)>,
-    banned_peers: HashSet<PeerId>,
-    banned_addrs: HashSet<Multiaddr>,
+    banned_peers: HashMap<PeerId, Option<SystemTime>>,
+    banned_addrs: HashMap<Multiaddr, Option<SystemTime>>,
+    // Capabilities of peers that identified themselves before being added.
+    identified: HashMap<PeerId, PeerCapabilities>,
+    gate: ConnectionGate,
 }
 
 impl PeerList {
@@ -44,8 +55,10 @@ impl PeerList {
             local_id,
             local_addrs: HashSet::with_capacity(LOCAL_ADDRS_INITIAL_CAP),
             peers: HashMap::with_capacity(REMOTE_PEERS_INITIAL_CAP),
-            banned_peers: HashSet::default(),
-            banned_addrs: HashSet::default(),
+            banned_peers: HashMap::default(),
+            banned_addrs: HashMap::default(),
+            identified: HashMap::default(),
+            gate: ConnectionGate::default(),
         }
     }
 
@@ -58,11 +71,11 @@ impl PeerList {
             (
                 peer_id,
                 (
-                    PeerInfo {
-                        address: peer.multiaddr,
-                        alias: peer.alias.unwrap_or_else(|| alias!(peer_id).to_owned()),
-                        relation: PeerRelation::Known,
-                    },
+                    PeerInfo::new(
+                        peer.multiaddr,
+                        peer.alias.unwrap_or_else(|| alias!(peer_id).to_owned()),
+                        PeerRelation::Known,
+                    ),
                     PeerState::Disconnected,
                 ),
             )
@@ -72,22 +85,43 @@ impl PeerList {
             local_id,
             local_addrs: HashSet::with_capacity(LOCAL_ADDRS_INITIAL_CAP),
             peers: p,
-            banned_peers: HashSet::default(),
-            banned_addrs: HashSet::default(),
+            banned_peers: HashMap::default(),
+            banned_addrs: HashMap::default(),
+            identified: HashMap::default(),
+            gate: ConnectionGate::default(),
         }
     }
 
-    pub fn insert_peer(&mut self, peer_id: PeerId, peer_info: PeerInfo) -> Result<(), (PeerId, PeerInfo, Error)> {
+    pub fn insert_peer(&mut self, peer_id: PeerId, mut peer_info: PeerInfo) -> Result<(), (PeerId, PeerInfo, Error)> {
         if self.contains(&peer_id) {
             return Err((peer_id, peer_info, Error::PeerIsDuplicate(peer_id)));
         }
 
+        if let Some(capabilities) = self.identified.remove(&peer_id) {
+            peer_info.capabilities.agent_version = capabilities.agent_version;
+            peer_info.capabilities.protocols = capabilities.protocols;
+        }
+
         // Since we already checked that such a `peer_id` is not yet present, the returned value is always `None`.
         let _ = self.peers.insert(peer_id, (peer_info, PeerState::Disconnected));
 
         Ok(())
     }
 
+    /// Keeps the capabilities of a peer that identified itself before being added, until it is inserted.
+    pub fn insert_identified(&mut self, peer_id: PeerId, capabilities: PeerCapabilities) {
+        // Bounds the buffer in case peers identify themselves, but never get added.
+        if self.identified.len() >= MAX_IDENTIFIED_PEERS && !self.identified.contains_key(&peer_id) {
+            return;
+        }
+
+        let _ = self.identified.insert(peer_id, capabilities);
+    }
+
+    pub fn remove_identified(&mut self, peer_id: &PeerId) {
+        let _ = self.identified.remove(peer_id);
+    }
+
     pub fn insert_local_addr(&mut self, addr: Multiaddr) -> Result<(), (Multiaddr, Error)> {
         if self.local_addrs.contains(&addr) {
             return Err((addr.clone(), Error::AddressIsDuplicate(addr)));
@@ -210,26 +244,29 @@ impl PeerList {
         self.banned_addrs.clear();
     }
 
-    pub fn ban_peer(&mut self, peer_id: PeerId) -> Result<(), Error> {
-        // TODO: use storage to persist banned peers
-        if self.banned_peers.insert(peer_id) {
-            Ok(())
-        } else {
+    /// Bans a peer, either permanently or until the given expiry.
+    pub fn ban_peer(&mut self, peer_id: PeerId, expiry: Option<SystemTime>) -> Result<(), Error> {
+        if self.is_peer_banned(&peer_id) {
             Err(Error::PeerIsBanned(peer_id))
+        } else {
+            self.banned_peers.insert(peer_id, expiry);
+            Ok(())
         }
     }
 
-    pub fn ban_address(&mut self, address: Multiaddr) -> Result<(), Error> {
-        // TODO: use storage to persist banned addrs
-        if self.banned_addrs.insert(address.clone()) {
-            Ok(())
-        } else {
+    /// Bans an address, either permanently or until the given expiry.
+    pub fn ban_address(&mut self, address: Multiaddr, expiry: Option<SystemTime>) -> Result<(), Error> {
+        if self.is_addr_banned(&address) {
             Err(Error::AddressIsBanned(address))
+        } else {
+            self.banned_addrs.insert(address, expiry);
+            Ok(())
         }
     }
 
     pub fn unban_peer(&mut self, peer_id: &PeerId) -> Result<(), Error> {
-        if self.banned_peers.remove(peer_id) {
+        if self.is_peer_banned(peer_id) {
+            self.banned_peers.remove(peer_id);
             Ok(())
         } else {
             Err(Error::PeerIsUnbanned(*peer_id))
@@ -237,7 +274,8 @@ impl PeerList {
     }
 
     pub fn unban_address(&mut self, addr: &Multiaddr) -> Result<(), Error> {
-        if self.banned_addrs.remove(addr) {
+        if self.is_addr_banned(addr) {
+            self.banned_addrs.remove(addr);
             Ok(())
         } else {
             Err(Error::AddressIsUnbanned(addr.clone()))
@@ -245,11 +283,62 @@ impl PeerList {
     }
 
     pub fn is_peer_banned(&self, peer_id: &PeerId) -> bool {
-        self.banned_peers.contains(peer_id)
+        self.banned_peers.get(peer_id).map_or(false, is_ban_active)
     }
 
     pub fn is_addr_banned(&self, addr: &Multiaddr) -> bool {
-        self.banned_addrs.contains(addr)
+        self.banned_addrs.get(addr).map_or(false, is_ban_active)
+    }
+
+    /// Replaces the rules which addresses are accepted and dialed.
+    pub fn set_connection_gate(&mut self, config: ConnectionGateConfig) {
+        self.gate = ConnectionGate::new(config);
+    }
+
+    /// Returns the connected peers whose addresses are denied by the connection gate.
+    pub fn connected_peers_denied_by_gate(&self) -> Vec<PeerId> {
+        self.filter_info(|info, state| state.is_connected() && self.gate.check_addr(&info.address).is_err())
+            .map(|(peer_id, _)| peer_id)
+            .collect()
+    }
+
+    /// Compares the known peers with the given static peers, and returns the changes needed to make them match.
+    pub fn diff_static_peers<'a>(&self, static_peers: impl IntoIterator<Item = &'a Peer>) -> PeeringDiff {
+        let mut diff = PeeringDiff::default();
+        let mut static_ids = HashSet::new();
+
+        for peer in static_peers {
+            static_ids.insert(peer.peer_id);
+
+            match self.peers.get(&peer.peer_id) {
+                None => diff.added.push(peer.clone()),
+                Some((info, _)) => {
+                    let alias = peer.alias.clone().unwrap_or_else(|| alias!(peer.peer_id).to_owned());
+
+                    if !info.relation.is_known() || info.address != peer.multiaddr || info.alias != alias {
+                        diff.updated.push(peer.clone());
+                    }
+                }
+            }
+        }
+
+        diff.removed = self
+            .filter_info(|info, _| info.relation.is_known())
+            .map(|(peer_id, _)| peer_id)
+            .filter(|peer_id| !static_ids.contains(peer_id))
+            .collect();
+
+        diff
+    }
+
+    /// Returns the currently banned peers together with the expiry of their ban.
+    pub fn banned_peers(&self) -> impl Iterator<Item = (&PeerId, &Option<SystemTime>)> {
+        self.banned_peers.iter().filter(|(_, expiry)| is_ban_active(expiry))
+    }
+
+    /// Returns the currently banned addresses together with the expiry of their ban.
+    pub fn banned_addrs(&self) -> impl Iterator<Item = (&Multiaddr, &Option<SystemTime>)> {
+        self.banned_addrs.iter().filter(|(_, expiry)| is_ban_active(expiry))
     }
 
     pub fn accepts_incoming_peer(&self, peer_id: &PeerId, peer_addr: &Multiaddr) -> Result<(), Error> {
@@ -258,16 +347,19 @@ impl PeerList {
         // - Deny one of our own addresses.
         // - Deny banned peers.
         // - Deny banned addresses.
+        // - Deny addresses denied by the connection gate.
         // - Deny already connected peers.
         // - Deny more than the configured unknown peers.
         if peer_id == &self.local_id {
             Err(Error::PeerIsLocal(*peer_id))
         } else if self.local_addrs.contains(&peer_addr) {
             Err(Error::AddressIsLocal(peer_addr.clone()))
-        } else if self.banned_peers.contains(peer_id) {
+        } else if self.is_peer_banned(peer_id) {
             Err(Error::PeerIsBanned(*peer_id))
-        } else if self.banned_addrs.contains(&peer_addr) {
+        } else if self.is_addr_banned(&peer_addr) {
             Err(Error::AddressIsBanned(peer_addr.clone()))
+        } else if let Err(e) = self.check_gate(peer_addr) {
+            Err(e)
         } else if self
             .satisfies(peer_id, |_, state| state.is_connected())
             .unwrap_or(false)
@@ -291,12 +383,13 @@ impl PeerList {
         // - Deny dialing an already connected peer.
         // - Deny dialing a local address.
         // - Deny dialing a banned address.
+        // - Deny dialing an address denied by the connection gate.
         // - Deny dialing more than configured unkown peers.
         if peer_id == &self.local_id {
             Err(Error::PeerIsLocal(*peer_id))
         } else if !self.contains(peer_id) {
             Err(Error::PeerNotPresent(*peer_id))
-        } else if self.banned_peers.contains(peer_id) {
+        } else if self.is_peer_banned(peer_id) {
             Err(Error::PeerIsBanned(*peer_id))
         } else if self
             .satisfies(peer_id, |_, state| state.is_connected())
@@ -308,8 +401,10 @@ impl PeerList {
 
             if self.local_addrs.contains(&peer_info.address) {
                 Err(Error::AddressIsLocal(peer_info.address.clone()))
-            } else if self.banned_addrs.contains(&peer_info.address) {
+            } else if self.is_addr_banned(&peer_info.address) {
                 Err(Error::AddressIsBanned(peer_info.address.clone()))
+            } else if let Err(e) = self.check_gate(&peer_info.address) {
+                Err(e)
             } else if peer_info.relation.is_unknown()
                 && self.filter_count(|info, _| info.relation.is_unknown()) >= global::max_unknown_peers()
             {
@@ -321,15 +416,50 @@ impl PeerList {
         }
     }
 
+    pub fn accepts_discovered_peer(&self, peer_id: &PeerId, peer_addr: &Multiaddr) -> Result<(), Error> {
+        // Checks performed are:
+        // - Deny ourself as peer.
+        // - Deny one of our own addresses.
+        // - Deny banned peers.
+        // - Deny banned addresses.
+        // - Deny addresses denied by the connection gate.
+        // - Deny already added peers, unless they are unknown.
+        // - Deny more than the configured discovered peers.
+        if peer_id == &self.local_id {
+            Err(Error::PeerIsLocal(*peer_id))
+        } else if self.local_addrs.contains(peer_addr) {
+            Err(Error::AddressIsLocal(peer_addr.clone()))
+        } else if self.is_peer_banned(peer_id) {
+            Err(Error::PeerIsBanned(*peer_id))
+        } else if self.is_addr_banned(peer_addr) {
+            Err(Error::AddressIsBanned(peer_addr.clone()))
+        } else if let Err(e) = self.gate.check_addr(peer_addr) {
+            Err(e)
+        } else if self
+            .satisfies(peer_id, |info, _| !info.relation.is_unknown())
+            .unwrap_or(false)
+        {
+            Err(Error::PeerIsDuplicate(*peer_id))
+        } else if self.filter_count(|info, _| info.relation.is_discovered()) >= global::max_discovered_peers() {
+            Err(Error::ExceedsDiscoveredPeerLimit(global::max_discovered_peers()))
+        } else {
+            // All checks passed! Accept that peer.
+            Ok(())
+        }
+    }
+
     pub fn allows_dialing_addr(&self, addr: &Multiaddr) -> Result<(), Error> {
         // Checks performed are:
         // - Deny dialing a local address.
         // - Deny dialing a banned address.
+        // - Deny dialing an address denied by the connection gate.
         // - Deny dialing an already connected peer (with that address).
         if self.local_addrs.contains(addr) {
             Err(Error::AddressIsLocal(addr.clone()))
-        } else if self.banned_addrs.contains(addr) {
+        } else if self.is_addr_banned(addr) {
             Err(Error::AddressIsBanned(addr.clone()))
+        } else if let Err(e) = self.check_gate(addr) {
+            Err(e)
         } else if let Some(peer_id) = self.find_peer_if_connected(addr) {
             Err(Error::PeerIsConnected(peer_id))
         } else {
@@ -338,6 +468,16 @@ impl PeerList {
         }
     }
 
+    fn check_gate(&self, addr: &Multiaddr) -> Result<(), Error> {
+        self.gate.check_addr(addr)?;
+
+        let connections = gate::ip_of(addr).map_or(0, |ip| {
+            self.filter_count(|info, state| state.is_connected() && gate::ip_of(&info.address) == Some(ip))
+        });
+
+        self.gate.check_connections_per_ip(addr, connections)
+    }
+
     fn find_peer_if_connected(&self, addr: &Multiaddr) -> Option<PeerId> {
         self.filter_info(|info, state| state.is_connected() && info.address == *addr)
             .next()
@@ -345,6 +485,21 @@ impl PeerList {
     }
 }
 
+/// The changes needed to make the known peers of a [`PeerList`] match a new set of static peers.
+#[derive(Debug, Default)]
+pub struct PeeringDiff {
+    /// Static peers that are not in the peerlist yet.
+    pub added: Vec<Peer>,
+    /// Static peers whose relation is not `Known`, or whose address or alias differs.
+    pub updated: Vec<Peer>,
+    /// Known peers that are no longer static peers.
+    pub removed: Vec<PeerId>,
+}
+
+fn is_ban_active(expiry: &Option<SystemTime>) -> bool {
+    expiry.map_or(true, |expiry| expiry > SystemTime::now())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -390,6 +545,25 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn insert_identified_peer() {
+        let local_id = gen_constant_peer_id();
+        let mut pl = PeerList::new(local_id);
+
+        let peer_id = gen_random_peer_id();
+        let capabilities = PeerCapabilities {
+            agent_version: Some("bee/0.1.0".to_string()),
+            protocols: vec!["/iota-gossip/1/1.0.0".to_string()],
+            gossip_version: None,
+        };
+
+        // The capabilities of a peer that identified itself before being added are applied once it is inserted.
+        pl.insert_identified(peer_id, capabilities.clone());
+        pl.insert_peer(peer_id, gen_constant_peer_info()).unwrap();
+
+        assert_eq!(pl.info(&peer_id).unwrap().capabilities, capabilities);
+    }
+
     #[test]
     fn deny_incoming_local_peer() {
         let local_id = gen_constant_peer_id();
@@ -414,6 +588,44 @@ mod tests {
         pl.accepts_incoming_peer(&peer_id, &peer_info.address).unwrap();
     }
 
+    #[test]
+    fn deny_discovered_peer_twice() {
+        let local_id = gen_constant_peer_id();
+        let peer_id = gen_random_peer_id();
+        let peer_info = gen_deterministic_peer_info(0, PeerRelation::Discovered);
+
+        let mut pl = PeerList::new(local_id);
+
+        assert!(matches!(
+            pl.accepts_discovered_peer(&local_id, &peer_info.address),
+            Err(Error::PeerIsLocal(_))
+        ));
+
+        pl.insert_peer(peer_id, peer_info.clone()).unwrap();
+
+        assert!(matches!(
+            pl.accepts_discovered_peer(&peer_id, &peer_info.address),
+            Err(Error::PeerIsDuplicate(_))
+        ));
+    }
+
+    #[test]
+    fn expired_ban() {
+        let local_id = gen_constant_peer_id();
+        let peer_id = gen_random_peer_id();
+
+        let mut pl = PeerList::new(local_id);
+
+        pl.ban_peer(peer_id, Some(SystemTime::now() - std::time::Duration::from_secs(1)))
+            .unwrap();
+        assert!(!pl.is_peer_banned(&peer_id));
+        assert_eq!(pl.banned_peers().count(), 0);
+
+        pl.ban_peer(peer_id, None).unwrap();
+        assert!(pl.is_peer_banned(&peer_id));
+        assert!(matches!(pl.ban_peer(peer_id, None), Err(Error::PeerIsBanned(_))));
+    }
+
     #[test]
     fn conditional_remove() {
         let local_id = gen_constant_peer_id();
@@ -432,6 +644,49 @@ mod tests {
         assert_eq!(0, pl.len());
     }
 
+    #[test]
+    fn diff_static_peers() {
+        let local_id = gen_constant_peer_id();
+        let mut pl = PeerList::new(local_id);
+
+        let unchanged = gen_random_peer_id();
+        let realiased = gen_random_peer_id();
+        let promoted = gen_random_peer_id();
+        let removed = gen_random_peer_id();
+        let added = gen_random_peer_id();
+
+        pl.insert_peer(unchanged, gen_deterministic_peer_info(1, PeerRelation::Known))
+            .unwrap();
+        pl.insert_peer(realiased, gen_deterministic_peer_info(2, PeerRelation::Known))
+            .unwrap();
+        pl.insert_peer(promoted, gen_deterministic_peer_info(3, PeerRelation::Discovered))
+            .unwrap();
+        pl.insert_peer(removed, gen_deterministic_peer_info(4, PeerRelation::Known))
+            .unwrap();
+
+        let peer = |peer_id, port: u16, alias: &str| Peer {
+            peer_id,
+            multiaddr: gen_deterministic_addr(port),
+            alias: Some(alias.into()),
+        };
+
+        let diff = pl.diff_static_peers(&[
+            peer(unchanged, 1, "1"),
+            peer(realiased, 2, "two"),
+            peer(promoted, 3, "3"),
+            peer(added, 5, "5"),
+        ]);
+
+        assert_eq!(diff.added.iter().map(|p| p.peer_id).collect::<Vec<_>>(), vec![added]);
+        assert_eq!(diff.removed, vec![removed]);
+
+        let mut updated = diff.updated.iter().map(|p| p.peer_id).collect::<Vec<_>>();
+        updated.sort();
+        let mut expected = vec![realiased, promoted];
+        expected.sort();
+        assert_eq!(updated, expected);
+    }
+
     // ===== helpers =====
 
     pub fn gen_constant_peer_id() -> PeerId {
@@ -443,19 +698,11 @@ mod tests {
     }
 
     pub fn gen_deterministic_peer_info(port: u16, relation: PeerRelation) -> PeerInfo {
-        PeerInfo {
-            address: gen_deterministic_addr(port),
-            alias: port.to_string(),
-            relation,
-        }
+        PeerInfo::new(gen_deterministic_addr(port), port.to_string(), relation)
     }
 
     pub fn gen_constant_peer_info() -> PeerInfo {
-        PeerInfo {
-            address: gen_deterministic_addr(1),
-            alias: String::new(),
-            relation: PeerRelation::Known,
-        }
+        PeerInfo::new(gen_deterministic_addr(1), String::new(), PeerRelation::Known)
     }
 
     pub fn gen_deterministic_addr(port: u16) -> Multiaddr {
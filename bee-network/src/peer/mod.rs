@@ -1,6 +1,10 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod bandwidth;
+pub mod capabilities;
 pub mod error;
+pub mod gate;
 pub mod info;
 pub mod list;
+pub mod store;
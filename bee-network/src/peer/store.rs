@@ -0,0 +1,301 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "full")]
+
+use super::{
+    info::{PeerInfo, PeerRelation},
+    list::{PeerList, PeerListWrapper},
+};
+
+use hashbrown::HashMap;
+use libp2p::{Multiaddr, PeerId};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// [`PeerStore`] errors.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Reading or writing the peer store file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// (De)serializing the peer store file failed.
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// The task writing the peer store file failed.
+    #[error("Writing task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+/// The relations that are persisted. Unknown peers are ephemeral and hence never persisted.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum StoredRelation {
+    Known,
+    Discovered,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredPeer {
+    peer_id: String,
+    address: String,
+    alias: String,
+    relation: StoredRelation,
+    last_seen: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredBan {
+    target: String,
+    expiry: Option<u64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredState {
+    peers: Vec<StoredPeer>,
+    banned_peers: Vec<StoredBan>,
+    banned_addrs: Vec<StoredBan>,
+}
+
+/// The peers and bans restored from a [`PeerStore`].
+#[derive(Debug, Default)]
+pub struct LoadedState {
+    pub peers: Vec<(PeerId, PeerInfo)>,
+    pub banned_peers: Vec<(PeerId, Option<SystemTime>)>,
+    pub banned_addrs: Vec<(Multiaddr, Option<SystemTime>)>,
+}
+
+/// A thread-safe wrapper around a [`PeerStore`] that only saves the peerlist when it changed.
+#[derive(Debug, Clone)]
+pub struct PeerStoreWrapper(pub Arc<Mutex<PeerStore>>, Arc<AtomicBool>);
+
+impl PeerStoreWrapper {
+    pub fn new(peer_store: PeerStore) -> Self {
+        Self(Arc::new(Mutex::new(peer_store)), Arc::new(AtomicBool::new(false)))
+    }
+
+    /// Marks the saved state as outdated, so that the next flush saves the peerlist.
+    pub fn mark_dirty(&self) {
+        self.1.store(true, Ordering::Relaxed);
+    }
+
+    /// Saves the peerlist if it changed since the last flush, or anyway if `force` is set, e.g. to refresh the
+    /// last-seen information of the connected peers.
+    pub async fn flush(&self, peerlist: &PeerListWrapper, force: bool) -> Result<(), Error> {
+        if !self.1.swap(false, Ordering::Relaxed) && !force {
+            return Ok(());
+        }
+
+        // Holding the lock during the write keeps concurrent flushes from racing on the file.
+        let mut peer_store = self.0.lock().await;
+        let stored = peer_store.snapshot(&*peerlist.0.read().await);
+        let path = peer_store.path.clone();
+
+        let res = match tokio::task::spawn_blocking(move || write(&path, &stored)).await {
+            Ok(res) => res,
+            Err(e) => Err(e.into()),
+        };
+
+        if res.is_err() {
+            // Retry with the next flush.
+            self.mark_dirty();
+        }
+
+        res
+    }
+}
+
+/// Persists the known and discovered peers, as well as banned peers and addresses, in a JSON file.
+#[derive(Debug)]
+pub struct PeerStore {
+    path: PathBuf,
+    last_seen: HashMap<PeerId, u64>,
+}
+
+impl PeerStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            last_seen: HashMap::default(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the persisted state. A missing file results in an empty state, invalid entries are skipped.
+    pub fn load(&mut self) -> Result<LoadedState, Error> {
+        if !self.path.exists() {
+            return Ok(LoadedState::default());
+        }
+
+        let stored: StoredState = serde_json::from_slice(&fs::read(&self.path)?)?;
+        let mut loaded = LoadedState::default();
+
+        for peer in stored.peers {
+            match (peer.peer_id.parse::<PeerId>(), peer.address.parse::<Multiaddr>()) {
+                (Ok(peer_id), Ok(address)) => {
+                    if let Some(last_seen) = peer.last_seen {
+                        self.last_seen.insert(peer_id, last_seen);
+                    }
+
+                    loaded.peers.push((
+                        peer_id,
+                        PeerInfo::new(
+                            address,
+                            peer.alias,
+                            match peer.relation {
+                                StoredRelation::Known => PeerRelation::Known,
+                                StoredRelation::Discovered => PeerRelation::Discovered,
+                            },
+                        ),
+                    ));
+                }
+                _ => warn!("Skipping invalid stored peer {}.", peer.peer_id),
+            }
+        }
+
+        for ban in stored.banned_peers {
+            match ban.target.parse::<PeerId>() {
+                Ok(peer_id) => loaded.banned_peers.push((peer_id, ban.expiry.map(from_unix_secs))),
+                Err(_) => warn!("Skipping invalid stored peer ban {}.", ban.target),
+            }
+        }
+
+        for ban in stored.banned_addrs {
+            match ban.target.parse::<Multiaddr>() {
+                Ok(addr) => loaded.banned_addrs.push((addr, ban.expiry.map(from_unix_secs))),
+                Err(_) => warn!("Skipping invalid stored address ban {}.", ban.target),
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    /// Saves the current state of the peerlist.
+    pub fn save(&mut self, peerlist: &PeerList) -> Result<(), Error> {
+        let stored = self.snapshot(peerlist);
+
+        write(&self.path, &stored)
+    }
+
+    // Captures the state of the peerlist to persist, and refreshes the last-seen information of connected peers.
+    fn snapshot(&mut self, peerlist: &PeerList) -> StoredState {
+        let now = to_unix_secs(SystemTime::now());
+
+        let peers = peerlist
+            .filter_info(|info, _| !info.relation.is_unknown())
+            .map(|(peer_id, info)| {
+                if peerlist
+                    .satisfies(&peer_id, |_, state| state.is_connected())
+                    .unwrap_or(false)
+                {
+                    self.last_seen.insert(peer_id, now);
+                }
+
+                StoredPeer {
+                    peer_id: peer_id.to_string(),
+                    address: info.address.to_string(),
+                    alias: info.alias,
+                    relation: if info.relation.is_known() {
+                        StoredRelation::Known
+                    } else {
+                        StoredRelation::Discovered
+                    },
+                    last_seen: self.last_seen.get(&peer_id).copied(),
+                }
+            })
+            .collect();
+
+        StoredState {
+            peers,
+            banned_peers: peerlist
+                .banned_peers()
+                .map(|(peer_id, expiry)| StoredBan {
+                    target: peer_id.to_string(),
+                    expiry: expiry.map(to_unix_secs),
+                })
+                .collect(),
+            banned_addrs: peerlist
+                .banned_addrs()
+                .map(|(addr, expiry)| StoredBan {
+                    target: addr.to_string(),
+                    expiry: expiry.map(to_unix_secs),
+                })
+                .collect(),
+        }
+    }
+}
+
+fn write(path: &Path, stored: &StoredState) -> Result<(), Error> {
+    // Write to a temporary file first, so a crash while writing doesn't corrupt the existing store.
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(stored)?)?;
+    fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
+fn to_unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+fn from_unix_secs(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::identity::ed25519::Keypair;
+
+    fn gen_random_peer_id() -> PeerId {
+        PeerId::from_public_key(libp2p_core::PublicKey::Ed25519(Keypair::generate().public()))
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("peerstore_{}.json", gen_random_peer_id()));
+        let mut pl = PeerList::new(gen_random_peer_id());
+
+        let known_id = gen_random_peer_id();
+        let unknown_id = gen_random_peer_id();
+        let banned_id = gen_random_peer_id();
+
+        for (peer_id, relation) in [(known_id, PeerRelation::Known), (unknown_id, PeerRelation::Unknown)].iter() {
+            pl.insert_peer(
+                *peer_id,
+                PeerInfo::new(
+                    "/ip4/127.0.0.1/tcp/15600".parse().unwrap(),
+                    peer_id.to_string(),
+                    *relation,
+                ),
+            )
+            .unwrap();
+        }
+        pl.ban_peer(banned_id, None).unwrap();
+
+        PeerStore::new(&path).save(&pl).unwrap();
+        let loaded = PeerStore::new(&path).load().unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded.peers.len(), 1);
+        assert_eq!(loaded.peers[0].0, known_id);
+        assert!(loaded.peers[0].1.relation.is_known());
+        assert_eq!(loaded.banned_peers, vec![(banned_id, None)]);
+    }
+}
@@ -1,13 +1,18 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::peer::info::PeerRelation;
+use crate::{
+    config::{ConnectionGateConfig, PeeringConfig},
+    peer::info::PeerRelation,
+};
 
 use super::error::Error;
 
 use libp2p::{Multiaddr, PeerId};
 use tokio::sync::mpsc;
 
+use std::time::Duration;
+
 pub type CommandReceiver = mpsc::UnboundedReceiver<Command>;
 pub type CommandSender = mpsc::UnboundedSender<Command>;
 
@@ -55,6 +60,13 @@ pub enum Command {
         /// The peer's id.
         peer_id: PeerId,
     },
+    /// Bans a peer for a limited duration.
+    BanPeerFor {
+        /// The peer's id.
+        peer_id: PeerId,
+        /// The duration of the ban.
+        duration: Duration,
+    },
     /// Unbans a peer.
     UnbanPeer {
         /// The peer's id.
@@ -77,6 +89,18 @@ pub enum Command {
         /// The peer's new relation.
         to: PeerRelation,
     },
+    /// Replaces the rules which addresses the node accepts connections from and dials. Connected peers whose addresses
+    /// are denied by the new rules are disconnected.
+    ReloadConnectionGate {
+        /// The new rules.
+        config: ConnectionGateConfig,
+    },
+    /// Applies new static peers. Missing peers are added, known peers that are no longer static are removed, and the
+    /// alias, address and relation of the others are updated without dropping their connections.
+    ReloadPeering {
+        /// The new static peers.
+        config: PeeringConfig,
+    },
 }
 
 /// Allows the user to send [`Command`]s to the network layer.
@@ -4,8 +4,14 @@
 use super::command::Command;
 
 use crate::{
+    discovery::reason::{DropReason, SelectionReason},
     network::origin::Origin,
-    peer::{error::Error as PeerError, info::PeerInfo},
+    peer::{
+        bandwidth::BandwidthStats,
+        capabilities::{GossipVersion, PeerCapabilities},
+        error::Error as PeerError,
+        info::{PeerInfo, PeerRelation},
+    },
     swarm::protocols::iota_gossip::{GossipReceiver, GossipSender},
 };
 
@@ -43,6 +49,14 @@ pub enum Event {
         address: Multiaddr,
     },
 
+    /// The gossip traffic counters of a connected peer were sampled.
+    BandwidthUpdated {
+        /// The peer's id.
+        peer_id: PeerId,
+        /// The peer's traffic counters.
+        stats: BandwidthStats,
+    },
+
     /// A command failed.
     CommandFailed {
         /// The command that failed.
@@ -89,17 +103,61 @@ pub enum Event {
         peer_id: PeerId,
     },
 
+    /// A peer selected by the peer discovery was dropped.
+    PeerDropped {
+        /// The peer's id.
+        peer_id: PeerId,
+        /// The reason why the peer was dropped.
+        reason: DropReason,
+    },
+
+    /// A peer identified itself, and its capabilities were updated.
+    PeerIdentified {
+        /// The peer's id.
+        peer_id: PeerId,
+        /// The peer's updated capabilities.
+        capabilities: PeerCapabilities,
+    },
+
+    /// The relation with a peer changed.
+    PeerRelationChanged {
+        /// The peer's id.
+        peer_id: PeerId,
+        /// The previous relation.
+        from: PeerRelation,
+        /// The new relation.
+        to: PeerRelation,
+    },
+
     /// A peer was removed.
     PeerRemoved {
         /// The peer's id.
         peer_id: PeerId,
     },
 
+    /// A peer was selected by the peer discovery.
+    PeerSelected {
+        /// The peer's id.
+        peer_id: PeerId,
+        /// The peer's info.
+        info: PeerInfo,
+        /// The reason why the peer was selected.
+        reason: SelectionReason,
+    },
+
     /// A peer was unbanned.
     PeerUnbanned {
         /// The peer's id.
         peer_id: PeerId,
     },
+
+    /// The alias or address of a peer changed.
+    PeerUpdated {
+        /// The peer's id.
+        peer_id: PeerId,
+        /// The peer's updated info.
+        info: PeerInfo,
+    },
 }
 
 /// Describes the internal events.
@@ -121,10 +179,35 @@ pub enum InternalEvent {
         origin: Origin,
         /// The negotiated substream the protocol is running on.
         substream: Box<NegotiatedSubstream>,
+        /// The negotiated version of the gossip protocol.
+        version: GossipVersion,
     },
 
     /// The gossip protocol has been dropped with a peer.
     ProtocolDropped { peer_id: PeerId },
+
+    /// A peer was found and verified by the peer discovery.
+    PeerDiscovered {
+        /// The peer's id.
+        peer_id: PeerId,
+        /// The peer's address.
+        peer_addr: Multiaddr,
+        /// The reason why the peer is a candidate.
+        reason: SelectionReason,
+    },
+
+    /// All addresses of a dialed peer turned out to be unreachable.
+    PeerUnreachable { peer_id: PeerId },
+
+    /// A peer identified itself.
+    PeerIdentified {
+        /// The peer's id.
+        peer_id: PeerId,
+        /// The peer's agent version.
+        agent_version: String,
+        /// The protocols supported by the peer.
+        protocols: Vec<String>,
+    },
 }
 
 /// Allows the user to receive [`Event`]s published by the network layer.
@@ -9,13 +9,17 @@ use super::{
 
 use crate::{
     alias,
+    config::{GossipConfig, GossipQueueConfig, Peer, PeeringConfig},
+    discovery::reason::DropReason,
     init::global::reconnect_interval_secs,
     peer::{
+        capabilities::PeerCapabilities,
         error::Error as PeerError,
         info::{PeerInfo, PeerRelation},
         list::PeerListWrapper as PeerList,
+        store::PeerStoreWrapper as PeerStore,
     },
-    swarm::protocols::iota_gossip,
+    swarm::protocols::iota_gossip::{self, RateLimiter},
 };
 
 use bee_runtime::shutdown_stream::ShutdownStream;
@@ -31,6 +35,8 @@ use rand::Rng;
 use tokio::time::{self, Duration, Instant};
 use tokio_stream::wrappers::{IntervalStream, UnboundedReceiverStream};
 
+use std::time::SystemTime;
+
 const MAX_PEER_STATE_CHECKER_DELAY_MILLIS: u64 = 2000;
 
 pub struct ServiceHostConfig {
@@ -38,6 +44,9 @@ pub struct ServiceHostConfig {
     pub senders: Senders,
     pub receivers: Receivers,
     pub peerlist: PeerList,
+    pub peer_store: Option<PeerStore>,
+    pub rate_limiter: RateLimiter,
+    pub gossip: GossipConfig,
 }
 
 #[derive(Clone)]
@@ -86,6 +95,9 @@ pub mod integrated {
                 senders,
                 receivers,
                 peerlist,
+                peer_store,
+                rate_limiter,
+                gossip,
             } = config;
 
             let Receivers {
@@ -94,12 +106,25 @@ pub mod integrated {
             } = receivers;
 
             node.spawn::<Self, _, _>(|shutdown| {
-                command_processor(shutdown, commands, senders.clone(), peerlist.clone())
+                command_processor(
+                    shutdown,
+                    commands,
+                    senders.clone(),
+                    peerlist.clone(),
+                    peer_store.clone(),
+                )
             });
             node.spawn::<Self, _, _>(|shutdown| {
-                event_processor(shutdown, internal_events, senders.clone(), peerlist.clone())
+                event_processor(
+                    shutdown,
+                    internal_events,
+                    senders.clone(),
+                    peerlist.clone(),
+                    rate_limiter,
+                    gossip,
+                )
             });
-            node.spawn::<Self, _, _>(|shutdown| peerstate_checker(shutdown, senders, peerlist));
+            node.spawn::<Self, _, _>(|shutdown| peerstate_checker(shutdown, senders, peerlist, peer_store));
 
             info!("Network service started.");
 
@@ -127,6 +152,9 @@ pub mod standalone {
                 senders,
                 receivers,
                 peerlist,
+                peer_store,
+                rate_limiter,
+                gossip,
             } = config;
 
             let Receivers {
@@ -150,42 +178,81 @@ pub mod standalone {
                 commands,
                 senders.clone(),
                 peerlist.clone(),
+                peer_store.clone(),
             ));
             tokio::spawn(event_processor(
                 shutdown_rx2,
                 internal_events,
                 senders.clone(),
                 peerlist.clone(),
+                rate_limiter,
+                gossip,
             ));
-            tokio::spawn(peerstate_checker(shutdown_rx3, senders, peerlist));
+            tokio::spawn(peerstate_checker(shutdown_rx3, senders, peerlist, peer_store));
 
             info!("Network service started.");
         }
     }
 }
 
-async fn command_processor(shutdown: Shutdown, commands: CommandReceiver, senders: Senders, peerlist: PeerList) {
+async fn command_processor(
+    shutdown: Shutdown,
+    commands: CommandReceiver,
+    senders: Senders,
+    peerlist: PeerList,
+    peer_store: Option<PeerStore>,
+) {
     debug!("Command processor running.");
 
     let mut commands = ShutdownStream::new(shutdown, UnboundedReceiverStream::new(commands));
 
     while let Some(command) = commands.next().await {
+        let persist = !matches!(
+            command,
+            Command::DialAddress { .. } | Command::DialPeer { .. } | Command::ReloadConnectionGate { .. }
+        );
+
         if let Err(e) = process_command(command, &senders, &peerlist).await {
             error!("Error processing command. Cause: {}", e);
             continue;
         }
+
+        // Changes are only marked here and saved by the peer state checker, so bursts of commands write the file once.
+        if persist {
+            if let Some(peer_store) = &peer_store {
+                peer_store.mark_dirty();
+            }
+        }
     }
 
+    // Make sure the latest state survives the shutdown.
+    persist_peers(&peer_store, &peerlist, true).await;
+
     debug!("Command processor stopped.");
 }
 
-async fn event_processor(shutdown: Shutdown, events: InternalEventReceiver, senders: Senders, peerlist: PeerList) {
+async fn persist_peers(peer_store: &Option<PeerStore>, peerlist: &PeerList, force: bool) {
+    if let Some(peer_store) = peer_store {
+        if let Err(e) = peer_store.flush(peerlist, force).await {
+            warn!("Saving the peer store failed. Cause: {}", e);
+        }
+    }
+}
+
+async fn event_processor(
+    shutdown: Shutdown,
+    events: InternalEventReceiver,
+    senders: Senders,
+    peerlist: PeerList,
+    rate_limiter: RateLimiter,
+    gossip: GossipConfig,
+) {
     debug!("Event processor running.");
 
     let mut internal_events = ShutdownStream::new(shutdown, UnboundedReceiverStream::new(events));
 
     while let Some(internal_event) = internal_events.next().await {
-        if let Err(e) = process_internal_event(internal_event, &senders, &peerlist).await {
+        if let Err(e) = process_internal_event(internal_event, &senders, &peerlist, &rate_limiter, &gossip).await {
             error!("Error processing internal event. Cause: {}", e);
             continue;
         }
@@ -195,11 +262,9 @@ async fn event_processor(shutdown: Shutdown, events: InternalEventReceiver, send
 }
 
 // TODO: implement exponential back-off to not spam the peer with reconnect attempts.
-async fn peerstate_checker(shutdown: Shutdown, senders: Senders, peerlist: PeerList) {
+async fn peerstate_checker(shutdown: Shutdown, senders: Senders, peerlist: PeerList, peer_store: Option<PeerStore>) {
     debug!("Peer checker running.");
 
-    let Senders { internal_commands, .. } = senders;
-
     // NOTE:
     // We want to reduce the overhead of simultaneous mutual dialing even if several nodes are started at the same time
     // (by script for example). We do this here by adding a small random delay to when this task will be executing
@@ -215,23 +280,37 @@ async fn peerstate_checker(shutdown: Shutdown, senders: Senders, peerlist: PeerL
     // Check, if there are any disconnected known peers, and schedule a reconnect attempt for each
     // of those.
     while interval.next().await.is_some() {
+        // Regularly persist the changes, and the last-seen information of connected peers to keep it reasonably
+        // up-to-date.
+        let any_connected = peerlist.0.read().await.filter_count(|_, state| state.is_connected()) > 0;
+        persist_peers(&peer_store, &peerlist, any_connected).await;
+
         let peerlist = peerlist.0.read().await;
 
         let num_known = peerlist.filter_count(|info, _| info.relation.is_known());
         let num_connected_known = peerlist.filter_count(|info, state| info.relation.is_known() && state.is_connected());
         let num_connected_unknown =
             peerlist.filter_count(|info, state| info.relation.is_unknown() && state.is_connected());
+        let num_connected_discovered =
+            peerlist.filter_count(|info, state| info.relation.is_discovered() && state.is_connected());
 
         info!(
-            "Connected peers: known {}/{} unknown {}.",
-            num_connected_known, num_known, num_connected_unknown,
+            "Connected peers: known {}/{} unknown {} discovered {}.",
+            num_connected_known, num_known, num_connected_unknown, num_connected_discovered,
         );
 
+        for (peer_id, info) in peerlist.filter_info(|_, state| state.is_connected()) {
+            let _ = senders.events.send(Event::BandwidthUpdated {
+                peer_id,
+                stats: info.bandwidth.stats(),
+            });
+        }
+
         for (peer_id, info) in peerlist.filter_info(|info, state| info.relation.is_known() && state.is_disconnected()) {
             info!("Trying to connect to: {} ({}).", info.alias, alias!(peer_id));
 
             // Ignore if the command fails. We can always retry the next time.
-            let _ = internal_commands.send(Command::DialPeer { peer_id });
+            let _ = senders.internal_commands.send(Command::DialPeer { peer_id });
         }
     }
 
@@ -254,7 +333,7 @@ async fn process_command(command: Command, senders: &Senders, peerlist: &PeerLis
         }
 
         Command::BanAddress { address } => {
-            peerlist.0.write().await.ban_address(address.clone())?;
+            peerlist.0.write().await.ban_address(address.clone(), None)?;
 
             senders
                 .events
@@ -263,7 +342,20 @@ async fn process_command(command: Command, senders: &Senders, peerlist: &PeerLis
         }
 
         Command::BanPeer { peer_id } => {
-            peerlist.0.write().await.ban_peer(peer_id)?;
+            peerlist.0.write().await.ban_peer(peer_id, None)?;
+
+            senders
+                .events
+                .send(Event::PeerBanned { peer_id })
+                .map_err(|_| Error::SendingEventFailed)?;
+        }
+
+        Command::BanPeerFor { peer_id, duration } => {
+            peerlist
+                .0
+                .write()
+                .await
+                .ban_peer(peer_id, Some(SystemTime::now() + duration))?;
 
             senders
                 .events
@@ -297,6 +389,27 @@ async fn process_command(command: Command, senders: &Senders, peerlist: &PeerLis
             disconnect_peer(peer_id, senders, peerlist).await?;
         }
 
+        Command::ReloadConnectionGate { config } => {
+            let denied = {
+                let mut peerlist = peerlist.0.write().await;
+                peerlist.set_connection_gate(config);
+                peerlist.connected_peers_denied_by_gate()
+            };
+
+            for peer_id in denied {
+                info!("Disconnecting {} denied by the connection gate.", alias!(peer_id));
+
+                // The peer might have disconnected in the meantime, which must not prevent disconnecting the others.
+                if let Err(e) = disconnect_peer(peer_id, senders, peerlist).await {
+                    warn!("Disconnecting {} failed. Cause: {}", alias!(peer_id), e);
+                }
+            }
+        }
+
+        Command::ReloadPeering { config } => {
+            reload_peering(config, senders, peerlist).await?;
+        }
+
         Command::RemovePeer { peer_id } => {
             remove_peer(peer_id, senders, peerlist).await?;
         }
@@ -327,6 +440,8 @@ async fn process_internal_event(
     internal_event: InternalEvent,
     senders: &Senders,
     peerlist: &PeerList,
+    rate_limiter: &RateLimiter,
+    gossip: &GossipConfig,
 ) -> Result<(), Error> {
     match internal_event {
         InternalEvent::AddressBound { address } => {
@@ -341,10 +456,15 @@ async fn process_internal_event(
 
             // Try to disconnect, but ignore errors in-case the peer was disconnected already.
             let _ = peerlist.update_state(&peer_id, |state| state.set_disconnected());
+            let _ = peerlist.update_info(&peer_id, |info| info.capabilities.gossip_version = None);
+            peerlist.remove_identified(&peer_id);
 
             // Try to remove unknown peers.
             let _ = peerlist.filter_remove(&peer_id, |peer_info, _| peer_info.relation.is_unknown());
 
+            // Try to remove discovered peers to free their slot for another one.
+            let dropped = peerlist.filter_remove(&peer_id, |peer_info, _| peer_info.relation.is_discovered());
+
             // We no longer need to hold the lock.
             drop(peerlist);
 
@@ -352,6 +472,137 @@ async fn process_internal_event(
                 .events
                 .send(Event::PeerDisconnected { peer_id })
                 .map_err(|_| Error::SendingEventFailed)?;
+
+            if dropped {
+                senders
+                    .events
+                    .send(Event::PeerDropped {
+                        peer_id,
+                        reason: DropReason::Disconnected,
+                    })
+                    .map_err(|_| Error::SendingEventFailed)?;
+            }
+        }
+
+        InternalEvent::PeerDiscovered {
+            peer_id,
+            peer_addr,
+            reason,
+        } => {
+            let mut peerlist = peerlist.0.write().await;
+
+            if let Err(e) = peerlist.accepts_discovered_peer(&peer_id, &peer_addr) {
+                debug!("Discovered peer {} not selected. Cause: {}", alias!(peer_id), e);
+                return Ok(());
+            }
+
+            // A discovered peer might already be connected as an "unknown" peer, in which case it is promoted and
+            // takes a discovery slot instead.
+            let peer_added = !peerlist.contains(&peer_id);
+
+            if peer_added {
+                let peer_info = PeerInfo::new(peer_addr, alias!(peer_id).to_string(), PeerRelation::Discovered);
+                peerlist.insert_peer(peer_id, peer_info).map_err(|(_, _, e)| e)?;
+            } else {
+                peerlist.update_info(&peer_id, |info| info.relation.set_discovered())?;
+            }
+
+            // Panic:
+            // We made sure, that the peer id exists, hence, unwrapping is fine.
+            let peer_info = peerlist.info(&peer_id).unwrap();
+            let connected = peerlist.satisfies(&peer_id, |_, state| state.is_connected())?;
+
+            // We no longer need to hold the lock.
+            drop(peerlist);
+
+            info!(
+                "Selected discovered peer {} ({}). Reason: {}.",
+                peer_info.alias,
+                alias!(peer_id),
+                reason
+            );
+
+            if peer_added {
+                senders
+                    .events
+                    .send(Event::PeerAdded {
+                        peer_id,
+                        info: peer_info.clone(),
+                    })
+                    .map_err(|_| Error::SendingEventFailed)?;
+
+                send_buffered_identity(senders, peer_id, &peer_info)?;
+            }
+
+            senders
+                .events
+                .send(Event::PeerSelected {
+                    peer_id,
+                    info: peer_info,
+                    reason,
+                })
+                .map_err(|_| Error::SendingEventFailed)?;
+
+            if !connected {
+                senders
+                    .internal_commands
+                    .send(Command::DialPeer { peer_id })
+                    .map_err(|_| Error::SendingCommandFailed)?;
+            }
+        }
+
+        InternalEvent::PeerUnreachable { peer_id } => {
+            let dropped = peerlist.0.write().await.filter_remove(&peer_id, |peer_info, state| {
+                peer_info.relation.is_discovered() && state.is_disconnected()
+            });
+
+            if dropped {
+                senders
+                    .events
+                    .send(Event::PeerDropped {
+                        peer_id,
+                        reason: DropReason::Unreachable,
+                    })
+                    .map_err(|_| Error::SendingEventFailed)?;
+            }
+        }
+
+        InternalEvent::PeerIdentified {
+            peer_id,
+            agent_version,
+            protocols,
+        } => {
+            let mut peerlist = peerlist.0.write().await;
+            let mut capabilities = None;
+
+            let updated = peerlist.update_info(&peer_id, |info| {
+                info.capabilities.agent_version = Some(agent_version.clone());
+                info.capabilities.protocols = protocols.clone();
+                capabilities = Some(info.capabilities.clone());
+            });
+
+            // An unknown peer might identify itself before it is added, in which case its capabilities are kept until
+            // it is.
+            if updated.is_err() {
+                peerlist.insert_identified(
+                    peer_id,
+                    PeerCapabilities {
+                        agent_version: Some(agent_version),
+                        protocols,
+                        gossip_version: None,
+                    },
+                );
+            }
+
+            // We no longer need to hold the lock.
+            drop(peerlist);
+
+            if let Some(capabilities) = capabilities {
+                senders
+                    .events
+                    .send(Event::PeerIdentified { peer_id, capabilities })
+                    .map_err(|_| Error::SendingEventFailed)?;
+            }
         }
 
         InternalEvent::ProtocolEstablished {
@@ -359,6 +610,7 @@ async fn process_internal_event(
             peer_addr,
             origin,
             substream,
+            version,
         } => {
             let mut peerlist = peerlist.0.write().await;
             let mut peer_added = false;
@@ -372,15 +624,16 @@ async fn process_internal_event(
             if accepted.is_ok() {
                 // If the peer doesn't exist yet - but is accepted as an "unknown" peer, we insert it now.
                 if !peerlist.contains(&peer_id) {
-                    let peer_info = PeerInfo {
-                        address: peer_addr,
-                        alias: alias!(peer_id).to_string(),
-                        relation: PeerRelation::Unknown,
-                    };
+                    let peer_info = PeerInfo::new(peer_addr, alias!(peer_id).to_string(), PeerRelation::Unknown);
                     peerlist.insert_peer(peer_id, peer_info).map_err(|(_, _, e)| e)?;
                     peer_added = true;
+                } else if origin.is_outbound() {
+                    // Remember the address we last successfully dialed the peer at.
+                    let _ = peerlist.update_info(&peer_id, |info| info.address = peer_addr.clone());
                 }
 
+                let _ = peerlist.update_info(&peer_id, |info| info.capabilities.gossip_version = Some(version));
+
                 // Panic:
                 // We made sure, that the peer id exists in the above if-branch, hence, unwrapping is fine.
                 let peer_info = peerlist.info(&peer_id).unwrap();
@@ -391,11 +644,37 @@ async fn process_internal_event(
                 let reader = BufReader::with_capacity(IO_BUFFER_LEN, r);
                 let writer = BufWriter::with_capacity(IO_BUFFER_LEN, w);
 
-                let (incoming_tx, incoming_rx) = iota_gossip::channel();
-                let (outgoing_tx, outgoing_rx) = iota_gossip::channel();
-
-                iota_gossip::start_incoming_processor(peer_id, reader, incoming_tx, senders.internal_events.clone());
-                iota_gossip::start_outgoing_processor(peer_id, writer, outgoing_rx, senders.internal_events.clone());
+                let (incoming_tx, incoming_rx) = {
+                    let bandwidth = peer_info.bandwidth.clone();
+                    let GossipQueueConfig { capacity, policy } = gossip.inbound();
+                    iota_gossip::channel(capacity, policy, move || bandwidth.record_dropped_inbound())
+                };
+                let (outgoing_tx, outgoing_rx) = {
+                    let bandwidth = peer_info.bandwidth.clone();
+                    let GossipQueueConfig { capacity, policy } = gossip.outbound();
+                    iota_gossip::channel(capacity, policy, move || bandwidth.record_dropped_outbound())
+                };
+
+                let limits = rate_limiter.peer_limits(peer_info.relation);
+
+                iota_gossip::start_incoming_processor(
+                    peer_id,
+                    reader,
+                    incoming_tx,
+                    outgoing_tx.clone(),
+                    senders.internal_events.clone(),
+                    peer_info.bandwidth.clone(),
+                    limits.inbound,
+                );
+                iota_gossip::start_outgoing_processor(
+                    peer_id,
+                    writer,
+                    outgoing_rx,
+                    senders.internal_events.clone(),
+                    peer_info.bandwidth.clone(),
+                    limits.outbound,
+                    gossip.coalescing(),
+                );
 
                 // We store a clone of the gossip send channel in order to send a shutdown signal.
                 let _ = peerlist.update_state(&peer_id, |state| state.set_connected(outgoing_tx.clone()));
@@ -414,6 +693,8 @@ async fn process_internal_event(
                             info: peer_info.clone(),
                         })
                         .map_err(|_| Error::SendingEventFailed)?;
+
+                    send_buffered_identity(senders, peer_id, &peer_info)?;
                 }
 
                 info!(
@@ -433,6 +714,8 @@ async fn process_internal_event(
                     })
                     .map_err(|_| Error::SendingEventFailed)?;
             } else {
+                peerlist.remove_identified(&peer_id);
+
                 // Panic:
                 // This branch handles the error case, so unwrapping it is fine.
                 debug!("{}", accepted.unwrap_err());
@@ -443,6 +726,21 @@ async fn process_internal_event(
     Ok(())
 }
 
+// Publishes the capabilities of a peer that identified itself before being added.
+fn send_buffered_identity(senders: &Senders, peer_id: PeerId, info: &PeerInfo) -> Result<(), Error> {
+    if info.capabilities.agent_version.is_some() {
+        senders
+            .events
+            .send(Event::PeerIdentified {
+                peer_id,
+                capabilities: info.capabilities.clone(),
+            })
+            .map_err(|_| Error::SendingEventFailed)?;
+    }
+
+    Ok(())
+}
+
 async fn add_peer(
     peer_id: PeerId,
     address: Multiaddr,
@@ -451,11 +749,7 @@ async fn add_peer(
     senders: &Senders,
     peerlist: &PeerList,
 ) -> Result<(), Error> {
-    let peer_info = PeerInfo {
-        address,
-        alias,
-        relation,
-    };
+    let peer_info = PeerInfo::new(address, alias, relation);
 
     let mut peerlist = peerlist.0.write().await;
 
@@ -471,10 +765,13 @@ async fn add_peer(
 
             senders
                 .events
-                .send(Event::PeerAdded { peer_id, info })
+                .send(Event::PeerAdded {
+                    peer_id,
+                    info: info.clone(),
+                })
                 .map_err(|_| Error::SendingEventFailed)?;
 
-            Ok(())
+            send_buffered_identity(senders, peer_id, &info)
         }
         Err((peer_id, peer_info, mut e)) => {
             // NB: This fixes an edge case where an in fact known peer connects before being added by the
@@ -486,17 +783,25 @@ async fn add_peer(
             // thoroughly in a live setup to really be sure.
 
             if matches!(e, PeerError::PeerIsDuplicate(_)) {
-                match peerlist.update_info(&peer_id, |info| *info = peer_info.clone()) {
+                // Keep the traffic and capabilities recorded while the peer was connected as unknown.
+                let updated = peerlist.update_info(&peer_id, |info| {
+                    info.address = peer_info.address.clone();
+                    info.alias = peer_info.alias.clone();
+                    info.relation = peer_info.relation;
+                });
+
+                match updated {
                     Ok(()) => {
+                        // Panic:
+                        // We just updated the peer_id so unwrapping here is fine.
+                        let info = peerlist.info(&peer_id).unwrap();
+
                         // We no longer need to hold the lock.
                         drop(peerlist);
 
                         senders
                             .events
-                            .send(Event::PeerAdded {
-                                peer_id,
-                                info: peer_info,
-                            })
+                            .send(Event::PeerAdded { peer_id, info })
                             .map_err(|_| Error::SendingEventFailed)?;
 
                         return Ok(());
@@ -556,6 +861,92 @@ async fn remove_peer(peer_id: PeerId, senders: &Senders, peerlist: &PeerList) ->
     }
 }
 
+async fn reload_peering(config: PeeringConfig, senders: &Senders, peerlist: &PeerList) -> Result<(), Error> {
+    let diff = peerlist.0.read().await.diff_static_peers(&config.peers);
+
+    info!(
+        "Reloading static peers: {} added, {} updated, {} removed.",
+        diff.added.len(),
+        diff.updated.len(),
+        diff.removed.len()
+    );
+
+    // A peer that changed in the meantime must not prevent applying the changes to the others.
+
+    for peer_id in diff.removed {
+        if let Err(e) = remove_peer(peer_id, senders, peerlist).await {
+            warn!("Removing {} failed. Cause: {}", alias!(peer_id), e);
+        }
+    }
+
+    for Peer {
+        peer_id,
+        multiaddr,
+        alias,
+    } in diff.added
+    {
+        let alias = alias.unwrap_or_else(|| alias!(peer_id).to_string());
+
+        if let Err(e) = add_peer(peer_id, multiaddr, alias, PeerRelation::Known, senders, peerlist).await {
+            warn!("Adding {} failed. Cause: {}", alias!(peer_id), e);
+        }
+    }
+
+    for Peer {
+        peer_id,
+        multiaddr,
+        alias,
+    } in diff.updated
+    {
+        let alias = alias.unwrap_or_else(|| alias!(peer_id).to_string());
+        let mut previous_relation = None;
+        let mut info_changed = false;
+
+        let update = {
+            let mut peerlist = peerlist.0.write().await;
+
+            peerlist
+                .update_info(&peer_id, |info| {
+                    if !info.relation.is_known() {
+                        previous_relation.replace(info.relation);
+                        info.relation.set_known();
+                    }
+                    if info.address != multiaddr || info.alias != alias {
+                        info.address = multiaddr.clone();
+                        info.alias = alias.clone();
+                        info_changed = true;
+                    }
+                })
+                .and_then(|_| peerlist.info(&peer_id))
+        };
+
+        match update {
+            Ok(info) => {
+                if let Some(from) = previous_relation {
+                    senders
+                        .events
+                        .send(Event::PeerRelationChanged {
+                            peer_id,
+                            from,
+                            to: PeerRelation::Known,
+                        })
+                        .map_err(|_| Error::SendingEventFailed)?;
+                }
+
+                if info_changed {
+                    senders
+                        .events
+                        .send(Event::PeerUpdated { peer_id, info })
+                        .map_err(|_| Error::SendingEventFailed)?;
+                }
+            }
+            Err(e) => warn!("Updating {} failed. Cause: {}", alias!(peer_id), e),
+        }
+    }
+
+    Ok(())
+}
+
 async fn disconnect_peer(peer_id: PeerId, senders: &Senders, peerlist: &PeerList) -> Result<(), Error> {
     let state_update = peerlist
         .0
@@ -573,9 +964,9 @@ async fn disconnect_peer(peer_id: PeerId, senders: &Senders, peerlist: &PeerList
                 .send(Event::PeerDisconnected { peer_id })
                 .map_err(|_| Error::SendingEventFailed)?;
 
-            // Try to send the shutdown signal. It has to be a Vec<u8>, but it doesn't have to allocate.
+            // Try to send the shutdown signal.
             // We ignore the potential error in case that peer disconnected from us already in the meantime.
-            let _ = gossip_sender.send(Vec::new());
+            let _ = gossip_sender.shutdown();
 
             Ok(())
         }
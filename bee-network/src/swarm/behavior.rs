@@ -5,38 +5,122 @@ use super::protocols::iota_gossip::{IotaGossipEvent, IotaGossipProtocol};
 
 use crate::{
     alias,
+    config::DiscoveryConfig,
+    discovery::{
+        self,
+        candidates::{Candidate, Candidates},
+    },
+    init::global::network_id,
+    peer::capabilities::GossipVersion,
     service::event::{InternalEvent, InternalEventSender},
 };
 
 use libp2p::{
     identify::{Identify, IdentifyConfig, IdentifyEvent},
     identity::PublicKey,
-    swarm::NetworkBehaviourEventProcess,
-    NetworkBehaviour,
+    kad::{store::MemoryStore, Kademlia, KademliaConfig, KademliaEvent, QueryResult},
+    swarm::{toggle::Toggle, NetworkBehaviourEventProcess},
+    NetworkBehaviour, PeerId,
 };
 use log::*;
 
 const IOTA_PROTOCOL_VERSION: &str = "iota/0.1.0";
+const AGENT_VERSION: &str = concat!("bee-network/", env!("CARGO_PKG_VERSION"));
 
 #[derive(NetworkBehaviour)]
 pub struct SwarmBehavior {
     identify: Identify,
     gossip: IotaGossipProtocol,
+    kademlia: Toggle<Kademlia<MemoryStore>>,
+    #[behaviour(ignore)]
+    gossip_protocol_names: Vec<String>,
+    #[behaviour(ignore)]
+    discovery_protocol_name: String,
+    #[behaviour(ignore)]
+    candidates: Candidates,
     #[behaviour(ignore)]
     internal_sender: InternalEventSender,
 }
 
 impl SwarmBehavior {
-    pub fn new(local_pk: PublicKey, internal_sender: InternalEventSender) -> Self {
+    pub fn new(
+        local_pk: PublicKey,
+        internal_sender: InternalEventSender,
+        discovery: &DiscoveryConfig,
+        gossip_versions: &[GossipVersion],
+    ) -> Self {
+        let local_id = local_pk.clone().into_peer_id();
         let protocol_version = IOTA_PROTOCOL_VERSION.to_string();
-        let config = IdentifyConfig::new(protocol_version, local_pk);
+        let config = IdentifyConfig::new(protocol_version, local_pk).with_agent_version(AGENT_VERSION.to_string());
+
+        let gossip = IotaGossipProtocol::new(gossip_versions);
+        let gossip_protocol_names = gossip.protocol_names();
+        let discovery_protocol_name = discovery::protocol_name(network_id());
+
+        let kademlia = if discovery.enabled() {
+            let mut config = KademliaConfig::default();
+            config.set_protocol_name(discovery_protocol_name.clone().into_bytes());
+
+            let mut kademlia = Kademlia::with_config(local_id, MemoryStore::new(local_id), config);
+
+            for (peer_id, addr) in discovery.entry_nodes() {
+                kademlia.add_address(peer_id, addr.clone());
+            }
+
+            Some(kademlia)
+        } else {
+            None
+        };
 
         Self {
             identify: Identify::new(config),
-            gossip: IotaGossipProtocol::new(),
+            gossip,
+            kademlia: kademlia.into(),
+            gossip_protocol_names,
+            discovery_protocol_name,
+            candidates: Candidates::new(discovery.entry_nodes().iter().map(|(peer_id, _)| *peer_id)),
             internal_sender,
         }
     }
+
+    /// Joins the discovery network through the configured entry nodes.
+    pub fn bootstrap(&mut self) {
+        if let Some(kademlia) = self.kademlia.as_mut() {
+            if let Err(e) = kademlia.bootstrap() {
+                warn!("Peer discovery bootstrap failed. Cause: {:?}.", e);
+            }
+        }
+    }
+
+    /// Queries the discovery network for new candidates by looking up a random peer id.
+    pub fn discover(&mut self) {
+        if let Some(kademlia) = self.kademlia.as_mut() {
+            kademlia.get_closest_peers(PeerId::random());
+        }
+    }
+
+    fn report_candidate(&self, candidate: Candidate) {
+        let Candidate {
+            peer_id,
+            peer_addr,
+            reason,
+        } = candidate;
+
+        debug!(
+            "Discovered candidate {} at {} ({}).",
+            alias!(peer_id),
+            peer_addr,
+            reason
+        );
+
+        if let Err(e) = self.internal_sender.send(InternalEvent::PeerDiscovered {
+            peer_id,
+            peer_addr,
+            reason,
+        }) {
+            warn!("Send event error for discovered peer {}. Cause: {}", alias!(peer_id), e);
+        }
+    }
 }
 
 impl NetworkBehaviourEventProcess<IdentifyEvent> for SwarmBehavior {
@@ -49,7 +133,43 @@ impl NetworkBehaviourEventProcess<IdentifyEvent> for SwarmBehavior {
                     info.observed_addr,
                 );
 
-                // TODO: log supported protocols by the peer (info.protocols)
+                trace!("{} supports protocols: {:?}.", alias!(peer_id), info.protocols);
+
+                if let Err(e) = self.internal_sender.send(InternalEvent::PeerIdentified {
+                    peer_id,
+                    agent_version: info.agent_version.clone(),
+                    protocols: info.protocols.clone(),
+                }) {
+                    warn!("Send event error for identified peer {}. Cause: {}", alias!(peer_id), e);
+                }
+
+                // Only peers that take part in the peer discovery of the same network are considered as candidates.
+                if let Some(kademlia) = self.kademlia.as_mut() {
+                    if info.protocols.contains(&self.discovery_protocol_name) {
+                        for addr in info.listen_addrs.iter() {
+                            kademlia.add_address(&peer_id, addr.clone());
+                        }
+
+                        // At least one version of the gossip protocol must be supported by both sides.
+                        let verified = info.protocol_version == IOTA_PROTOCOL_VERSION
+                            && self
+                                .gossip_protocol_names
+                                .iter()
+                                .any(|name| info.protocols.contains(name));
+
+                        if !verified {
+                            debug!(
+                                "Discovered peer {} failed verification: protocol version {}.",
+                                alias!(peer_id),
+                                info.protocol_version
+                            );
+                        }
+
+                        if let Some(candidate) = self.candidates.verify(peer_id, verified) {
+                            self.report_candidate(candidate);
+                        }
+                    }
+                }
             }
             IdentifyEvent::Sent { peer_id } => {
                 trace!("Sent Identify request to {}.", alias!(peer_id));
@@ -64,6 +184,43 @@ impl NetworkBehaviourEventProcess<IdentifyEvent> for SwarmBehavior {
     }
 }
 
+impl NetworkBehaviourEventProcess<KademliaEvent> for SwarmBehavior {
+    fn inject_event(&mut self, event: KademliaEvent) {
+        match event {
+            KademliaEvent::RoutingUpdated { peer, addresses, .. } => {
+                trace!("Discovery routing updated for {}.", alias!(peer));
+
+                if let Some(candidate) = self.candidates.insert_addr(peer, addresses.first().clone()) {
+                    self.report_candidate(candidate);
+                }
+            }
+            KademliaEvent::QueryResult { result, .. } => match result {
+                QueryResult::Bootstrap(Ok(ok)) => {
+                    trace!("Discovery bootstrap: {} remaining.", ok.num_remaining);
+                }
+                QueryResult::Bootstrap(Err(e)) => {
+                    warn!("Discovery bootstrap error: Cause: {:?}.", e);
+                }
+                QueryResult::GetClosestPeers(Ok(ok)) => {
+                    debug!("Discovery query returned {} peers.", ok.peers.len());
+
+                    // Peers that were not selected before, e.g. because the quota was reached, get another chance.
+                    for peer_id in ok.peers.iter() {
+                        if let Some(candidate) = self.candidates.get(peer_id) {
+                            self.report_candidate(candidate);
+                        }
+                    }
+                }
+                QueryResult::GetClosestPeers(Err(e)) => {
+                    debug!("Discovery query error: Cause: {:?}.", e);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
 impl NetworkBehaviourEventProcess<IotaGossipEvent> for SwarmBehavior {
     fn inject_event(&mut self, event: IotaGossipEvent) {
         match event {
@@ -78,14 +235,20 @@ impl NetworkBehaviourEventProcess<IotaGossipEvent> for SwarmBehavior {
                 peer_addr,
                 origin,
                 substream,
+                version,
             } => {
-                debug!("Successfully negotiated IOTA gossip protocol with {}.", alias!(peer_id));
+                debug!(
+                    "Successfully negotiated IOTA gossip protocol version {} with {}.",
+                    version,
+                    alias!(peer_id)
+                );
 
                 if let Err(e) = self.internal_sender.send(InternalEvent::ProtocolEstablished {
                     peer_id,
                     peer_addr,
                     origin,
                     substream,
+                    version,
                 }) {
                     warn!(
                         "Send event error for {} after successfully established IOTA gossip protocol. Cause: {}",
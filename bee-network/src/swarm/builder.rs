@@ -3,19 +3,24 @@
 
 use super::{behavior::SwarmBehavior, error::Error};
 
-use crate::service::event::InternalEventSender;
+use crate::{
+    config::{DiscoveryConfig, TransportKind},
+    peer::capabilities::GossipVersion,
+    service::event::InternalEventSender,
+};
 
 use libp2p::{
     core::{
         connection::ConnectionLimits,
+        transport::{MemoryTransport, OptionalTransport},
         upgrade::{self, SelectUpgrade},
     },
     dns, identity, mplex, noise,
     swarm::SwarmBuilder,
-    tcp, yamux, Swarm, Transport,
+    tcp, websocket, yamux, Swarm, Transport,
 };
 
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
 const MAX_CONNECTIONS_PER_PEER: u32 = 1;
 const DEFAULT_CONNECTION_TIMEOUT_SECS: u64 = 10;
@@ -23,6 +28,9 @@ const DEFAULT_CONNECTION_TIMEOUT_SECS: u64 = 10;
 pub fn build_swarm(
     local_keys: &identity::Keypair,
     internal_sender: InternalEventSender,
+    discovery: &DiscoveryConfig,
+    transports: &HashSet<TransportKind>,
+    gossip_versions: &[GossipVersion],
 ) -> Result<Swarm<SwarmBehavior>, Error> {
     let local_pk = local_keys.public();
     let local_id = local_pk.clone().into_peer_id();
@@ -35,28 +43,36 @@ pub fn build_swarm(
     let mpx_config = mplex::MplexConfig::default();
     let ymx_config = yamux::YamuxConfig::default();
 
-    let transport = if cfg!(test) {
-        use libp2p_core::transport::MemoryTransport;
-
-        MemoryTransport::default()
-            .upgrade(upgrade::Version::V1Lazy)
-            .authenticate(noi_config.into_authenticated())
-            .multiplex(SelectUpgrade::new(ymx_config, mpx_config))
-            .timeout(Duration::from_secs(DEFAULT_CONNECTION_TIMEOUT_SECS))
-            .boxed()
+    let memory = if transports.contains(&TransportKind::Memory) {
+        Some(MemoryTransport::default())
     } else {
-        let tcp_config = tcp::TokioTcpConfig::new().nodelay(true).port_reuse(true);
-        let dns_config = dns::TokioDnsConfig::system(tcp_config)?;
+        None
+    };
 
-        dns_config
-            .upgrade(upgrade::Version::V1Lazy)
-            .authenticate(noi_config.into_authenticated())
-            .multiplex(SelectUpgrade::new(ymx_config, mpx_config))
-            .timeout(Duration::from_secs(DEFAULT_CONNECTION_TIMEOUT_SECS))
-            .boxed()
+    // WebSocket runs on top of TCP, so the TCP transport is also built if only WebSocket is enabled.
+    let dns_tcp = if transports.contains(&TransportKind::Tcp) || transports.contains(&TransportKind::WebSocket) {
+        let tcp_config = tcp::TokioTcpConfig::new().nodelay(true).port_reuse(true);
+        Some(dns::TokioDnsConfig::system(tcp_config)?)
+    } else {
+        None
     };
 
-    let behavior = SwarmBehavior::new(local_pk, internal_sender);
+    let ws = dns_tcp
+        .clone()
+        .filter(|_| transports.contains(&TransportKind::WebSocket))
+        .map(websocket::WsConfig::new);
+    let dns_tcp = dns_tcp.filter(|_| transports.contains(&TransportKind::Tcp));
+
+    let transport = OptionalTransport::from(memory)
+        .or_transport(OptionalTransport::from(dns_tcp))
+        .or_transport(OptionalTransport::from(ws))
+        .upgrade(upgrade::Version::V1Lazy)
+        .authenticate(noi_config.into_authenticated())
+        .multiplex(SelectUpgrade::new(ymx_config, mpx_config))
+        .timeout(Duration::from_secs(DEFAULT_CONNECTION_TIMEOUT_SECS))
+        .boxed();
+
+    let behavior = SwarmBehavior::new(local_pk, internal_sender, discovery, gossip_versions);
     let limits = ConnectionLimits::default().with_max_established_per_peer(Some(MAX_CONNECTIONS_PER_PEER));
 
     let swarm = SwarmBuilder::new(transport, behavior, local_id)
@@ -0,0 +1,377 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use futures::{future::poll_fn, Stream};
+use serde::Deserialize;
+use tokio::sync::Notify;
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+};
+
+/// Describes what happens to a message sent into a full gossip queue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// The sender waits until there is space in the queue.
+    Block,
+    /// The oldest queued message is dropped to make space for the new one.
+    DropOldest,
+    /// The new message is dropped.
+    DropNewest,
+    /// The new message is dropped and the connection to the peer is shut down.
+    Disconnect,
+}
+
+/// Messages that control the gossip stream instead of being written to it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GossipControl {
+    /// Ends the gossip stream and drops the connection to the peer.
+    Shutdown,
+}
+
+/// An item received from a gossip queue.
+#[derive(Debug, Eq, PartialEq)]
+pub enum GossipItem {
+    /// A gossip message.
+    Message(Vec<u8>),
+    /// A control message, which always takes precedence over queued gossip messages.
+    Control(GossipControl),
+}
+
+/// Gossip queue errors.
+#[derive(Debug, Eq, PartialEq, thiserror::Error)]
+pub enum GossipError {
+    /// The receiving end of the queue was dropped or the queue was shut down.
+    #[error("Gossip queue closed.")]
+    Closed,
+    /// The queue is full and the message could not be sent without blocking.
+    #[error("Gossip queue full.")]
+    Full,
+    /// The queue is full and the message was dropped.
+    #[error("Gossip queue full, message dropped.")]
+    Dropped,
+    /// The queue is full and the connection is shut down.
+    #[error("Gossip queue full, disconnecting.")]
+    Disconnected,
+}
+
+struct State {
+    queue: VecDeque<Vec<u8>>,
+    control: Option<GossipControl>,
+    closed: bool,
+    num_senders: usize,
+    receiver_alive: bool,
+    receiver_waker: Option<Waker>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    not_full: Notify,
+    on_drop: Box<dyn Fn() + Send + Sync>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // Panic:
+        // The lock is never held across a panic, hence it can't be poisoned.
+        self.state.lock().expect("poisoned gossip queue lock")
+    }
+}
+
+fn wake_receiver(state: &mut State) {
+    if let Some(waker) = state.receiver_waker.take() {
+        waker.wake();
+    }
+}
+
+/// Creates a bounded gossip queue that holds at most `capacity` messages and applies `policy` when it is full.
+///
+/// `on_drop` is called for every message dropped due to the overflow policy.
+pub fn channel(
+    capacity: usize,
+    policy: OverflowPolicy,
+    on_drop: impl Fn() + Send + Sync + 'static,
+) -> (GossipSender, GossipReceiver) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: VecDeque::new(),
+            control: None,
+            closed: false,
+            num_senders: 1,
+            receiver_alive: true,
+            receiver_waker: None,
+        }),
+        // A queue without capacity would drop or block every message.
+        capacity: capacity.max(1),
+        policy,
+        not_full: Notify::new(),
+        on_drop: Box::new(on_drop),
+    });
+
+    (GossipSender { shared: shared.clone() }, GossipReceiver { shared })
+}
+
+/// The sending end of a gossip queue.
+pub struct GossipSender {
+    shared: Arc<Shared>,
+}
+
+impl GossipSender {
+    /// Sends a message, applying the overflow policy if the queue is full. Only the `Block` policy ever waits.
+    pub async fn send(&self, message: Vec<u8>) -> Result<(), GossipError> {
+        let mut message = message;
+
+        loop {
+            let not_full = self.shared.not_full.notified();
+
+            match self.push(message) {
+                Err((m, GossipError::Full)) => message = m,
+                res => return res.map_err(|(_, e)| e),
+            }
+
+            not_full.await;
+        }
+    }
+
+    /// Sends a message without waiting. With the `Block` policy a full queue results in [`GossipError::Full`].
+    pub fn try_send(&self, message: Vec<u8>) -> Result<(), GossipError> {
+        self.push(message).map_err(|(_, e)| e)
+    }
+
+    /// Sends the shutdown control message, which ends the gossip stream once received.
+    pub fn shutdown(&self) -> Result<(), GossipError> {
+        let mut state = self.shared.lock();
+
+        if !state.receiver_alive || state.closed {
+            return Err(GossipError::Closed);
+        }
+
+        state.control.replace(GossipControl::Shutdown);
+        state.closed = true;
+        wake_receiver(&mut state);
+        self.shared.not_full.notify_waiters();
+
+        Ok(())
+    }
+
+    /// Returns the number of queued messages.
+    pub fn len(&self) -> usize {
+        self.shared.lock().queue.len()
+    }
+
+    /// Returns whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn push(&self, message: Vec<u8>) -> Result<(), (Vec<u8>, GossipError)> {
+        let mut state = self.shared.lock();
+
+        if !state.receiver_alive || state.closed {
+            return Err((message, GossipError::Closed));
+        }
+
+        if state.queue.len() >= self.shared.capacity {
+            match self.shared.policy {
+                OverflowPolicy::Block => return Err((message, GossipError::Full)),
+                OverflowPolicy::DropOldest => {
+                    state.queue.pop_front();
+                    (self.shared.on_drop)();
+                }
+                OverflowPolicy::DropNewest => {
+                    (self.shared.on_drop)();
+                    return Err((message, GossipError::Dropped));
+                }
+                OverflowPolicy::Disconnect => {
+                    (self.shared.on_drop)();
+                    state.control.replace(GossipControl::Shutdown);
+                    state.closed = true;
+                    wake_receiver(&mut state);
+                    self.shared.not_full.notify_waiters();
+                    return Err((message, GossipError::Disconnected));
+                }
+            }
+        }
+
+        state.queue.push_back(message);
+        wake_receiver(&mut state);
+
+        Ok(())
+    }
+}
+
+impl Clone for GossipSender {
+    fn clone(&self) -> Self {
+        self.shared.lock().num_senders += 1;
+
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for GossipSender {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+
+        state.num_senders -= 1;
+
+        if state.num_senders == 0 {
+            wake_receiver(&mut state);
+        }
+    }
+}
+
+impl fmt::Debug for GossipSender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GossipSender")
+            .field("capacity", &self.shared.capacity)
+            .field("policy", &self.shared.policy)
+            .finish()
+    }
+}
+
+/// The receiving end of a gossip queue.
+///
+/// As a [`Stream`] it yields the gossip messages, and ends when all senders were dropped or the queue was shut down.
+pub struct GossipReceiver {
+    shared: Arc<Shared>,
+}
+
+impl GossipReceiver {
+    /// Receives the next item, or `None` if all senders were dropped.
+    pub async fn recv(&mut self) -> Option<GossipItem> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    /// Polls for the next item, or `None` if all senders were dropped.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<GossipItem>> {
+        let mut state = self.shared.lock();
+
+        if let Some(control) = state.control.take() {
+            return Poll::Ready(Some(GossipItem::Control(control)));
+        }
+
+        if let Some(message) = state.queue.pop_front() {
+            drop(state);
+            self.shared.not_full.notify_one();
+
+            return Poll::Ready(Some(GossipItem::Message(message)));
+        }
+
+        if state.num_senders == 0 {
+            return Poll::Ready(None);
+        }
+
+        state.receiver_waker.replace(cx.waker().clone());
+
+        Poll::Pending
+    }
+}
+
+impl Stream for GossipReceiver {
+    type Item = Vec<u8>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match self.get_mut().poll_recv(cx) {
+            Poll::Ready(Some(GossipItem::Message(message))) => Poll::Ready(Some(message)),
+            Poll::Ready(Some(GossipItem::Control(GossipControl::Shutdown))) | Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for GossipReceiver {
+    fn drop(&mut self) {
+        self.shared.lock().receiver_alive = false;
+        // Wake up all blocked senders, so they can notice that the queue is closed.
+        self.shared.not_full.notify_waiters();
+    }
+}
+
+impl fmt::Debug for GossipReceiver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GossipReceiver")
+            .field("capacity", &self.shared.capacity)
+            .field("policy", &self.shared.policy)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::StreamExt;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn counting_channel(capacity: usize, policy: OverflowPolicy) -> (GossipSender, GossipReceiver, Arc<AtomicUsize>) {
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let counter = dropped.clone();
+        let (tx, rx) = channel(capacity, policy, move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+
+        (tx, rx, dropped)
+    }
+
+    #[tokio::test]
+    async fn drop_oldest() {
+        let (tx, mut rx, dropped) = counting_channel(2, OverflowPolicy::DropOldest);
+
+        for i in 0..3u8 {
+            tx.send(vec![i]).await.unwrap();
+        }
+
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+        assert_eq!(rx.next().await, Some(vec![1]));
+        assert_eq!(rx.next().await, Some(vec![2]));
+    }
+
+    #[tokio::test]
+    async fn drop_newest() {
+        let (tx, mut rx, dropped) = counting_channel(1, OverflowPolicy::DropNewest);
+
+        tx.send(vec![0]).await.unwrap();
+        assert_eq!(tx.send(vec![1]).await, Err(GossipError::Dropped));
+
+        assert_eq!(dropped.load(Ordering::Relaxed), 1);
+        assert_eq!(rx.next().await, Some(vec![0]));
+    }
+
+    #[tokio::test]
+    async fn disconnect() {
+        let (tx, mut rx, _) = counting_channel(1, OverflowPolicy::Disconnect);
+
+        tx.send(vec![0]).await.unwrap();
+        assert_eq!(tx.send(vec![1]).await, Err(GossipError::Disconnected));
+
+        assert_eq!(rx.recv().await, Some(GossipItem::Control(GossipControl::Shutdown)));
+        assert_eq!(tx.send(vec![2]).await, Err(GossipError::Closed));
+    }
+
+    #[tokio::test]
+    async fn block() {
+        let (tx, mut rx, dropped) = counting_channel(1, OverflowPolicy::Block);
+
+        tx.send(vec![0]).await.unwrap();
+        assert_eq!(tx.try_send(vec![1]), Err(GossipError::Full));
+
+        let blocked = tokio::spawn(async move { tx.send(vec![1]).await });
+
+        assert_eq!(rx.next().await, Some(vec![0]));
+        assert_eq!(blocked.await.unwrap(), Ok(()));
+        assert_eq!(rx.next().await, Some(vec![1]));
+        // All senders are gone.
+        assert_eq!(rx.next().await, None);
+        assert_eq!(dropped.load(Ordering::Relaxed), 0);
+    }
+}
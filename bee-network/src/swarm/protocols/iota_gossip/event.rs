@@ -1,7 +1,7 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::network::origin::Origin;
+use crate::{network::origin::Origin, peer::capabilities::GossipVersion};
 
 use libp2p::{
     swarm::{NegotiatedSubstream, ProtocolsHandlerUpgrErr},
@@ -25,6 +25,7 @@ pub enum IotaGossipEvent {
         peer_addr: Multiaddr,
         origin: Origin,
         substream: Box<NegotiatedSubstream>,
+        version: GossipVersion,
     },
 
     /// An error occured during negotiation.
@@ -47,7 +48,10 @@ pub enum IotaGossipHandlerEvent {
     SentUpgradeRequest { to: PeerId },
 
     /// Successfully upgraded to the IOTA gossip protocol.
-    UpgradeCompleted { substream: Box<NegotiatedSubstream> },
+    UpgradeCompleted {
+        substream: Box<NegotiatedSubstream>,
+        version: GossipVersion,
+    },
 
     /// An errror occured during the upgrade.
     UpgradeError {
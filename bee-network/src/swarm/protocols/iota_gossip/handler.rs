@@ -3,7 +3,7 @@
 
 use super::{event::IotaGossipHandlerEvent, id::IotaGossipIdentifier, upgrade::IotaGossipProtocolUpgrade};
 
-use crate::network::origin::Origin;
+use crate::{network::origin::Origin, peer::capabilities::GossipVersion};
 
 use libp2p::{
     core::upgrade::OutboundUpgrade,
@@ -26,8 +26,8 @@ use std::{
 };
 
 pub struct GossipProtocolHandler {
-    /// Exchanged protocol information necessary during negotiation.
-    info: IotaGossipIdentifier,
+    /// Exchanged protocol information necessary during negotiation, one per supported version.
+    info: Vec<IotaGossipIdentifier>,
 
     /// Keep alive setting.
     keep_alive: KeepAlive,
@@ -42,7 +42,7 @@ pub struct IotaGossipHandlerInEvent {
 }
 
 impl GossipProtocolHandler {
-    pub fn new(info: IotaGossipIdentifier) -> Self {
+    pub fn new(info: Vec<IotaGossipIdentifier>) -> Self {
         Self {
             info,
             keep_alive: KeepAlive::Yes,
@@ -98,12 +98,17 @@ impl ProtocolsHandler for GossipProtocolHandler {
     /// **libp2p docs**:
     ///
     /// Injects the output of a successful upgrade on a new inbound substream.
-    fn inject_fully_negotiated_inbound(&mut self, new_inbound: NegotiatedSubstream, _: Self::InboundOpenInfo) {
+    fn inject_fully_negotiated_inbound(
+        &mut self,
+        (new_inbound, version): (NegotiatedSubstream, GossipVersion),
+        _: Self::InboundOpenInfo,
+    ) {
         let negotiated_inbound = ProtocolsHandlerEvent::Custom(IotaGossipHandlerEvent::UpgradeCompleted {
             substream: Box::new(new_inbound),
+            version,
         });
 
-        debug!("gossip handler: fully negotiated inbound.");
+        debug!("gossip handler: fully negotiated inbound (version {}).", version);
 
         self.events.push_back(negotiated_inbound);
     }
@@ -114,12 +119,17 @@ impl ProtocolsHandler for GossipProtocolHandler {
     ///
     /// The second argument is the information that was previously passed to
     /// [`ProtocolsHandlerEvent::OutboundSubstreamRequest`].
-    fn inject_fully_negotiated_outbound(&mut self, new_outbound: NegotiatedSubstream, _: Self::OutboundOpenInfo) {
+    fn inject_fully_negotiated_outbound(
+        &mut self,
+        (new_outbound, version): (NegotiatedSubstream, GossipVersion),
+        _: Self::OutboundOpenInfo,
+    ) {
         let negotiated_outbound = ProtocolsHandlerEvent::Custom(IotaGossipHandlerEvent::UpgradeCompleted {
             substream: Box::new(new_outbound),
+            version,
         });
 
-        debug!("gossip handler: fully negotiated outbound.");
+        debug!("gossip handler: fully negotiated outbound (version {}).", version);
 
         self.events.push_back(negotiated_outbound);
     }
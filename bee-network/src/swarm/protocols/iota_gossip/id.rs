@@ -1,27 +1,39 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::peer::capabilities::GossipVersion;
+
 use libp2p_core::ProtocolName;
 
 use std::fmt;
 
 #[derive(Debug, Clone)]
-pub struct IotaGossipIdentifier(String);
+pub struct IotaGossipIdentifier {
+    name: String,
+    version: GossipVersion,
+}
 
 impl IotaGossipIdentifier {
-    pub fn new(name: impl AsRef<str>, network_id: u64, version: impl AsRef<str>) -> Self {
-        Self(format!("/{}/{}/{}", name.as_ref(), network_id, version.as_ref()))
+    pub fn new(name: impl AsRef<str>, network_id: u64, version: GossipVersion) -> Self {
+        Self {
+            name: format!("/{}/{}/{}", name.as_ref(), network_id, version),
+            version,
+        }
+    }
+
+    pub fn version(&self) -> GossipVersion {
+        self.version
     }
 }
 
 impl ProtocolName for IotaGossipIdentifier {
     fn protocol_name(&self) -> &[u8] {
-        self.0.as_bytes()
+        self.name.as_bytes()
     }
 }
 
 impl fmt::Display for IotaGossipIdentifier {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.name)
     }
 }
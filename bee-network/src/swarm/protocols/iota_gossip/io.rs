@@ -1,50 +1,66 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use super::{
+    channel::{GossipControl, GossipError, GossipItem, GossipReceiver, GossipSender},
+    limit::Limits,
+};
+
 use crate::{
     alias,
+    config::CoalescingConfig,
+    peer::bandwidth::Bandwidth,
     service::event::{InternalEvent, InternalEventSender},
 };
 
 use futures::{
     io::{BufReader, BufWriter, ReadHalf, WriteHalf},
-    AsyncReadExt, AsyncWriteExt, StreamExt,
+    AsyncReadExt, AsyncWrite, AsyncWriteExt,
 };
 use libp2p::{swarm::NegotiatedSubstream, PeerId};
 use log::*;
-use tokio::sync::mpsc;
-use tokio_stream::wrappers::UnboundedReceiverStream;
-
-const MSG_BUFFER_LEN: usize = 32768;
+use tokio::time::{timeout_at, Instant};
 
-/// A type alias for an unbounded channel sender.
-pub type GossipSender = mpsc::UnboundedSender<Vec<u8>>;
+use std::time::Duration;
 
-/// A type alias for an unbounded channel receiver.
-pub type GossipReceiver = UnboundedReceiverStream<Vec<u8>>;
-
-pub fn channel() -> (GossipSender, GossipReceiver) {
-    let (sender, receiver) = mpsc::unbounded_channel();
-    (sender, UnboundedReceiverStream::new(receiver))
-}
+const MSG_BUFFER_LEN: usize = 32768;
 
 pub fn start_incoming_processor(
     peer_id: PeerId,
     mut reader: BufReader<ReadHalf<Box<NegotiatedSubstream>>>,
     incoming_tx: GossipSender,
+    outgoing_tx: GossipSender,
     internal_event_sender: InternalEventSender,
+    bandwidth: Bandwidth,
+    limits: Limits,
 ) {
     tokio::spawn(async move {
         let mut msg_buf = vec![0u8; MSG_BUFFER_LEN];
 
         loop {
             if let Some(len) = (&mut reader).read(&mut msg_buf).await.ok().filter(|len| *len > 0) {
-                if incoming_tx.send(msg_buf[..len].to_vec()).is_err() {
-                    debug!("gossip-in: receiver dropped locally.");
+                bandwidth.record_received(len);
+
+                // Not reading from the stream while throttled lets the transport apply backpressure on the remote.
+                limits.throttle(len).await;
 
-                    // The receiver of this channel was dropped, maybe due to a shutdown. There is nothing we can do to
-                    // salvage this situation, hence we drop the connection.
-                    break;
+                match incoming_tx.send(msg_buf[..len].to_vec()).await {
+                    // Dropped messages are accounted for by the channel.
+                    Ok(()) | Err(GossipError::Dropped) => {}
+                    Err(GossipError::Disconnected) => {
+                        debug!("gossip-in: receiver overflowed locally.");
+
+                        // The receiver can't keep up with this peer, hence we tell the writer to drop the connection.
+                        let _ = outgoing_tx.shutdown();
+                        break;
+                    }
+                    Err(_) => {
+                        debug!("gossip-in: receiver dropped locally.");
+
+                        // The receiver of this channel was dropped, maybe due to a shutdown. There is nothing we can do
+                        // to salvage this situation, hence we drop the connection.
+                        break;
+                    }
                 }
             } else {
                 debug!("gossip-in: stream closed remotely.");
@@ -65,6 +81,7 @@ pub fn start_incoming_processor(
         // Reasons why this task might end:
         // (1) The remote dropped the TCP connection.
         // (2) The local dropped the gossip_in receiver channel.
+        // (3) The local couldn't keep up with the gossip_in channel and the overflow policy is to disconnect.
 
         debug!("gossip-in: exiting gossip-in processor for {}.", alias!(peer_id));
     });
@@ -73,44 +90,188 @@ pub fn start_incoming_processor(
 pub fn start_outgoing_processor(
     peer_id: PeerId,
     mut writer: BufWriter<WriteHalf<Box<NegotiatedSubstream>>>,
-    outgoing_rx: GossipReceiver,
+    mut outgoing_rx: GossipReceiver,
     internal_event_sender: InternalEventSender,
+    bandwidth: Bandwidth,
+    limits: Limits,
+    coalescing: CoalescingConfig,
 ) {
     tokio::spawn(async move {
-        let mut outgoing_gossip_receiver = outgoing_rx.fuse();
-
-        // If the gossip sender dropped we end the connection.
-        while let Some(message) = outgoing_gossip_receiver.next().await {
-            // NB: Instead of polling another shutdown channel, we use an empty message
-            // to signal that we want to end the connection. We use this "trick" whenever the network
-            // receives the `DisconnectPeer` command to enforce that the connection will be dropped.
-
-            if message.is_empty() {
+        match write_gossip(&mut writer, &mut outgoing_rx, &bandwidth, &limits, &coalescing).await {
+            WriteEnd::Shutdown => {
                 debug!("gossip-out: received shutdown message.");
 
-                // NB: The network service will not shut down before it has received the `ConnectionDropped` event from
-                // all once connected peers, hence if the following send fails, then it must be
-                // considered a bug.
+                // NB: The network service will not shut down before it has received the `ProtocolDropped` event from
+                // all once connected peers, hence if the following send fails, then it must be considered a bug.
 
                 internal_event_sender
                     .send(InternalEvent::ProtocolDropped { peer_id })
                     .expect("The service must not shutdown as long as there are gossip tasks running.");
-
-                break;
-            }
-
-            // If sending to the stream fails we end the connection.
-            // TODO: buffer for x milliseconds before flushing.
-            if (&mut writer).write_all(&message).await.is_err() || (&mut writer).flush().await.is_err() {
-                debug!("gossip-out: stream closed remotely");
-                break;
             }
+            WriteEnd::Failed => debug!("gossip-out: stream closed remotely"),
+            WriteEnd::Closed => debug!("gossip-out: all senders dropped locally."),
         }
 
         // Reasons why this task might end:
-        // (1) The local send the shutdown message (len = 0)
+        // (1) The local sent the shutdown control message.
         // (2) The remote dropped the TCP connection.
+        // (3) All senders of the gossip_out channel were dropped.
 
         debug!("gossip-out: exiting gossip-out processor for {}.", alias!(peer_id));
     });
 }
+
+/// Describes why [`write_gossip`] returned.
+#[derive(Debug, Eq, PartialEq)]
+pub enum WriteEnd {
+    /// The shutdown control message was received.
+    Shutdown,
+    /// All senders of the queue were dropped.
+    Closed,
+    /// Writing to the stream failed.
+    Failed,
+}
+
+/// Writes the messages of a gossip queue to a stream until the queue is shut down or closed, or writing fails.
+///
+/// Without coalescing every message is flushed right away. With coalescing, messages are written into the buffer of the
+/// writer and flushed once `max_bytes` accumulated or the first unflushed message waited for `max_delay_millis`.
+pub async fn write_gossip<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    outgoing_rx: &mut GossipReceiver,
+    bandwidth: &Bandwidth,
+    limits: &Limits,
+    coalescing: &CoalescingConfig,
+) -> WriteEnd {
+    let max_delay = Duration::from_millis(coalescing.max_delay_millis());
+    // The number of written, but not yet flushed bytes, and until when they may stay unflushed.
+    let mut unflushed = 0;
+    let mut deadline = None;
+
+    loop {
+        let item = match deadline {
+            Some(flush_at) => match timeout_at(flush_at, outgoing_rx.recv()).await {
+                Ok(item) => item,
+                Err(_) => {
+                    if writer.flush().await.is_err() {
+                        return WriteEnd::Failed;
+                    }
+                    unflushed = 0;
+                    deadline = None;
+                    continue;
+                }
+            },
+            None => outgoing_rx.recv().await,
+        };
+
+        // The shutdown control message is sent whenever the network receives the `DisconnectPeer` command, or the
+        // gossip channels overflow with the `Disconnect` policy, to enforce that the connection will be dropped.
+        let message = match item {
+            Some(GossipItem::Message(message)) => message,
+            Some(GossipItem::Control(GossipControl::Shutdown)) => {
+                // Be nice and deliver what is already written, the connection is dropped anyway.
+                let _ = writer.flush().await;
+                return WriteEnd::Shutdown;
+            }
+            None => {
+                let _ = writer.flush().await;
+                return WriteEnd::Closed;
+            }
+        };
+
+        limits.throttle(message.len()).await;
+
+        if writer.write_all(&message).await.is_err() {
+            return WriteEnd::Failed;
+        }
+
+        bandwidth.record_sent(message.len());
+        unflushed += message.len();
+
+        if coalescing.enabled() && unflushed < coalescing.max_bytes() {
+            deadline.get_or_insert_with(|| Instant::now() + max_delay);
+        } else {
+            if writer.flush().await.is_err() {
+                return WriteEnd::Failed;
+            }
+            unflushed = 0;
+            deadline = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swarm::protocols::iota_gossip::{channel, OverflowPolicy};
+
+    use futures::io::Cursor;
+
+    use std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    /// A writer that counts the flushes of the messages it receives.
+    #[derive(Default)]
+    struct FlushCounter {
+        inner: Cursor<Vec<u8>>,
+        flushes: usize,
+    }
+
+    impl AsyncWrite for FlushCounter {
+        fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            Pin::new(&mut self.inner).poll_write(cx, buf)
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.flushes += 1;
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    async fn write_messages(coalescing: CoalescingConfig) -> FlushCounter {
+        let (tx, mut rx) = channel(16, OverflowPolicy::Block, || {});
+        let mut writer = FlushCounter::default();
+
+        for _ in 0..10 {
+            tx.send(vec![0u8; 100]).await.unwrap();
+        }
+        drop(tx);
+
+        let end = write_gossip(&mut writer, &mut rx, &Bandwidth::new(), &Limits::default(), &coalescing).await;
+
+        assert_eq!(end, WriteEnd::Closed);
+        assert_eq!(writer.inner.get_ref().len(), 1000);
+
+        writer
+    }
+
+    #[tokio::test]
+    async fn flush_every_message() {
+        let writer = write_messages(CoalescingConfig::default()).await;
+
+        // One flush per message, and a final one when the queue closes.
+        assert_eq!(writer.flushes, 11);
+    }
+
+    #[tokio::test]
+    async fn coalesce_messages() {
+        let writer = write_messages(
+            CoalescingConfig::build()
+                .with_enabled(true)
+                .with_max_bytes(500)
+                .with_max_delay_millis(1000)
+                .finish(),
+        )
+        .await;
+
+        // One flush per 500 bytes, and a final one when the queue closes.
+        assert_eq!(writer.flushes, 3);
+    }
+}
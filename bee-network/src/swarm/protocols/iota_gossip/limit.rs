@@ -0,0 +1,164 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{config::BandwidthConfig, peer::info::PeerRelation};
+
+use tokio::time::{sleep, Instant};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// A token bucket that is refilled with `rate` tokens (bytes) per second, and holds at most one second worth of tokens.
+///
+/// Consumers may overdraw the bucket, in which case they have to wait until the debt is paid off. That way frames
+/// larger than the bucket still pass, while the average rate is enforced.
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(rate: u64) -> Self {
+        // A rate of 0 would never refill, hence stall the connection forever.
+        let rate = rate.max(1) as f64;
+
+        Self {
+            rate,
+            state: Mutex::new(BucketState {
+                tokens: rate,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes `amount` tokens from the bucket and returns how long the caller has to wait before proceeding.
+    pub fn consume(&self, amount: usize) -> Duration {
+        // Panic:
+        // The lock is never held across a panic, hence it can't be poisoned.
+        let mut state = self.state.lock().expect("poisoned token bucket lock");
+
+        let now = Instant::now();
+        let refill = now.saturating_duration_since(state.last_refill).as_secs_f64() * self.rate;
+
+        state.tokens = (state.tokens + refill).min(self.rate) - amount as f64;
+        state.last_refill = now;
+
+        if state.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        }
+    }
+}
+
+/// The token buckets limiting the gossip traffic with a peer in one direction.
+#[derive(Clone, Debug, Default)]
+pub struct Limits(Vec<Arc<TokenBucket>>);
+
+impl Limits {
+    /// Takes `amount` tokens from all buckets, and waits until the most restrictive one allows to proceed.
+    pub async fn throttle(&self, amount: usize) {
+        let delay = self
+            .0
+            .iter()
+            .map(|bucket| bucket.consume(amount))
+            .max()
+            .unwrap_or_else(|| Duration::from_secs(0));
+
+        if delay > Duration::from_secs(0) {
+            sleep(delay).await;
+        }
+    }
+}
+
+/// The limits of the inbound and outbound gossip traffic with a peer.
+#[derive(Clone, Debug, Default)]
+pub struct PeerLimits {
+    pub inbound: Limits,
+    pub outbound: Limits,
+}
+
+/// Hands out the [`PeerLimits`] for new gossip connections according to the [`BandwidthConfig`].
+#[derive(Clone, Debug, Default)]
+pub struct RateLimiter {
+    config: BandwidthConfig,
+    global_inbound: Option<Arc<TokenBucket>>,
+    global_outbound: Option<Arc<TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: BandwidthConfig) -> Self {
+        let global = config.global();
+
+        Self {
+            global_inbound: global.inbound().map(|rate| Arc::new(TokenBucket::new(rate))),
+            global_outbound: global.outbound().map(|rate| Arc::new(TokenBucket::new(rate))),
+            config,
+        }
+    }
+
+    /// Returns the limits of a peer with the given relation. Each call creates new buckets for the peer, while the
+    /// global buckets are shared.
+    pub fn peer_limits(&self, relation: PeerRelation) -> PeerLimits {
+        let limits = self.config.relation(relation);
+
+        let collect = |global: &Option<Arc<TokenBucket>>, rate: Option<u64>| {
+            Limits(
+                global
+                    .iter()
+                    .cloned()
+                    .chain(rate.map(|rate| Arc::new(TokenBucket::new(rate))))
+                    .collect(),
+            )
+        };
+
+        PeerLimits {
+            inbound: collect(&self.global_inbound, limits.inbound()),
+            outbound: collect(&self.global_outbound, limits.outbound()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TrafficLimits;
+
+    #[test]
+    fn bucket_allows_burst_then_throttles() {
+        let bucket = TokenBucket::new(1000);
+
+        assert_eq!(bucket.consume(1000), Duration::from_secs(0));
+
+        let delay = bucket.consume(500);
+        assert!(delay > Duration::from_millis(400) && delay <= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn peer_limits_share_global_buckets() {
+        let limiter = RateLimiter::new(
+            BandwidthConfig::build()
+                .with_global(TrafficLimits::new(Some(1000), None))
+                .with_relation(PeerRelation::Unknown, TrafficLimits::new(Some(100), Some(100)))
+                .finish(),
+        );
+
+        let known = limiter.peer_limits(PeerRelation::Known);
+        let unknown = limiter.peer_limits(PeerRelation::Unknown);
+
+        assert_eq!(known.inbound.0.len(), 1);
+        assert!(known.outbound.0.is_empty());
+        assert_eq!(unknown.inbound.0.len(), 2);
+        assert_eq!(unknown.outbound.0.len(), 1);
+        assert!(Arc::ptr_eq(&known.inbound.0[0], &unknown.inbound.0[0]));
+    }
+}
@@ -1,13 +1,17 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+mod channel;
 mod event;
 mod handler;
 mod id;
 mod io;
+mod limit;
 mod protocol;
 mod upgrade;
 
+pub use channel::*;
 pub use event::*;
 pub use io::*;
+pub use limit::*;
 pub use protocol::*;
@@ -7,7 +7,7 @@ use super::{
     id::IotaGossipIdentifier,
 };
 
-use crate::{alias, init::global::network_id, network::origin::Origin};
+use crate::{alias, init::global::network_id, network::origin::Origin, peer::capabilities::GossipVersion};
 
 use libp2p::{
     core::{connection::ConnectionId, ConnectedPoint},
@@ -22,7 +22,6 @@ use std::{
 };
 
 const IOTA_GOSSIP_NAME: &str = "iota-gossip";
-const IOTA_GOSSIP_VERSION: &str = "1.0.0";
 
 struct ConnectionInfo {
     addr: Multiaddr,
@@ -44,10 +43,10 @@ struct HandlerEvent {
     event: IotaGossipHandlerEvent,
 }
 
-/// Substream upgrade protocol for `/iota-gossip/1.0.0`.
+/// Substream upgrade protocol for `/iota-gossip/<network id>/<version>`.
 pub struct IotaGossipProtocol {
-    /// The gossip protocol identifier.
-    id: IotaGossipIdentifier,
+    /// The gossip protocol identifiers, one per supported version, ordered from the highest to the lowest version.
+    ids: Vec<IotaGossipIdentifier>,
 
     /// Counts the number of handlers created.
     num_handlers: usize,
@@ -66,15 +65,14 @@ pub struct IotaGossipProtocol {
 }
 
 impl IotaGossipProtocol {
-    pub fn new() -> Self {
-        Self::default()
-    }
-}
-
-impl Default for IotaGossipProtocol {
-    fn default() -> Self {
+    /// Creates the gossip protocol supporting the given versions, which are expected to be ordered from the highest to
+    /// the lowest version.
+    pub fn new(versions: &[GossipVersion]) -> Self {
         Self {
-            id: IotaGossipIdentifier::new(IOTA_GOSSIP_NAME, network_id(), IOTA_GOSSIP_VERSION),
+            ids: versions
+                .iter()
+                .map(|version| IotaGossipIdentifier::new(IOTA_GOSSIP_NAME, network_id(), *version))
+                .collect(),
             num_handlers: 0,
             num_inbounds: 0,
             num_outbounds: 0,
@@ -82,6 +80,11 @@ impl Default for IotaGossipProtocol {
             peers: HashMap::with_capacity(8),
         }
     }
+
+    /// Returns the full names of the supported gossip protocol versions, e.g. `/iota-gossip/<network id>/1.0.0`.
+    pub fn protocol_names(&self) -> Vec<String> {
+        self.ids.iter().map(ToString::to_string).collect()
+    }
 }
 
 impl NetworkBehaviour for IotaGossipProtocol {
@@ -106,7 +109,7 @@ impl NetworkBehaviour for IotaGossipProtocol {
         self.num_handlers += 1;
         debug!("gossip protocol: new handler ({}).", self.num_handlers);
 
-        GossipProtocolHandler::new(self.id.clone())
+        GossipProtocolHandler::new(self.ids.clone())
     }
 
     /// **libp2p docs**:
@@ -191,13 +194,14 @@ impl NetworkBehaviour for IotaGossipProtocol {
             IotaGossipHandlerEvent::SentUpgradeRequest { to } => {
                 NetworkBehaviourAction::GenerateEvent(IotaGossipEvent::SentUpgradeRequest { to })
             }
-            IotaGossipHandlerEvent::UpgradeCompleted { substream } => {
+            IotaGossipHandlerEvent::UpgradeCompleted { substream, version } => {
                 if let Some(conn_info) = self.peers.remove(&peer_id) {
                     NetworkBehaviourAction::GenerateEvent(IotaGossipEvent::UpgradeCompleted {
                         peer_id,
                         peer_addr: conn_info.addr,
                         origin: conn_info.origin,
                         substream,
+                        version,
                     })
                 } else {
                     return;
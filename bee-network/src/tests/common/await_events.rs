@@ -96,3 +96,21 @@ pub async fn get_gossip_channels(rx: &mut NetworkEventReceiver) -> (GossipReceiv
         }
     }
 }
+
+pub async fn get_selected_peer_id(rx: &mut NetworkEventReceiver) -> PeerId {
+    let timeout = time::sleep(Duration::from_secs(20));
+    tokio::pin!(timeout);
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => {
+                if let Some(Event::PeerSelected { peer_id, .. }) = event {
+                    return peer_id;
+                }
+            },
+            () = &mut timeout => {
+                panic!("timed out before receiving `PeerSelected` event");
+            }
+        }
+    }
+}
@@ -5,3 +5,4 @@ pub mod await_events;
 pub mod keys_and_ids;
 pub mod network_config;
 pub mod shutdown;
+pub mod sim;
@@ -3,7 +3,7 @@
 
 #![cfg(feature = "full")]
 
-use crate::{Multiaddr, NetworkConfig, Protocol};
+use crate::{DiscoveryConfig, Multiaddr, NetworkConfig, PeerId, Protocol};
 
 pub fn get_network_config_with_port(port: u16) -> NetworkConfig {
     let mut config = NetworkConfig::default();
@@ -20,3 +20,23 @@ pub fn get_in_memory_network_config(port: u64) -> NetworkConfig {
         })
         .finish()
 }
+
+pub fn get_in_memory_network_config_with_discovery(port: u64, entry_nodes: &[(PeerId, u64)]) -> NetworkConfig {
+    let discovery = entry_nodes
+        .iter()
+        .fold(
+            DiscoveryConfig::build().with_enabled(true),
+            |builder, (peer_id, port)| builder.with_entry_node(format!("/memory/{}/p2p/{}", port, peer_id)),
+        )
+        .finish()
+        .unwrap();
+
+    NetworkConfig::build_in_memory()
+        .with_bind_multiaddr({
+            let mut m = Multiaddr::empty();
+            m.push(Protocol::Memory(port));
+            m
+        })
+        .with_discovery(discovery)
+        .finish()
+}
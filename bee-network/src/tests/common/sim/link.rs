@@ -0,0 +1,108 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::NodeId;
+
+use crate::GossipSender;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tokio::{
+    sync::mpsc,
+    time::{self, Instant},
+};
+
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+/// The conditions of a directed link between two simulated nodes.
+///
+/// They apply to the gossip sent with [`Simulation::send`](super::Simulation::send), the transport itself is not
+/// affected.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Link {
+    /// The delay of every message sent over the link.
+    pub latency: Duration,
+    /// The probability, between 0 and 1, that a message sent over the link is lost.
+    pub loss: f64,
+}
+
+impl Link {
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_loss(mut self, loss: f64) -> Self {
+        self.loss = loss;
+        self
+    }
+}
+
+type LinkSender = mpsc::UnboundedSender<(Instant, Vec<u8>)>;
+
+/// The links between the connected nodes of a simulation.
+///
+/// Every directed link has its own task that delays messages before handing them to the gossip channel, so the
+/// order of the messages sent over a link is preserved.
+pub struct Links {
+    conditions: Mutex<HashMap<(NodeId, NodeId), Link>>,
+    senders: Mutex<HashMap<(NodeId, NodeId), LinkSender>>,
+    rng: Mutex<StdRng>,
+}
+
+impl Links {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            conditions: Mutex::new(HashMap::new()),
+            senders: Mutex::new(HashMap::new()),
+            rng: Mutex::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+
+    pub fn set(&self, from: NodeId, to: NodeId, link: Link) {
+        self.conditions.lock().unwrap().insert((from, to), link);
+    }
+
+    pub fn open(&self, from: NodeId, to: NodeId, gossip_out: GossipSender) {
+        let (tx, mut rx) = mpsc::unbounded_channel::<(Instant, Vec<u8>)>();
+
+        tokio::spawn(async move {
+            while let Some((due, message)) = rx.recv().await {
+                time::sleep_until(due).await;
+
+                if gossip_out.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        self.senders.lock().unwrap().insert((from, to), tx);
+    }
+
+    pub fn close(&self, from: NodeId, to: NodeId) {
+        self.senders.lock().unwrap().remove(&(from, to));
+    }
+
+    pub fn close_all(&self, from: NodeId) {
+        self.senders.lock().unwrap().retain(|(f, _), _| *f != from);
+    }
+
+    /// Sends a message over the link. Returns `false` if there is no link, or the message was lost.
+    pub fn send(&self, from: NodeId, to: NodeId, message: Vec<u8>) -> bool {
+        let link = self
+            .conditions
+            .lock()
+            .unwrap()
+            .get(&(from, to))
+            .copied()
+            .unwrap_or_default();
+
+        if link.loss > 0.0 && self.rng.lock().unwrap().gen_bool(link.loss.min(1.0)) {
+            return false;
+        }
+
+        match self.senders.lock().unwrap().get(&(from, to)) {
+            Some(tx) => tx.send((Instant::now() + link.latency, message)).is_ok(),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,436 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "full")]
+
+//! A harness that runs several nodes in-process on the in-memory transport.
+//!
+//! Tests declare a topology, start the nodes, inject latency, message loss, partitions and crashes, and assert on the
+//! events every node observed. Message loss is driven by a seeded RNG, so a simulation behaves the same in every run.
+//!
+//! Latency and loss are applied to the gossip sent with [`Simulation::send`], not at the transport: connection setup,
+//! identification and peer discovery traffic are unaffected. This covers all the gossip of a simulation, since the
+//! network layer doesn't send gossip on its own.
+
+mod link;
+mod recorder;
+
+pub use link::Link;
+pub use recorder::SimEvent;
+
+use link::Links;
+use recorder::{contains_sequence, is_connected, EventLog};
+
+use super::keys_and_ids::{gen_constant_net_id, gen_random_keys};
+
+use crate::{
+    standalone::init, Command, Keypair, Multiaddr, NetworkCommandSender, NetworkConfig, PeerId, PeerRelation, Protocol,
+    PublicKey,
+};
+
+use futures::channel::oneshot;
+use tokio::{task::JoinHandle, time};
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// Every simulation gets its own in-memory ports, so nodes of a previous test can't interfere.
+static NEXT_PORT: AtomicU64 = AtomicU64::new(30000);
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_SEED: u64 = 42;
+
+/// The index of a node in a simulation.
+pub type NodeId = usize;
+
+type ConfigFn = Box<dyn Fn(NodeId, Multiaddr) -> NetworkConfig + Send + Sync>;
+
+struct SimNode {
+    keys: Keypair,
+    peer_id: PeerId,
+    address: Multiaddr,
+    running: Option<RunningNode>,
+}
+
+struct RunningNode {
+    commands: NetworkCommandSender,
+    shutdown: oneshot::Sender<()>,
+    recorder: JoinHandle<()>,
+}
+
+pub struct Simulation {
+    nodes: Vec<SimNode>,
+    peers: Arc<HashMap<PeerId, NodeId>>,
+    edges: HashSet<(NodeId, NodeId)>,
+    partitioned: HashSet<(NodeId, NodeId)>,
+    log: Arc<EventLog>,
+    links: Arc<Links>,
+    config: ConfigFn,
+}
+
+impl Simulation {
+    /// Creates a simulation of `num_nodes` unconnected nodes, that aren't started yet.
+    pub fn new(num_nodes: usize) -> Self {
+        let first_port = NEXT_PORT.fetch_add(num_nodes as u64, Ordering::SeqCst);
+
+        let nodes = (0..num_nodes)
+            .map(|i| {
+                let keys = gen_random_keys();
+                let peer_id = PeerId::from_public_key(PublicKey::Ed25519(keys.public()));
+
+                let mut address = Multiaddr::empty();
+                address.push(Protocol::Memory(first_port + i as u64));
+
+                SimNode {
+                    keys,
+                    peer_id,
+                    address,
+                    running: None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let peers = nodes.iter().enumerate().map(|(i, node)| (node.peer_id, i)).collect();
+
+        Self {
+            nodes,
+            peers: Arc::new(peers),
+            edges: HashSet::new(),
+            partitioned: HashSet::new(),
+            log: Arc::new(EventLog::default()),
+            links: Arc::new(Links::new(DEFAULT_SEED)),
+            config: Box::new(|_, address| NetworkConfig::build_in_memory().with_bind_multiaddr(address).finish()),
+        }
+    }
+
+    /// Sets the seed of the RNG that decides which messages are lost.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.links = Arc::new(Links::new(seed));
+        self
+    }
+
+    /// Sets how the config of a node is created from its index and bind address.
+    pub fn with_config(mut self, f: impl Fn(NodeId, Multiaddr) -> NetworkConfig + Send + Sync + 'static) -> Self {
+        self.config = Box::new(f);
+        self
+    }
+
+    /// Declares that the two nodes know each other, and should be connected once started.
+    pub fn connect(mut self, a: NodeId, b: NodeId) -> Self {
+        self.edges.insert(edge(a, b));
+        self
+    }
+
+    /// Connects every node with its successor.
+    pub fn line(self) -> Self {
+        (1..self.nodes.len()).fold(self, |sim, i| sim.connect(i - 1, i))
+    }
+
+    /// Connects every node with every other node.
+    pub fn full_mesh(self) -> Self {
+        let n = self.nodes.len();
+
+        (0..n)
+            .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+            .fold(self, |sim, (a, b)| sim.connect(a, b))
+    }
+
+    pub fn peer_id(&self, node: NodeId) -> PeerId {
+        self.nodes[node].peer_id
+    }
+
+    pub fn address(&self, node: NodeId) -> &Multiaddr {
+        &self.nodes[node].address
+    }
+
+    /// Starts all nodes, and waits until all declared connections are established.
+    pub async fn start(&mut self) {
+        for node in 0..self.nodes.len() {
+            self.start_node(node).await;
+        }
+
+        for (a, b) in self.edges.clone() {
+            self.add_peer(a, b);
+            self.add_peer(b, a);
+            self.command(
+                a,
+                Command::DialPeer {
+                    peer_id: self.peer_id(b),
+                },
+            );
+        }
+
+        for (a, b) in self.edges.clone() {
+            self.await_connected(a, b).await;
+        }
+    }
+
+    /// Sets the conditions of the link from `from` to `to`.
+    pub fn set_link(&self, from: NodeId, to: NodeId, link: Link) {
+        self.links.set(from, to, link);
+    }
+
+    /// Sends a message to a connected peer over the simulated link. Returns `false` if the message was lost, or there
+    /// is no connection.
+    ///
+    /// Only messages sent this way are subject to the conditions of the link.
+    pub fn send(&self, from: NodeId, to: NodeId, message: impl Into<Vec<u8>>) -> bool {
+        self.links.send(from, to, message.into())
+    }
+
+    /// Sends a command to a running node.
+    pub fn command(&self, node: NodeId, command: Command) {
+        self.nodes[node]
+            .running
+            .as_ref()
+            .unwrap_or_else(|| panic!("node {} is not running", node))
+            .commands
+            .send(command)
+            .expect("send command");
+    }
+
+    /// Separates the two groups of nodes by banning and disconnecting each other.
+    pub async fn partition(&mut self, group_a: &[NodeId], group_b: &[NodeId]) {
+        for &a in group_a {
+            for &b in group_b {
+                self.command(
+                    a,
+                    Command::BanPeer {
+                        peer_id: self.peer_id(b),
+                    },
+                );
+                self.command(
+                    b,
+                    Command::BanPeer {
+                        peer_id: self.peer_id(a),
+                    },
+                );
+                self.command(
+                    a,
+                    Command::DisconnectPeer {
+                        peer_id: self.peer_id(b),
+                    },
+                );
+
+                self.partitioned.insert(edge(a, b));
+            }
+        }
+
+        for &a in group_a {
+            for &b in group_b {
+                if self.edges.contains(&edge(a, b)) {
+                    self.await_disconnected(a, b).await;
+                }
+            }
+        }
+    }
+
+    /// Lifts all partitions, and waits until the declared connections across them are re-established.
+    pub async fn heal(&mut self) {
+        let partitioned = std::mem::take(&mut self.partitioned);
+
+        for &(a, b) in &partitioned {
+            self.command(
+                a,
+                Command::UnbanPeer {
+                    peer_id: self.peer_id(b),
+                },
+            );
+            self.command(
+                b,
+                Command::UnbanPeer {
+                    peer_id: self.peer_id(a),
+                },
+            );
+
+            // Don't wait for the reconnect interval.
+            if self.edges.contains(&(a, b)) {
+                self.command(
+                    a,
+                    Command::DialPeer {
+                        peer_id: self.peer_id(b),
+                    },
+                );
+            }
+        }
+
+        for (a, b) in partitioned {
+            if self.edges.contains(&(a, b)) {
+                self.await_connected(a, b).await;
+            }
+        }
+    }
+
+    /// Shuts a node down, and waits until its neighbors noticed.
+    pub async fn crash(&mut self, node: NodeId) {
+        let running = self.nodes[node]
+            .running
+            .take()
+            .unwrap_or_else(|| panic!("node {} is not running", node));
+
+        // The event channel is only closed once the node released its listener, so it can be restarted right away.
+        let _ = running.shutdown.send(());
+        let _ = time::timeout(DEFAULT_TIMEOUT, running.recorder).await;
+
+        for peer in self.neighbors(node) {
+            if self.nodes[peer].running.is_some() {
+                self.await_event(peer, |events| !is_connected(events, node)).await;
+            }
+        }
+    }
+
+    /// Restarts a crashed node with the same identity and address, and waits until it reconnected to its running
+    /// neighbors.
+    pub async fn restart(&mut self, node: NodeId) {
+        self.start_node(node).await;
+
+        for peer in self.neighbors(node) {
+            if self.nodes[peer].running.is_some() {
+                self.add_peer(node, peer);
+                self.command(
+                    node,
+                    Command::DialPeer {
+                        peer_id: self.peer_id(peer),
+                    },
+                );
+            }
+        }
+
+        for peer in self.neighbors(node) {
+            if self.nodes[peer].running.is_some() {
+                self.await_connected(node, peer).await;
+            }
+        }
+    }
+
+    /// Returns the events recorded by a node so far.
+    pub fn events(&self, node: NodeId) -> Vec<SimEvent> {
+        self.log.events(node)
+    }
+
+    /// Forgets the events recorded by a node so far.
+    pub fn clear_events(&self, node: NodeId) {
+        self.log.clear(node);
+    }
+
+    /// Waits until a node recorded `event`.
+    pub async fn expect(&self, node: NodeId, event: SimEvent) {
+        self.expect_sequence(node, &[event]).await;
+    }
+
+    /// Waits until a node recorded all `events` in the given order, not necessarily consecutively.
+    pub async fn expect_sequence(&self, node: NodeId, events: &[SimEvent]) {
+        if !self
+            .log
+            .wait_until(node, DEFAULT_TIMEOUT, |recorded| contains_sequence(recorded, events))
+            .await
+        {
+            panic!(
+                "node {} didn't record {:?} in time, recorded: {:?}",
+                node,
+                events,
+                self.events(node)
+            );
+        }
+    }
+
+    /// Asserts that a node doesn't record `event` within `duration`.
+    pub async fn expect_none(&self, node: NodeId, event: SimEvent, duration: Duration) {
+        if self
+            .log
+            .wait_until(node, duration, |recorded| recorded.contains(&event))
+            .await
+        {
+            panic!("node {} unexpectedly recorded {:?}", node, event);
+        }
+    }
+
+    /// Waits until both nodes consider each other connected.
+    pub async fn await_connected(&self, a: NodeId, b: NodeId) {
+        self.await_event(a, |events| is_connected(events, b)).await;
+        self.await_event(b, |events| is_connected(events, a)).await;
+    }
+
+    /// Waits until both nodes consider each other disconnected.
+    pub async fn await_disconnected(&self, a: NodeId, b: NodeId) {
+        self.await_event(a, |events| !is_connected(events, b)).await;
+        self.await_event(b, |events| !is_connected(events, a)).await;
+    }
+
+    async fn await_event(&self, node: NodeId, predicate: impl Fn(&[SimEvent]) -> bool) {
+        if !self.log.wait_until(node, DEFAULT_TIMEOUT, predicate).await {
+            panic!("timed out, node {} recorded: {:?}", node, self.events(node));
+        }
+    }
+
+    async fn start_node(&mut self, node: NodeId) {
+        let config = (self.config)(node, self.nodes[node].address.clone());
+        let keys = self.nodes[node].keys.clone();
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let shutdown = Box::new(Box::pin(async move {
+            let _ = shutdown_rx.await;
+        }));
+
+        let (commands, events) = init(config, keys, gen_constant_net_id(), shutdown)
+            .await
+            .expect("init failed");
+
+        let recorder = tokio::spawn(recorder::record(
+            node,
+            events,
+            self.peers.clone(),
+            self.log.clone(),
+            self.links.clone(),
+        ));
+
+        self.nodes[node].running.replace(RunningNode {
+            commands,
+            shutdown: shutdown_tx,
+            recorder,
+        });
+    }
+
+    fn add_peer(&self, node: NodeId, peer: NodeId) {
+        self.command(
+            node,
+            Command::AddPeer {
+                peer_id: self.peer_id(peer),
+                multiaddr: self.address(peer).clone(),
+                alias: Some(peer.to_string()),
+                relation: PeerRelation::Known,
+            },
+        );
+    }
+
+    fn neighbors(&self, node: NodeId) -> Vec<NodeId> {
+        self.edges
+            .iter()
+            .filter_map(|&(a, b)| match node {
+                n if n == a => Some(b),
+                n if n == b => Some(a),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        for node in &mut self.nodes {
+            if let Some(running) = node.running.take() {
+                let _ = running.shutdown.send(());
+            }
+        }
+    }
+}
+
+fn edge(a: NodeId, b: NodeId) -> (NodeId, NodeId) {
+    (a.min(b), a.max(b))
+}
@@ -0,0 +1,170 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{link::Links, NodeId};
+
+use crate::{Event, GossipReceiver, GossipVersion, NetworkEventReceiver, PeerId};
+
+use tokio::{sync::Notify, time};
+use tokio_stream::StreamExt;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// What a simulated node observed, with peers referred to by their node index.
+///
+/// [`Event`] isn't `Clone` and contains the gossip channels, hence the events are recorded in this simplified form.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SimEvent {
+    Added(NodeId),
+    Removed(NodeId),
+    Connected(NodeId),
+    /// The gossip protocol version negotiated with a peer, recorded right after [`SimEvent::Connected`].
+    Negotiated {
+        peer: NodeId,
+        version: Option<GossipVersion>,
+    },
+    Disconnected(NodeId),
+    Banned(NodeId),
+    Unbanned(NodeId),
+    Selected(NodeId),
+    Dropped(NodeId),
+    Received {
+        from: NodeId,
+        message: Vec<u8>,
+    },
+    CommandFailed(String),
+    /// The node was shut down.
+    Stopped,
+}
+
+/// The events recorded by all nodes of a simulation.
+#[derive(Default)]
+pub struct EventLog {
+    events: Mutex<HashMap<NodeId, Vec<SimEvent>>>,
+    notify: Notify,
+}
+
+impl EventLog {
+    pub fn push(&self, node: NodeId, event: SimEvent) {
+        self.events.lock().unwrap().entry(node).or_default().push(event);
+        self.notify.notify_waiters();
+    }
+
+    pub fn events(&self, node: NodeId) -> Vec<SimEvent> {
+        self.events.lock().unwrap().get(&node).cloned().unwrap_or_default()
+    }
+
+    pub fn clear(&self, node: NodeId) {
+        self.events.lock().unwrap().remove(&node);
+    }
+
+    /// Waits until the events of `node` satisfy `predicate`. Returns `false` if that didn't happen within `timeout`.
+    pub async fn wait_until(&self, node: NodeId, timeout: Duration, predicate: impl Fn(&[SimEvent]) -> bool) -> bool {
+        time::timeout(timeout, async {
+            loop {
+                // Create the future before checking, so a notification in between isn't missed.
+                let notified = self.notify.notified();
+
+                if predicate(&self.events(node)) {
+                    return;
+                }
+
+                notified.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+/// Returns whether the last connection related event of a node about `peer` is [`SimEvent::Connected`].
+pub fn is_connected(events: &[SimEvent], peer: NodeId) -> bool {
+    events
+        .iter()
+        .rev()
+        .find_map(|event| match event {
+            SimEvent::Connected(p) if *p == peer => Some(true),
+            SimEvent::Disconnected(p) if *p == peer => Some(false),
+            SimEvent::Stopped => Some(false),
+            _ => None,
+        })
+        .unwrap_or(false)
+}
+
+/// Returns whether `expected` occurs in `events` in the given order, not necessarily consecutively.
+pub fn contains_sequence(events: &[SimEvent], expected: &[SimEvent]) -> bool {
+    let mut events = events.iter();
+
+    expected.iter().all(|e| events.any(|event| event == e))
+}
+
+/// Records the events of a node until its event channel is closed, and hands the gossip channels of connected peers
+/// to the links.
+pub async fn record(
+    node: NodeId,
+    mut events: NetworkEventReceiver,
+    peers: Arc<HashMap<PeerId, NodeId>>,
+    log: Arc<EventLog>,
+    links: Arc<Links>,
+) {
+    while let Some(event) = events.recv().await {
+        let (peer_id, to_sim_event): (PeerId, fn(NodeId) -> SimEvent) = match event {
+            Event::PeerAdded { peer_id, .. } => (peer_id, SimEvent::Added),
+            Event::PeerRemoved { peer_id } => (peer_id, SimEvent::Removed),
+            Event::PeerConnected {
+                peer_id,
+                info,
+                gossip_in,
+                gossip_out,
+            } => {
+                if let Some(&peer) = peers.get(&peer_id) {
+                    links.open(node, peer, gossip_out);
+                    tokio::spawn(receive(node, peer, gossip_in, log.clone()));
+
+                    log.push(node, SimEvent::Connected(peer));
+                    log.push(
+                        node,
+                        SimEvent::Negotiated {
+                            peer,
+                            version: info.capabilities.gossip_version,
+                        },
+                    );
+                }
+                continue;
+            }
+            Event::PeerDisconnected { peer_id } => {
+                if let Some(&peer) = peers.get(&peer_id) {
+                    links.close(node, peer);
+                }
+                (peer_id, SimEvent::Disconnected)
+            }
+            Event::PeerBanned { peer_id } => (peer_id, SimEvent::Banned),
+            Event::PeerUnbanned { peer_id } => (peer_id, SimEvent::Unbanned),
+            Event::PeerSelected { peer_id, .. } => (peer_id, SimEvent::Selected),
+            Event::PeerDropped { peer_id, .. } => (peer_id, SimEvent::Dropped),
+            Event::CommandFailed { command, reason } => {
+                log.push(node, SimEvent::CommandFailed(format!("{:?}: {}", command, reason)));
+                continue;
+            }
+            _ => continue,
+        };
+
+        // Peers that aren't part of the simulation are ignored.
+        if let Some(&peer) = peers.get(&peer_id) {
+            log.push(node, to_sim_event(peer));
+        }
+    }
+
+    links.close_all(node);
+    log.push(node, SimEvent::Stopped);
+}
+
+async fn receive(node: NodeId, from: NodeId, mut gossip_in: GossipReceiver, log: Arc<EventLog>) {
+    while let Some(message) = gossip_in.next().await {
+        log.push(node, SimEvent::Received { from, message });
+    }
+}
@@ -0,0 +1,34 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "full")]
+
+use super::common::{await_events::*, keys_and_ids::*, network_config::*, shutdown::*};
+
+use crate::standalone::init;
+
+#[tokio::test]
+#[serial_test::serial]
+async fn discover_entry_node() {
+    let keys1 = gen_deterministic_keys(1);
+    let peer_id1 = gen_deterministic_peer_id(1);
+    let config1 = get_in_memory_network_config_with_discovery(1337, &[]);
+
+    let keys2 = gen_random_keys();
+    let config2 = get_in_memory_network_config_with_discovery(4242, &[(peer_id1, 1337)]);
+
+    let network_id = gen_constant_net_id();
+
+    let (_tx1, mut rx1) = init(config1, keys1, network_id, shutdown(10))
+        .await
+        .expect("init failed");
+
+    assert_eq!(get_local_id(&mut rx1).await, peer_id1);
+    let _address1 = get_bind_address(&mut rx1).await;
+
+    let (_tx2, mut rx2) = init(config2, keys2, network_id, shutdown(10))
+        .await
+        .expect("init failed");
+
+    assert_eq!(get_selected_peer_id(&mut rx2).await, peer_id1);
+}
@@ -8,5 +8,7 @@ mod add_peer;
 mod alias;
 mod common;
 mod connect_peer;
+mod discovery;
 mod initialize;
 mod send_recv;
+mod simulation;
@@ -0,0 +1,203 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "full")]
+
+use super::common::sim::{Link, SimEvent, Simulation};
+
+use crate::{GossipConfig, GossipVersion, NetworkConfig};
+
+use tokio::time::{Duration, Instant};
+
+#[tokio::test]
+#[serial_test::serial]
+async fn line_topology_with_latency() {
+    let mut sim = Simulation::new(3).line();
+    sim.start().await;
+
+    sim.set_link(0, 1, Link::default().with_latency(Duration::from_millis(300)));
+
+    let sent = Instant::now();
+    assert!(sim.send(0, 1, b"first".to_vec()));
+    assert!(sim.send(0, 1, b"second".to_vec()));
+    assert!(sim.send(1, 2, b"third".to_vec()));
+
+    sim.expect(
+        2,
+        SimEvent::Received {
+            from: 1,
+            message: b"third".to_vec(),
+        },
+    )
+    .await;
+    sim.expect_sequence(
+        1,
+        &[
+            SimEvent::Received {
+                from: 0,
+                message: b"first".to_vec(),
+            },
+            SimEvent::Received {
+                from: 0,
+                message: b"second".to_vec(),
+            },
+        ],
+    )
+    .await;
+    assert!(sent.elapsed() >= Duration::from_millis(300));
+
+    // Nodes 0 and 2 aren't connected.
+    assert!(!sim.send(0, 2, b"unreachable".to_vec()));
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn message_loss_is_deterministic() {
+    let mut sim = Simulation::new(2).line().with_seed(7);
+    sim.start().await;
+
+    sim.set_link(0, 1, Link::default().with_loss(0.5));
+
+    let delivered = (0..20u8).filter(|i| sim.send(0, 1, vec![*i])).collect::<Vec<_>>();
+    assert!(!delivered.is_empty() && delivered.len() < 20);
+
+    // Messages sent over a link arrive in order, so once a final message sent without loss arrived, all the
+    // delivered messages did.
+    sim.set_link(0, 1, Link::default());
+    assert!(sim.send(0, 1, b"last".to_vec()));
+    sim.expect(
+        1,
+        SimEvent::Received {
+            from: 0,
+            message: b"last".to_vec(),
+        },
+    )
+    .await;
+
+    let received = sim
+        .events(1)
+        .into_iter()
+        .filter_map(|event| match event {
+            SimEvent::Received { message, .. } if message != b"last" => Some(message[0]),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(received, delivered);
+
+    // The same seed loses the same messages.
+    let mut replay = Simulation::new(2).line().with_seed(7);
+    replay.start().await;
+    replay.set_link(0, 1, Link::default().with_loss(0.5));
+
+    assert_eq!(
+        (0..20u8).filter(|i| replay.send(0, 1, vec![*i])).collect::<Vec<_>>(),
+        delivered
+    );
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn partition_and_heal() {
+    let mut sim = Simulation::new(3).full_mesh();
+    sim.start().await;
+
+    sim.partition(&[0], &[1, 2]).await;
+
+    assert!(!sim.send(1, 0, b"across".to_vec()));
+    assert!(sim.send(1, 2, b"within".to_vec()));
+    sim.expect(
+        2,
+        SimEvent::Received {
+            from: 1,
+            message: b"within".to_vec(),
+        },
+    )
+    .await;
+
+    sim.clear_events(0);
+    sim.heal().await;
+    sim.expect_sequence(0, &[SimEvent::Unbanned(1), SimEvent::Connected(1)])
+        .await;
+
+    assert!(sim.send(1, 0, b"healed".to_vec()));
+    sim.expect(
+        0,
+        SimEvent::Received {
+            from: 1,
+            message: b"healed".to_vec(),
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn crash_and_restart() {
+    let mut sim = Simulation::new(2).line();
+    sim.start().await;
+
+    sim.crash(1).await;
+    sim.expect(0, SimEvent::Disconnected(1)).await;
+    sim.expect(1, SimEvent::Stopped).await;
+    assert!(!sim.send(0, 1, b"lost".to_vec()));
+
+    sim.restart(1).await;
+
+    assert!(sim.send(0, 1, b"back".to_vec()));
+    sim.expect(
+        1,
+        SimEvent::Received {
+            from: 0,
+            message: b"back".to_vec(),
+        },
+    )
+    .await;
+    sim.expect_none(
+        1,
+        SimEvent::Received {
+            from: 0,
+            message: b"lost".to_vec(),
+        },
+        Duration::from_millis(200),
+    )
+    .await;
+}
+
+#[tokio::test]
+#[serial_test::serial]
+async fn highest_common_gossip_version_is_negotiated() {
+    let v2 = GossipVersion::new(2, 0, 0);
+
+    // Node 1 only supports the first version, nodes 0 and 2 support both.
+    let mut sim = Simulation::new(3)
+        .connect(0, 1)
+        .connect(0, 2)
+        .with_config(move |node, address| {
+            let gossip = match node {
+                1 => GossipConfig::build().with_version(GossipVersion::V1),
+                _ => GossipConfig::build().with_version(v2).with_version(GossipVersion::V1),
+            };
+
+            NetworkConfig::build_in_memory()
+                .with_bind_multiaddr(address)
+                .with_gossip(gossip.finish())
+                .finish()
+        });
+    sim.start().await;
+
+    for (node, peer, version) in vec![
+        (0, 1, GossipVersion::V1),
+        (1, 0, GossipVersion::V1),
+        (0, 2, v2),
+        (2, 0, v2),
+    ] {
+        sim.expect(
+            node,
+            SimEvent::Negotiated {
+                peer,
+                version: Some(version),
+            },
+        )
+        .await;
+    }
+}
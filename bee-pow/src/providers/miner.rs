@@ -6,20 +6,25 @@
 use crate::providers::{NonceProvider, NonceProviderBuilder};
 
 use bee_crypto::ternary::{
-    sponge::{BatchHasher, CurlPRounds, BATCH_SIZE},
+    sponge::{BatchHasher, CurlPRounds},
     HASH_LENGTH,
 };
 use bee_ternary::{b1t6, Btrit, T1B1Buf, TritBuf};
 
 use crypto::hashes::{blake2b::Blake2b256, Digest};
+use futures::channel::oneshot;
 use thiserror::Error;
 
 use std::{
+    future::Future,
+    pin::Pin,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
+    task::{Context, Poll},
     thread,
+    time::Instant,
 };
 
 const DEFAULT_NUM_WORKERS: usize = 1;
@@ -33,6 +38,9 @@ pub enum Error {
     /// The worker has been cancelled.
     #[error("The worker has been cancelled.")]
     Cancelled,
+    /// The deadline of the job passed before a nonce was found.
+    #[error("The deadline of the job passed before a nonce was found.")]
+    DeadlineExceeded,
     /// Invalid proof of work score.
     #[error("Invalid proof of work score {0}, requiring {} trailing zeros.")]
     InvalidPowScore(f64, usize),
@@ -103,23 +111,81 @@ pub struct Miner {
 }
 
 impl Miner {
+    /// Starts mining a nonce for the given bytes and target score on background threads, and returns the handle of the
+    /// job, which resolves to the nonce when awaited.
+    ///
+    /// Unlike [`NonceProvider::nonce`], this never blocks the calling thread, so it can be used from within an async
+    /// runtime. Each job has its own cancellation, independent from the `MinerCancel` of the `Miner`, so several jobs
+    /// can run at the same time. A job gives up once the optional deadline passes.
+    pub fn spawn(&self, bytes: &[u8], target_score: f64, deadline: Option<Instant>) -> Result<MinerJob, Error> {
+        let target_zeros = target_zeros(bytes.len(), target_score)?;
+        let pow_digest = pow_digest(bytes);
+        let cancel = MinerCancel::new();
+        let hashes = Arc::new(AtomicU64::new(0));
+        let (sender, receiver) = oneshot::channel();
+        let worker_width = u64::MAX / self.num_workers as u64;
+
+        let workers = (0..self.num_workers)
+            .map(|i| {
+                let start_nonce = i as u64 * worker_width;
+                let cancel = cancel.clone();
+                let pow_digest = pow_digest.clone();
+                let hashes = hashes.clone();
+
+                thread::spawn(move || Miner::worker(cancel, pow_digest, start_nonce, target_zeros, hashes, deadline))
+            })
+            .collect::<Vec<_>>();
+
+        // Joining the workers from a thread of its own keeps the caller free.
+        thread::spawn(move || {
+            let mut result = Err(Error::Cancelled);
+
+            for worker in workers {
+                match worker.join() {
+                    Ok(Ok(nonce)) => result = Ok(nonce),
+                    Ok(Err(Error::DeadlineExceeded)) if result.is_err() => result = Err(Error::DeadlineExceeded),
+                    _ => continue,
+                }
+            }
+
+            // The job may have been dropped in the meantime.
+            let _ = sender.send(result);
+        });
+
+        Ok(MinerJob {
+            cancel,
+            hashes,
+            expected_hashes: 3f64.powi(target_zeros as i32),
+            receiver,
+        })
+    }
+
     fn worker(
         cancel: MinerCancel,
         pow_digest: TritBuf<T1B1Buf>,
         start_nonce: u64,
         target_zeros: usize,
+        hashes: Arc<AtomicU64>,
+        deadline: Option<Instant>,
     ) -> Result<u64, Error> {
         let mut nonce = start_nonce;
         let mut hasher = BatchHasher::<T1B1Buf>::new(HASH_LENGTH, CurlPRounds::Rounds81);
-        let mut buffers = Vec::<TritBuf<T1B1Buf>>::with_capacity(BATCH_SIZE);
+        // Full batches are the fastest, and they are larger on CPUs with wider SIMD registers.
+        let batch_size = hasher.capacity();
+        let mut buffers = Vec::<TritBuf<T1B1Buf>>::with_capacity(batch_size);
 
-        for _ in 0..BATCH_SIZE {
+        for _ in 0..batch_size {
             let mut buffer = TritBuf::<T1B1Buf>::zeros(HASH_LENGTH);
             buffer[..pow_digest.len()].copy_from(&pow_digest);
             buffers.push(buffer);
         }
 
         while !cancel.is_cancelled() {
+            if deadline.map_or(false, |deadline| Instant::now() >= deadline) {
+                cancel.trigger();
+                return Err(Error::DeadlineExceeded);
+            }
+
             for (i, buffer) in buffers.iter_mut().enumerate() {
                 let nonce_trits = b1t6::encode::<T1B1Buf>(&(nonce + i as u64).to_le_bytes());
                 buffer[pow_digest.len()..pow_digest.len() + nonce_trits.len()].copy_from(&nonce_trits);
@@ -135,7 +201,8 @@ impl Miner {
                 }
             }
 
-            nonce += BATCH_SIZE as u64;
+            hashes.fetch_add(batch_size as u64, Ordering::Relaxed);
+            nonce += batch_size as u64;
         }
 
         Err(Error::Cancelled)
@@ -150,19 +217,10 @@ impl NonceProvider for Miner {
         self.cancel.reset();
 
         let mut nonce = 0;
-        let mut pow_digest = TritBuf::<T1B1Buf>::new();
-        let target_zeros =
-            (((bytes.len() + std::mem::size_of::<u64>()) as f64 * target_score).ln() / LN_3).ceil() as usize;
-
-        if target_zeros > HASH_LENGTH {
-            return Err(Self::Error::InvalidPowScore(target_score, target_zeros));
-        }
-
+        let target_zeros = target_zeros(bytes.len(), target_score)?;
+        let pow_digest = pow_digest(bytes);
         let worker_width = u64::MAX / self.num_workers as u64;
         let mut workers = Vec::with_capacity(self.num_workers);
-        let hash = Blake2b256::digest(&bytes);
-
-        b1t6::encode::<T1B1Buf>(&hash).iter().for_each(|t| pow_digest.push(t));
 
         for i in 0..self.num_workers {
             let start_nonce = i as u64 * worker_width;
@@ -170,7 +228,7 @@ impl NonceProvider for Miner {
             let _pow_digest = pow_digest.clone();
 
             workers.push(thread::spawn(move || {
-                Miner::worker(_cancel, _pow_digest, start_nonce, target_zeros)
+                Miner::worker(_cancel, _pow_digest, start_nonce, target_zeros, Arc::default(), None)
             }));
         }
 
@@ -184,3 +242,90 @@ impl NonceProvider for Miner {
         Ok(nonce)
     }
 }
+
+/// The handle of a Proof of Work job started with [`Miner::spawn`], resolving to the mined nonce.
+///
+/// Dropping the handle cancels the job.
+pub struct MinerJob {
+    cancel: MinerCancel,
+    hashes: Arc<AtomicU64>,
+    expected_hashes: f64,
+    receiver: oneshot::Receiver<Result<u64, Error>>,
+}
+
+impl MinerJob {
+    /// Cancels the job, which then resolves to [`Error::Cancelled`] unless a nonce was already found.
+    pub fn cancel(&self) {
+        self.cancel.trigger();
+    }
+
+    /// Returns the current progress of the job.
+    pub fn progress(&self) -> MinerProgress {
+        MinerProgress {
+            hashes: self.hashes.load(Ordering::Relaxed),
+            expected_hashes: self.expected_hashes,
+        }
+    }
+}
+
+impl Future for MinerJob {
+    type Output = Result<u64, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // The sender is only dropped without sending if the thread joining the workers panicked.
+        Pin::new(&mut self.receiver)
+            .poll(cx)
+            .map(|result| result.unwrap_or(Err(Error::Cancelled)))
+    }
+}
+
+impl Drop for MinerJob {
+    fn drop(&mut self) {
+        self.cancel.trigger();
+    }
+}
+
+/// The progress of a [`MinerJob`].
+#[derive(Clone, Copy, Debug)]
+pub struct MinerProgress {
+    hashes: u64,
+    expected_hashes: f64,
+}
+
+impl MinerProgress {
+    /// Returns the number of hashes tried so far.
+    pub fn hashes(&self) -> u64 {
+        self.hashes
+    }
+
+    /// Returns the number of hashes it takes on average to reach the target score.
+    pub fn expected_hashes(&self) -> f64 {
+        self.expected_hashes
+    }
+
+    /// Returns an estimate of the number of hashes left to try, based on the average. Mining being random, the
+    /// actual number may be anything.
+    pub fn remaining_hashes(&self) -> f64 {
+        (self.expected_hashes - self.hashes as f64).max(0.0)
+    }
+}
+
+/// Returns the number of trailing zero trits the hash of a message of the given length needs to reach a target score.
+fn target_zeros(length: usize, target_score: f64) -> Result<usize, Error> {
+    let target_zeros = (((length + std::mem::size_of::<u64>()) as f64 * target_score).ln() / LN_3).ceil() as usize;
+
+    if target_zeros > HASH_LENGTH {
+        return Err(Error::InvalidPowScore(target_score, target_zeros));
+    }
+
+    Ok(target_zeros)
+}
+
+fn pow_digest(bytes: &[u8]) -> TritBuf<T1B1Buf> {
+    let mut pow_digest = TritBuf::<T1B1Buf>::new();
+    let hash = Blake2b256::digest(bytes);
+
+    b1t6::encode::<T1B1Buf>(&hash).iter().for_each(|t| pow_digest.push(t));
+
+    pow_digest
+}
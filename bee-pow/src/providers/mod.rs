@@ -4,6 +4,8 @@
 //! Contains nonce providers for Proof of Work.
 
 pub mod miner;
+#[cfg(feature = "remote")]
+pub mod remote;
 pub mod u64;
 
 /// A trait to build nonce providers.
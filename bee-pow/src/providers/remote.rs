@@ -0,0 +1,147 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Contains a nonce provider that delegates Proof of Work to remote endpoints.
+//!
+//! An endpoint is sent a `POST` request with a JSON body holding the hex encoded bytes of the message without its
+//! nonce, and the target score, e.g. `{"bytes":"0a2b...","target_score":4000.0}`. It has to answer with the nonce, as
+//! a decimal string since it doesn't always fit in a JSON number, e.g. `{"nonce":"1536"}`.
+
+use crate::{
+    providers::{
+        miner::{self, Miner, MinerBuilder},
+        NonceProvider, NonceProviderBuilder,
+    },
+    score::compute_pow_score,
+};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use std::time::Duration;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors occurring when computing nonces with the `Remote` nonce provider.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// There is neither an endpoint nor a local fallback.
+    #[error("No remote PoW endpoint nor local fallback.")]
+    NoEndpoint,
+    /// A request to an endpoint failed.
+    #[error("Request to the remote PoW endpoint failed: {0}.")]
+    Request(#[from] Box<ureq::Error>),
+    /// An endpoint answered with something else than a nonce.
+    #[error("Invalid response from the remote PoW endpoint: {0}.")]
+    InvalidResponse(String),
+    /// An endpoint answered with a nonce that doesn't reach the target score.
+    #[error("The nonce of the remote PoW endpoint only reaches the score {0}.")]
+    InsufficientScore(f64),
+    /// The local fallback failed.
+    #[error(transparent)]
+    Miner(#[from] miner::Error),
+}
+
+/// Builder for the `Remote` nonce provider.
+#[derive(Default)]
+pub struct RemoteBuilder {
+    endpoints: Vec<String>,
+    timeout: Option<Duration>,
+    fallback: Option<MinerBuilder>,
+}
+
+impl RemoteBuilder {
+    /// Adds the URL of an endpoint. Endpoints are tried in the order they were added.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoints.push(endpoint.into());
+        self
+    }
+
+    /// Sets how long to wait for an endpoint to answer before trying the next one.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout.replace(timeout);
+        self
+    }
+
+    /// Sets a `Miner` to do the Proof of Work locally if no endpoint provides a valid nonce.
+    pub fn with_local_fallback(mut self, fallback: MinerBuilder) -> Self {
+        self.fallback.replace(fallback);
+        self
+    }
+}
+
+impl NonceProviderBuilder for RemoteBuilder {
+    type Provider = Remote;
+
+    fn finish(self) -> Remote {
+        Remote {
+            endpoints: self.endpoints,
+            agent: ureq::AgentBuilder::new()
+                .timeout(self.timeout.unwrap_or(DEFAULT_TIMEOUT))
+                .build(),
+            fallback: self.fallback.map(MinerBuilder::finish),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct NonceResponse {
+    nonce: String,
+}
+
+/// A nonce provider that delegates Proof of Work to remote endpoints, and checks the nonces they provide.
+pub struct Remote {
+    endpoints: Vec<String>,
+    agent: ureq::Agent,
+    fallback: Option<Miner>,
+}
+
+impl Remote {
+    fn request(&self, endpoint: &str, bytes: &[u8], target_score: f64) -> Result<u64, Error> {
+        let response = self
+            .agent
+            .post(endpoint)
+            .send_json(serde_json::json!({
+                "bytes": hex::encode(bytes),
+                "target_score": target_score,
+            }))
+            .map_err(Box::new)?
+            .into_json::<NonceResponse>()
+            .map_err(|e| Error::InvalidResponse(e.to_string()))?;
+
+        response
+            .nonce
+            .parse()
+            .map_err(|_| Error::InvalidResponse(response.nonce))
+    }
+}
+
+impl NonceProvider for Remote {
+    type Builder = RemoteBuilder;
+    type Error = Error;
+
+    fn nonce(&self, bytes: &[u8], target_score: f64) -> Result<u64, Self::Error> {
+        let mut error = Error::NoEndpoint;
+
+        for endpoint in self.endpoints.iter() {
+            // Endpoints are not trusted, the nonce they provide is only accepted if it reaches the target score.
+            match self.request(endpoint, bytes, target_score) {
+                Ok(nonce) => {
+                    let score = compute_pow_score(&[bytes, &nonce.to_le_bytes()].concat());
+
+                    if score >= target_score {
+                        return Ok(nonce);
+                    }
+
+                    error = Error::InsufficientScore(score);
+                }
+                Err(e) => error = e,
+            }
+        }
+
+        match &self.fallback {
+            Some(miner) => Ok(miner.nonce(bytes, target_score)?),
+            None => Err(error),
+        }
+    }
+}
@@ -22,9 +22,15 @@ pub fn compute_pow_score(bytes: &[u8]) -> f64 {
 /// Returns the Proof of Work hash of given bytes.
 /// Panic: expects at least 8 bytes.
 pub fn pow_hash(bytes: &[u8]) -> TritBuf<T1B1Buf> {
+    CurlP81::new().digest(&pow_input(bytes)).unwrap()
+}
+
+/// Returns the `HASH_LENGTH` trits that are hashed with `CurlP81` to get the Proof of Work hash of given bytes.
+/// This allows hashing the inputs of several messages at once, e.g. with a batched hasher.
+/// Panic: expects at least 8 bytes.
+pub fn pow_input(bytes: &[u8]) -> TritBuf<T1B1Buf> {
     debug_assert!(bytes.len() >= std::mem::size_of::<u8>());
 
-    let mut curl = CurlP81::new();
     let length = bytes.len() - std::mem::size_of::<u64>();
     let mut pow_input = TritBuf::<T1B1Buf>::with_capacity(HASH_LENGTH);
     let pow_digest = Blake2b256::digest(&bytes[..length]);
@@ -39,7 +45,7 @@ pub fn pow_hash(bytes: &[u8]) -> TritBuf<T1B1Buf> {
     pow_input.push(Btrit::Zero);
     pow_input.push(Btrit::Zero);
 
-    curl.digest(&pow_input).unwrap()
+    pow_input
 }
 
 /// Returns the number of trailing zeros of a Proof of Work hash.
@@ -3,13 +3,17 @@
 
 use bee_pow::{
     providers::{
-        miner::{MinerBuilder, MinerCancel},
+        miner::{Error, MinerBuilder, MinerCancel},
         NonceProvider, NonceProviderBuilder,
     },
     score::compute_pow_score,
 };
 use bee_test::rand::bytes::rand_bytes;
 
+use futures::executor::block_on;
+
+use std::time::{Duration, Instant};
+
 #[test]
 fn miner_provide() {
     let miner = MinerBuilder::new().with_num_workers(4).finish();
@@ -55,3 +59,46 @@ fn miner_abort() {
     assert!(now.elapsed().as_secs() < 2);
     assert!(matches!(handle.join(), Ok(0)));
 }
+
+#[test]
+fn miner_job_provide() {
+    let miner = MinerBuilder::new().with_num_workers(4).finish();
+    let mut bytes = rand_bytes(256);
+
+    let job = miner.spawn(&bytes[0..248], 4000f64, None).unwrap();
+    let nonce = block_on(job).unwrap();
+    bytes[248..].copy_from_slice(&nonce.to_le_bytes());
+
+    assert!(compute_pow_score(&bytes) >= 4000f64);
+}
+
+#[test]
+fn miner_jobs_are_cancelled_independently() {
+    let miner = MinerBuilder::new().with_num_workers(2).finish();
+    let bytes = rand_bytes(248);
+
+    let hard = miner.spawn(&bytes, 1e12f64, None).unwrap();
+    let easy = miner.spawn(&bytes, 4000f64, None).unwrap();
+
+    std::thread::sleep(Duration::from_millis(200));
+    hard.cancel();
+
+    assert!(hard.progress().hashes() > 0);
+    assert!(hard.progress().remaining_hashes() > 0f64);
+    assert!(matches!(block_on(hard), Err(Error::Cancelled)));
+    assert!(block_on(easy).is_ok());
+}
+
+#[test]
+fn miner_job_deadline() {
+    let miner = MinerBuilder::new().with_num_workers(2).finish();
+    let bytes = rand_bytes(248);
+    let now = Instant::now();
+
+    let job = miner
+        .spawn(&bytes, 1e12f64, Some(now + Duration::from_millis(200)))
+        .unwrap();
+
+    assert!(matches!(block_on(job), Err(Error::DeadlineExceeded)));
+    assert!(now.elapsed() < Duration::from_secs(2));
+}
@@ -0,0 +1,157 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "remote")]
+
+use bee_pow::{
+    providers::{
+        miner::MinerBuilder,
+        remote::{Error, RemoteBuilder},
+        NonceProvider, NonceProviderBuilder,
+    },
+    score::compute_pow_score,
+};
+use bee_test::rand::bytes::rand_bytes;
+
+use serde_json::Value;
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    thread,
+    time::Duration,
+};
+
+/// Starts a stand-in PoW endpoint answering requests with the body returned by `answer`, and returns its URL.
+fn endpoint(answer: impl Fn(&[u8], f64) -> String + Send + 'static) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/pow", listener.local_addr().unwrap());
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            serve(stream.unwrap(), &answer);
+        }
+    });
+
+    url
+}
+
+fn serve(mut stream: TcpStream, answer: &impl Fn(&[u8], f64) -> String) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut content_length = 0;
+
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+
+        if line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+    }
+
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).unwrap();
+
+    let request: Value = serde_json::from_slice(&body).unwrap();
+    let bytes = hex::decode(request["bytes"].as_str().unwrap()).unwrap();
+    let body = answer(&bytes, request["target_score"].as_f64().unwrap());
+
+    // The client may have given up waiting already.
+    let _ = write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+}
+
+fn mining_endpoint() -> String {
+    endpoint(|bytes, target_score| {
+        let nonce = MinerBuilder::new()
+            .with_num_workers(2)
+            .finish()
+            .nonce(bytes, target_score)
+            .unwrap();
+
+        format!(r#"{{"nonce":"{}"}}"#, nonce)
+    })
+}
+
+fn cheating_endpoint() -> String {
+    endpoint(|_, _| r#"{"nonce":"0"}"#.to_string())
+}
+
+fn has_score(bytes: &[u8], nonce: u64, target_score: f64) -> bool {
+    compute_pow_score(&[bytes, &nonce.to_le_bytes()].concat()) >= target_score
+}
+
+#[test]
+fn remote_provide() {
+    let remote = RemoteBuilder::default().with_endpoint(mining_endpoint()).finish();
+    let bytes = rand_bytes(248);
+
+    let nonce = remote.nonce(&bytes, 4000f64).unwrap();
+
+    assert!(has_score(&bytes, nonce, 4000f64));
+}
+
+#[test]
+fn remote_skips_invalid_nonces() {
+    let remote = RemoteBuilder::default()
+        .with_endpoint(cheating_endpoint())
+        .with_endpoint(mining_endpoint())
+        .finish();
+    let bytes = rand_bytes(248);
+
+    let nonce = remote.nonce(&bytes, 4000f64).unwrap();
+
+    assert!(has_score(&bytes, nonce, 4000f64));
+}
+
+#[test]
+fn remote_rejects_invalid_nonces() {
+    let remote = RemoteBuilder::default().with_endpoint(cheating_endpoint()).finish();
+    // A nonce of 0 is unlikely to reach such a score.
+    let bytes = rand_bytes(248);
+
+    assert!(matches!(remote.nonce(&bytes, 1e9f64), Err(Error::InsufficientScore(_))));
+}
+
+#[test]
+fn remote_falls_back_to_miner() {
+    // Nothing listens on the port of a dropped listener.
+    let unreachable = format!(
+        "http://{}/pow",
+        TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap()
+    );
+    let slow = endpoint(|_, _| {
+        thread::sleep(Duration::from_secs(5));
+        r#"{"nonce":"0"}"#.to_string()
+    });
+    let remote = RemoteBuilder::default()
+        .with_endpoint(unreachable)
+        .with_endpoint(slow)
+        .with_timeout(Duration::from_millis(500))
+        .with_local_fallback(MinerBuilder::new().with_num_workers(2))
+        .finish();
+    let bytes = rand_bytes(248);
+
+    let nonce = remote.nonce(&bytes, 4000f64).unwrap();
+
+    assert!(has_score(&bytes, nonce, 4000f64));
+}
+
+#[test]
+fn remote_without_endpoints() {
+    let remote = RemoteBuilder::default().finish();
+
+    assert!(matches!(
+        remote.nonce(&rand_bytes(248), 4000f64),
+        Err(Error::NoEndpoint)
+    ));
+}
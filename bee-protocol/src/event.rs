@@ -0,0 +1,82 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that provides the events dispatched by the protocol workers.
+
+use bee_message::{
+    milestone::{Milestone, MilestoneIndex},
+    MessageId,
+};
+use bee_network::PeerId;
+
+use std::time::Duration;
+
+/// An event that indicates that the protocol started exchanging packets with a peer.
+#[derive(Clone)]
+pub struct PeerConnected {
+    /// The peer's id.
+    pub peer_id: PeerId,
+}
+
+/// An event that indicates that the protocol stopped exchanging packets with a peer.
+#[derive(Clone)]
+pub struct PeerDisconnected {
+    /// The peer's id.
+    pub peer_id: PeerId,
+}
+
+/// An event that indicates that the reputation score of a connected peer was updated.
+#[derive(Clone)]
+pub struct PeerScoreUpdated {
+    /// The peer's id.
+    pub peer_id: PeerId,
+    /// The peer's score.
+    pub score: i32,
+}
+
+/// An event that indicates that a message received from a peer was processed and inserted into the tangle.
+#[derive(Clone)]
+pub struct MessageProcessed {
+    /// The message's id.
+    pub message_id: MessageId,
+}
+
+/// An event that indicates that a valid milestone was processed and registered in the tangle.
+#[derive(Clone)]
+pub struct MilestoneProcessed {
+    /// The milestone's index.
+    pub index: MilestoneIndex,
+    /// The milestone's data.
+    pub milestone: Milestone,
+}
+
+/// An event that indicates that the node fell behind its latest milestone and started catching up.
+#[derive(Clone)]
+pub struct WarpSyncStarted {
+    /// The solid milestone index the warp sync started from.
+    pub from: MilestoneIndex,
+    /// The latest milestone index at the time the warp sync started.
+    pub to: MilestoneIndex,
+}
+
+/// An event that indicates the progress of a warp sync, whenever a milestone got solid.
+#[derive(Clone)]
+pub struct WarpSyncProgress {
+    /// The new solid milestone index.
+    pub index: MilestoneIndex,
+    /// The latest milestone index.
+    pub target: MilestoneIndex,
+    /// The estimated time until the latest milestone is solid, given the pace of the warp sync so far.
+    pub eta: Option<Duration>,
+}
+
+/// An event that indicates that a warp sync caught up with the latest milestone.
+#[derive(Clone)]
+pub struct WarpSyncCompleted {
+    /// The solid milestone index the warp sync started from.
+    pub from: MilestoneIndex,
+    /// The solid milestone index the warp sync ended at.
+    pub to: MilestoneIndex,
+    /// How long the warp sync took.
+    pub duration: Duration,
+}
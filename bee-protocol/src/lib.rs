@@ -5,4 +5,6 @@
 
 #![deny(missing_docs, warnings)]
 
+pub mod event;
 pub mod types;
+pub mod workers;
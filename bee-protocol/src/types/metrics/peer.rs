@@ -11,6 +11,8 @@ pub struct PeerMetrics {
     invalid_messages: AtomicU64,
     new_messages: AtomicU64,
     known_messages: AtomicU64,
+    rate_limited_messages: AtomicU64,
+    filtered_messages: AtomicU64,
     invalid_packets: AtomicU64,
     milestone_requests_received: AtomicU64,
     messages_received: AtomicU64,
@@ -53,6 +55,26 @@ impl PeerMetrics {
         self.known_messages.fetch_add(1, Ordering::SeqCst)
     }
 
+    /// Returns the number of rate limited messages of the `PeerMetrics`.
+    pub fn rate_limited_messages(&self) -> u64 {
+        self.rate_limited_messages.load(Ordering::Relaxed)
+    }
+
+    /// Increments the number of rate limited messages of the `PeerMetrics`.
+    pub fn rate_limited_messages_inc(&self) -> u64 {
+        self.rate_limited_messages.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Returns the number of filtered messages of the `PeerMetrics`.
+    pub fn filtered_messages(&self) -> u64 {
+        self.filtered_messages.load(Ordering::Relaxed)
+    }
+
+    /// Increments the number of filtered messages of the `PeerMetrics`.
+    pub fn filtered_messages_inc(&self) -> u64 {
+        self.filtered_messages.fetch_add(1, Ordering::SeqCst)
+    }
+
     /// Returns the number of invalid packets of the `PeerMetrics`.
     pub fn invalid_packets(&self) -> u64 {
         self.invalid_packets.load(Ordering::Relaxed)
@@ -156,14 +178,20 @@ mod tests {
         assert_eq!(metrics.invalid_messages(), 0);
         assert_eq!(metrics.new_messages(), 0);
         assert_eq!(metrics.known_messages(), 0);
+        assert_eq!(metrics.rate_limited_messages(), 0);
+        assert_eq!(metrics.filtered_messages(), 0);
 
         metrics.invalid_messages_inc();
         metrics.new_messages_inc();
         metrics.known_messages_inc();
+        metrics.rate_limited_messages_inc();
+        metrics.filtered_messages_inc();
 
         assert_eq!(metrics.invalid_messages(), 1);
         assert_eq!(metrics.new_messages(), 1);
         assert_eq!(metrics.known_messages(), 1);
+        assert_eq!(metrics.rate_limited_messages(), 1);
+        assert_eq!(metrics.filtered_messages(), 1);
     }
 
     #[test]
@@ -6,4 +6,5 @@
 pub mod metrics;
 pub mod milestone_key_manager;
 pub mod milestone_key_range;
+pub mod packets;
 pub mod peer;
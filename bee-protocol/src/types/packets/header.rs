@@ -0,0 +1,30 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+/// The size of a serialized [`HeaderPacket`].
+pub const HEADER_SIZE: usize = 3;
+
+/// The header preceding every packet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HeaderPacket {
+    /// The type of the packet.
+    pub packet_type: u8,
+    /// The size of the payload following the header.
+    pub packet_length: u16,
+}
+
+impl HeaderPacket {
+    /// Deserializes a header.
+    pub fn from_bytes(bytes: &[u8; HEADER_SIZE]) -> Self {
+        Self {
+            packet_type: bytes[0],
+            packet_length: u16::from_le_bytes([bytes[1], bytes[2]]),
+        }
+    }
+
+    /// Serializes the header into a buffer of [`HEADER_SIZE`] bytes.
+    pub fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes[0] = self.packet_type;
+        bytes[1..HEADER_SIZE].copy_from_slice(&self.packet_length.to_le_bytes());
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::Packet;
+
+use std::{convert::TryInto, ops::Range};
+
+const SOLID_MILESTONE_INDEX_SIZE: usize = 4;
+const PRUNED_INDEX_SIZE: usize = 4;
+const LATEST_MILESTONE_INDEX_SIZE: usize = 4;
+const CONNECTED_PEERS_SIZE: usize = 1;
+const SYNCED_PEERS_SIZE: usize = 1;
+const CONSTANT_SIZE: usize = SOLID_MILESTONE_INDEX_SIZE
+    + PRUNED_INDEX_SIZE
+    + LATEST_MILESTONE_INDEX_SIZE
+    + CONNECTED_PEERS_SIZE
+    + SYNCED_PEERS_SIZE;
+
+/// A packet that informs a peer about the milestone indexes and the number of peers of the sender.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HeartbeatPacket {
+    /// The index of the latest solid milestone.
+    pub solid_milestone_index: u32,
+    /// The index up to which the sender pruned its database.
+    pub pruned_index: u32,
+    /// The index of the latest known milestone.
+    pub latest_milestone_index: u32,
+    /// The number of connected peers.
+    pub connected_peers: u8,
+    /// The number of connected peers that are synced.
+    pub synced_peers: u8,
+}
+
+impl Packet for HeartbeatPacket {
+    const ID: u8 = 0x04;
+
+    fn size_range() -> Range<usize> {
+        CONSTANT_SIZE..CONSTANT_SIZE + 1
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        // Panic:
+        // The size of the payload was checked against `size_range`, hence the slices have the right lengths.
+        Self {
+            solid_milestone_index: u32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+            pruned_index: u32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+            latest_milestone_index: u32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+            connected_peers: bytes[12],
+            synced_peers: bytes[13],
+        }
+    }
+
+    fn size(&self) -> usize {
+        CONSTANT_SIZE
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes[0..4].copy_from_slice(&self.solid_milestone_index.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.pruned_index.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.latest_milestone_index.to_le_bytes());
+        bytes[12] = self.connected_peers;
+        bytes[13] = self.synced_peers;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::packets::{tlv_from_bytes, tlv_to_bytes, Error, HeaderPacket, HEADER_SIZE};
+
+    use std::convert::TryInto;
+
+    #[test]
+    fn tlv_round_trip() {
+        let heartbeat = HeartbeatPacket {
+            solid_milestone_index: 1,
+            pruned_index: 2,
+            latest_milestone_index: 3,
+            connected_peers: 4,
+            synced_peers: 5,
+        };
+
+        let bytes = tlv_to_bytes(&heartbeat);
+        let header = HeaderPacket::from_bytes(bytes[..HEADER_SIZE].try_into().unwrap());
+
+        assert_eq!(header.packet_type, HeartbeatPacket::ID);
+        assert_eq!(header.packet_length as usize, CONSTANT_SIZE);
+        assert_eq!(
+            tlv_from_bytes::<HeartbeatPacket>(&header, &bytes[HEADER_SIZE..]),
+            Ok(heartbeat)
+        );
+    }
+
+    #[test]
+    fn invalid_length() {
+        let header = HeaderPacket {
+            packet_type: HeartbeatPacket::ID,
+            packet_length: 13,
+        };
+
+        assert_eq!(
+            tlv_from_bytes::<HeartbeatPacket>(&header, &[0u8; 13]),
+            Err(Error::InvalidLength(13))
+        );
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::Packet;
+
+use bee_message::{MESSAGE_LENGTH_MAX, MESSAGE_LENGTH_MIN};
+
+use std::ops::Range;
+
+/// A packet that carries a serialized message.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MessagePacket {
+    /// The bytes of the message.
+    pub bytes: Vec<u8>,
+}
+
+impl MessagePacket {
+    /// Creates a new `MessagePacket`.
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+}
+
+impl Packet for MessagePacket {
+    const ID: u8 = 0x02;
+
+    fn size_range() -> Range<usize> {
+        MESSAGE_LENGTH_MIN..MESSAGE_LENGTH_MAX + 1
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self { bytes: bytes.to_vec() }
+    }
+
+    fn size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::packets::{tlv_from_bytes, tlv_to_bytes, Error, HeaderPacket, HEADER_SIZE};
+
+    use std::convert::TryInto;
+
+    #[test]
+    fn tlv_round_trip() {
+        let message = MessagePacket::new(vec![0x2a; 500]);
+
+        let bytes = tlv_to_bytes(&message);
+        let header = HeaderPacket::from_bytes(bytes[..HEADER_SIZE].try_into().unwrap());
+
+        assert_eq!(header.packet_type, MessagePacket::ID);
+        assert_eq!(header.packet_length, 500);
+        assert_eq!(
+            tlv_from_bytes::<MessagePacket>(&header, &bytes[HEADER_SIZE..]),
+            Ok(message)
+        );
+    }
+
+    #[test]
+    fn invalid_length() {
+        for length in [MESSAGE_LENGTH_MIN - 1, MESSAGE_LENGTH_MAX + 1].iter().copied() {
+            let header = HeaderPacket {
+                packet_type: MessagePacket::ID,
+                packet_length: length as u16,
+            };
+
+            assert_eq!(
+                tlv_from_bytes::<MessagePacket>(&header, &vec![0u8; length]),
+                Err(Error::InvalidLength(length))
+            );
+        }
+    }
+}
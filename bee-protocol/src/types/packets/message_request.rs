@@ -0,0 +1,81 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::Packet;
+
+use bee_message::{MessageId, MESSAGE_ID_LENGTH};
+
+use std::{convert::TryInto, ops::Range};
+
+const CONSTANT_SIZE: usize = MESSAGE_ID_LENGTH;
+
+/// A packet that requests the message with a given id.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MessageRequestPacket {
+    /// The id of the requested message.
+    pub message_id: MessageId,
+}
+
+impl MessageRequestPacket {
+    /// Creates a new `MessageRequestPacket`.
+    pub fn new(message_id: MessageId) -> Self {
+        Self { message_id }
+    }
+}
+
+impl Packet for MessageRequestPacket {
+    const ID: u8 = 0x03;
+
+    fn size_range() -> Range<usize> {
+        CONSTANT_SIZE..CONSTANT_SIZE + 1
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        // Panic:
+        // The size of the payload was checked against `size_range`, hence the slice has the right length.
+        Self {
+            message_id: MessageId::new(bytes.try_into().unwrap()),
+        }
+    }
+
+    fn size(&self) -> usize {
+        CONSTANT_SIZE
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(self.message_id.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::packets::{tlv_from_bytes, tlv_to_bytes, Error, HeaderPacket, HEADER_SIZE};
+
+    #[test]
+    fn tlv_round_trip() {
+        let request = MessageRequestPacket::new(MessageId::new([0x2a; MESSAGE_ID_LENGTH]));
+
+        let bytes = tlv_to_bytes(&request);
+        let header = HeaderPacket::from_bytes(bytes[..HEADER_SIZE].try_into().unwrap());
+
+        assert_eq!(header.packet_type, MessageRequestPacket::ID);
+        assert_eq!(
+            tlv_from_bytes::<MessageRequestPacket>(&header, &bytes[HEADER_SIZE..]),
+            Ok(request)
+        );
+    }
+
+    #[test]
+    fn invalid_length() {
+        let header = HeaderPacket {
+            packet_type: MessageRequestPacket::ID,
+            packet_length: 31,
+        };
+
+        assert_eq!(
+            tlv_from_bytes::<MessageRequestPacket>(&header, &[0u8; 31]),
+            Err(Error::InvalidLength(31))
+        );
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use super::Packet;
+
+use std::{convert::TryInto, ops::Range};
+
+const INDEX_SIZE: usize = 4;
+const CONSTANT_SIZE: usize = INDEX_SIZE;
+
+/// A packet that requests the milestone with a given index.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MilestoneRequestPacket {
+    /// The index of the requested milestone, 0 requesting the latest one.
+    pub index: u32,
+}
+
+impl MilestoneRequestPacket {
+    /// Creates a new `MilestoneRequestPacket`.
+    pub fn new(index: u32) -> Self {
+        Self { index }
+    }
+}
+
+impl Packet for MilestoneRequestPacket {
+    const ID: u8 = 0x01;
+
+    fn size_range() -> Range<usize> {
+        CONSTANT_SIZE..CONSTANT_SIZE + 1
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        // Panic:
+        // The size of the payload was checked against `size_range`, hence the slice has the right length.
+        Self {
+            index: u32::from_le_bytes(bytes[0..INDEX_SIZE].try_into().unwrap()),
+        }
+    }
+
+    fn size(&self) -> usize {
+        CONSTANT_SIZE
+    }
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&self.index.to_le_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::packets::{tlv_from_bytes, tlv_to_bytes, HeaderPacket, HEADER_SIZE};
+
+    #[test]
+    fn tlv_round_trip() {
+        let request = MilestoneRequestPacket::new(0x0102_0304);
+
+        let bytes = tlv_to_bytes(&request);
+        let header = HeaderPacket::from_bytes(bytes[..HEADER_SIZE].try_into().unwrap());
+
+        assert_eq!(header.packet_type, MilestoneRequestPacket::ID);
+        assert_eq!(&bytes[HEADER_SIZE..], &[0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(
+            tlv_from_bytes::<MilestoneRequestPacket>(&header, &bytes[HEADER_SIZE..]),
+            Ok(request)
+        );
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that provides the packets exchanged between peers.
+//!
+//! Packets are framed as type-length-value: a [`HeaderPacket`] holding the type and the length of the payload,
+//! followed by the payload itself.
+
+mod header;
+mod heartbeat;
+mod message;
+mod message_request;
+mod milestone_request;
+
+pub use header::{HeaderPacket, HEADER_SIZE};
+pub use heartbeat::HeartbeatPacket;
+pub use message::MessagePacket;
+pub use message_request::MessageRequestPacket;
+pub use milestone_request::MilestoneRequestPacket;
+
+use std::{fmt, ops::Range};
+
+/// A packet exchanged between peers.
+pub trait Packet: Sized {
+    /// The type of the packet, as sent in its header.
+    const ID: u8;
+
+    /// Returns the range of valid payload sizes.
+    fn size_range() -> Range<usize>;
+
+    /// Deserializes a payload whose size is within [`Packet::size_range`].
+    fn from_bytes(bytes: &[u8]) -> Self;
+
+    /// Returns the size of the serialized payload.
+    fn size(&self) -> usize;
+
+    /// Serializes the payload into a buffer of [`Packet::size`] bytes.
+    fn to_bytes(&self, bytes: &mut [u8]);
+}
+
+/// Errors occurring while deserializing a packet.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Error {
+    /// The header announces a different packet type.
+    InvalidType(u8),
+    /// The payload size is not valid for the packet type.
+    InvalidLength(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidType(packet_type) => write!(f, "Invalid packet type {}.", packet_type),
+            Error::InvalidLength(length) => write!(f, "Invalid packet length {}.", length),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Serializes a packet, preceded by its header.
+pub fn tlv_to_bytes<P: Packet>(packet: &P) -> Vec<u8> {
+    let size = packet.size();
+    let mut bytes = vec![0u8; HEADER_SIZE + size];
+
+    HeaderPacket {
+        packet_type: P::ID,
+        packet_length: size as u16,
+    }
+    .to_bytes(&mut bytes[..HEADER_SIZE]);
+    packet.to_bytes(&mut bytes[HEADER_SIZE..]);
+
+    bytes
+}
+
+/// Deserializes the payload of a packet announced by `header`.
+pub fn tlv_from_bytes<P: Packet>(header: &HeaderPacket, bytes: &[u8]) -> Result<P, Error> {
+    if header.packet_type != P::ID {
+        return Err(Error::InvalidType(header.packet_type));
+    }
+
+    if header.packet_length as usize != bytes.len() || !P::size_range().contains(&bytes.len()) {
+        return Err(Error::InvalidLength(bytes.len()));
+    }
+
+    Ok(P::from_bytes(bytes))
+}
@@ -5,15 +5,21 @@
 
 use crate::types::metrics::PeerMetrics;
 
-use bee_message::milestone::MilestoneIndex;
+use bee_message::{milestone::MilestoneIndex, MessageId};
 use bee_network::{Multiaddr, PeerId, PeerInfo, PeerRelation};
 
+use lru::LruCache;
+
 use std::{
-    sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering},
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering},
+        Mutex,
+    },
     time::{SystemTime, UNIX_EPOCH},
 };
 
 const SYNCED_THRESHOLD: u32 = 2;
+const KNOWN_MESSAGES_CAPACITY: usize = 5_000;
 
 /// A type holding information related to a peer.
 pub struct Peer {
@@ -28,6 +34,7 @@ pub struct Peer {
     synced_peers: AtomicU8,
     heartbeat_sent_timestamp: AtomicU64,
     heartbeat_received_timestamp: AtomicU64,
+    known_messages: Mutex<LruCache<MessageId, ()>>,
 }
 
 impl Peer {
@@ -45,6 +52,7 @@ impl Peer {
             synced_peers: AtomicU8::new(0),
             heartbeat_sent_timestamp: AtomicU64::new(0),
             heartbeat_received_timestamp: AtomicU64::new(0),
+            known_messages: Mutex::new(LruCache::new(KNOWN_MESSAGES_CAPACITY)),
         }
     }
 
@@ -165,6 +173,18 @@ impl Peer {
         self.heartbeat_received_timestamp.load(Ordering::Relaxed)
     }
 
+    /// Remembers that the `Peer` has a message, e.g. because it sent it, so that it doesn't get sent to it.
+    ///
+    /// Only the most recent messages are remembered.
+    pub fn set_known_message(&self, message_id: MessageId) {
+        self.known_messages.lock().unwrap().put(message_id, ());
+    }
+
+    /// Returns whether the `Peer` is known to have a message.
+    pub fn knows_message(&self, message_id: &MessageId) -> bool {
+        self.known_messages.lock().unwrap().contains(message_id)
+    }
+
     /// Returns whether the `Peer` is synced or not.
     pub fn is_synced(&self) -> bool {
         self.is_synced_threshold(SYNCED_THRESHOLD)
@@ -175,6 +195,11 @@ impl Peer {
         *self.solid_milestone_index() >= (*self.latest_milestone_index()).saturating_sub(threshold)
     }
 
+    /// Returns whether the solid milestone index of the `Peer` is close enough to a given latest milestone index.
+    pub fn is_synced_with(&self, latest_milestone_index: MilestoneIndex) -> bool {
+        *self.solid_milestone_index() >= (*latest_milestone_index).saturating_sub(SYNCED_THRESHOLD)
+    }
+
     /// Returns whether the `Peer` has the data referenced by a given milestone index.
     pub fn has_data(&self, index: MilestoneIndex) -> bool {
         // +1 to allow for a little delay before a Heartbeat comes from a peer.
@@ -0,0 +1,10 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that gossips new messages to the connected peers.
+
+mod worker;
+
+pub use worker::BroadcasterWorker;
+
+pub(crate) use worker::BroadcasterWorkerEvent;
@@ -0,0 +1,173 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    types::{
+        metrics::NodeMetrics,
+        packets::{tlv_to_bytes, MessagePacket},
+        peer::Peer,
+    },
+    workers::{
+        metrics::NodeMetricsResWorker,
+        peer::{PeerManager, PeerManagerResWorker},
+    },
+};
+
+use bee_message::MessageId;
+use bee_network::{GossipError, PeerId};
+use bee_runtime::{node::Node, shutdown_stream::ShutdownStream, worker::Worker};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use log::{debug, info, trace};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use std::{any::TypeId, convert::Infallible};
+
+pub(crate) struct BroadcasterWorkerEvent {
+    /// The peer the message was received from, `None` if it originates from the node itself.
+    pub(crate) source: Option<PeerId>,
+    pub(crate) message_id: MessageId,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// A worker that gossips new messages to the connected peers that aren't known to have them already.
+pub struct BroadcasterWorker {
+    pub(crate) tx: mpsc::UnboundedSender<BroadcasterWorkerEvent>,
+}
+
+#[async_trait]
+impl<N: Node> Worker<N> for BroadcasterWorker {
+    type Config = ();
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        vec![
+            TypeId::of::<PeerManagerResWorker>(),
+            TypeId::of::<NodeMetricsResWorker>(),
+        ]
+        .leak()
+    }
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        let peer_manager = node.resource::<PeerManager>();
+        let metrics = node.resource::<NodeMetrics>();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let mut receiver = ShutdownStream::new(shutdown, UnboundedReceiverStream::new(rx));
+
+            while let Some(BroadcasterWorkerEvent {
+                source,
+                message_id,
+                bytes,
+            }) = receiver.next().await
+            {
+                let packet = tlv_to_bytes(&MessagePacket::new(bytes));
+
+                peer_manager
+                    .for_each_connected(|peer, sender| {
+                        // Waiting for a slow peer would hold the message back from all the others.
+                        if gossip(peer, source.as_ref(), message_id, || sender.try_send(packet.clone())) {
+                            metrics.messages_sent_inc();
+                        }
+                    })
+                    .await;
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self { tx })
+    }
+}
+
+/// Sends a message to a peer with `send`, unless the message came from that peer or the peer is known to have it
+/// already. Returns whether the message was sent.
+fn gossip(
+    peer: &Peer,
+    source: Option<&PeerId>,
+    message_id: MessageId,
+    send: impl FnOnce() -> Result<(), GossipError>,
+) -> bool {
+    if source == Some(peer.id()) || peer.knows_message(&message_id) {
+        return false;
+    }
+
+    match send() {
+        Ok(()) => {
+            trace!("Sent message {} to {}.", message_id, peer.alias());
+            peer.set_known_message(message_id);
+            peer.metrics().messages_sent_inc();
+            true
+        }
+        Err(e) => {
+            debug!("Sending message {} to {} failed: {}.", message_id, peer.alias(), e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bee_network::{PeerInfo, PeerRelation};
+
+    fn peer() -> Peer {
+        Peer::new(
+            PeerId::random(),
+            PeerInfo::new(
+                "/ip4/127.0.0.1/tcp/15600".parse().unwrap(),
+                String::from("peer"),
+                PeerRelation::Known,
+            ),
+        )
+    }
+
+    #[test]
+    fn message_is_not_echoed_to_its_source() {
+        let peer = peer();
+        let message_id = MessageId::new([1; 32]);
+
+        assert!(!gossip(&peer, Some(peer.id()), message_id, || panic!(
+            "sent to the source"
+        )));
+        assert_eq!(peer.metrics().messages_sent(), 0);
+    }
+
+    #[test]
+    fn peers_knowing_the_message_are_skipped() {
+        let peer = peer();
+        let message_id = MessageId::new([1; 32]);
+
+        peer.set_known_message(message_id);
+
+        assert!(!gossip(&peer, None, message_id, || panic!(
+            "sent to a peer knowing the message"
+        )));
+        assert_eq!(peer.metrics().messages_sent(), 0);
+    }
+
+    #[test]
+    fn messages_sent_are_accounted() {
+        let peer = peer();
+        let source = PeerId::random();
+        let (first, second) = (MessageId::new([1; 32]), MessageId::new([2; 32]));
+
+        assert!(gossip(&peer, Some(&source), first, || Ok(())));
+        assert!(peer.knows_message(&first));
+        assert_eq!(peer.metrics().messages_sent(), 1);
+
+        // A message is only sent once to a peer.
+        assert!(!gossip(&peer, None, first, || Ok(())));
+
+        // Failed sends are neither counted, nor make the message known to the peer.
+        assert!(!gossip(&peer, None, second, || Err(GossipError::Full)));
+        assert!(!peer.knows_message(&second));
+        assert_eq!(peer.metrics().messages_sent(), 1);
+    }
+}
@@ -0,0 +1,8 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that informs the connected peers about the state of the node.
+
+mod worker;
+
+pub use worker::HeartbeatWorker;
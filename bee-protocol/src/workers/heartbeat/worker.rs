@@ -0,0 +1,143 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    event::PeerConnected,
+    types::{
+        metrics::NodeMetrics,
+        packets::{tlv_to_bytes, HeartbeatPacket},
+        peer::Peer,
+    },
+    workers::{
+        metrics::NodeMetricsResWorker,
+        peer::{PeerManager, PeerManagerResWorker},
+    },
+};
+
+use bee_network::{GossipSender, PeerId};
+use bee_runtime::{node::Node, shutdown_stream::ShutdownStream, worker::Worker};
+use bee_tangle::{
+    event::{PruningMilestoneIndexChanged, SolidMilestoneChanged},
+    storage::StorageBackend,
+    MsTangle, TangleWorker,
+};
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use log::{debug, info};
+use tokio::{sync::mpsc, time::interval};
+use tokio_stream::wrappers::{IntervalStream, UnboundedReceiverStream};
+
+use std::{any::TypeId, convert::Infallible, time::Duration};
+
+// In seconds
+const HEARTBEAT_INTERVAL: u64 = 30;
+
+/// A worker that sends a heartbeat with the milestone indexes and the peer counts of the node to the connected peers.
+///
+/// Heartbeats are sent periodically, to all peers whenever the solid or the pruning milestone index changed, and to a
+/// peer as soon as it connected.
+#[derive(Default)]
+pub struct HeartbeatWorker {}
+
+#[async_trait]
+impl<N: Node> Worker<N> for HeartbeatWorker
+where
+    N::Backend: StorageBackend,
+{
+    type Config = ();
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        vec![
+            TypeId::of::<TangleWorker>(),
+            TypeId::of::<PeerManagerResWorker>(),
+            TypeId::of::<NodeMetricsResWorker>(),
+        ]
+        .leak()
+    }
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        let tangle = node.resource::<MsTangle<N::Backend>>();
+        let peer_manager = node.resource::<PeerManager>();
+        let metrics = node.resource::<NodeMetrics>();
+
+        // `None` targets all connected peers, `Some` a single one.
+        let (tx, rx) = mpsc::unbounded_channel::<Option<PeerId>>();
+
+        {
+            let tx = tx.clone();
+            node.bus().add_listener::<Self, SolidMilestoneChanged, _>(move |_| {
+                let _ = tx.send(None);
+            });
+        }
+        {
+            let tx = tx.clone();
+            node.bus()
+                .add_listener::<Self, PruningMilestoneIndexChanged, _>(move |_| {
+                    let _ = tx.send(None);
+                });
+        }
+        node.bus().add_listener::<Self, PeerConnected, _>(move |event| {
+            let _ = tx.send(Some(event.peer_id));
+        });
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let ticker = IntervalStream::new(interval(Duration::from_secs(HEARTBEAT_INTERVAL))).map(|_| None);
+            let mut targets = ShutdownStream::new(shutdown, stream::select(ticker, UnboundedReceiverStream::new(rx)));
+
+            while let Some(target) = targets.next().await {
+                let (connected, synced) = peer_manager
+                    .connected_and_synced(tangle.get_latest_milestone_index())
+                    .await;
+
+                let heartbeat = tlv_to_bytes(&HeartbeatPacket {
+                    solid_milestone_index: *tangle.get_solid_milestone_index(),
+                    pruned_index: *tangle.get_pruning_index(),
+                    latest_milestone_index: *tangle.get_latest_milestone_index(),
+                    connected_peers: connected.min(u8::MAX as usize) as u8,
+                    synced_peers: synced.min(u8::MAX as usize) as u8,
+                });
+
+                match target {
+                    Some(peer_id) => {
+                        if let Some(peer) = peer_manager.get(&peer_id).await {
+                            if let Some(sender) = peer_manager.sender(&peer_id).await {
+                                send_heartbeat(&metrics, &peer, &sender, heartbeat);
+                            }
+                        }
+                    }
+                    None => {
+                        for (peer, sender) in peer_manager.connected().await {
+                            send_heartbeat(&metrics, &peer, &sender, heartbeat.clone());
+                        }
+                    }
+                }
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self::default())
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        node.bus().remove_listeners_by_id(TypeId::of::<Self>());
+
+        Ok(())
+    }
+}
+
+fn send_heartbeat(metrics: &NodeMetrics, peer: &Peer, sender: &GossipSender, heartbeat: Vec<u8>) {
+    // Heartbeats are periodic, hence a heartbeat that doesn't fit into a full queue is not worth waiting for.
+    match sender.try_send(heartbeat) {
+        Ok(()) => {
+            metrics.heartbeats_sent_inc();
+            peer.metrics().heartbeats_sent_inc();
+            peer.set_heartbeat_sent_timestamp();
+        }
+        Err(e) => debug!("Sending heartbeat to {} failed: {}.", peer.alias(), e),
+    }
+}
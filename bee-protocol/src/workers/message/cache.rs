@@ -0,0 +1,35 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_message::MessageId;
+
+use lru::LruCache;
+
+use std::sync::{Arc, Mutex};
+
+/// The ids of the messages that were recently handed to the processor and weren't rejected by it, shared by the
+/// [`HasherWorker`](crate::workers::HasherWorker) and the [`ProcessorWorker`](crate::workers::ProcessorWorker).
+///
+/// An id is only added once a message passed the checks of the hasher, and is removed again if the processor rejects
+/// the message, so a rejected copy doesn't cause the copies sent by other peers to be dropped as known.
+#[derive(Clone)]
+pub(crate) struct MessageCache(Arc<Mutex<LruCache<MessageId, ()>>>);
+
+impl MessageCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self(Arc::new(Mutex::new(LruCache::new(capacity))))
+    }
+
+    /// Returns whether the cache contains the id, and marks it as recently used if so.
+    pub(crate) fn contains(&self, message_id: &MessageId) -> bool {
+        self.0.lock().unwrap().get(message_id).is_some()
+    }
+
+    pub(crate) fn insert(&self, message_id: MessageId) {
+        self.0.lock().unwrap().put(message_id, ());
+    }
+
+    pub(crate) fn remove(&self, message_id: &MessageId) {
+        self.0.lock().unwrap().pop(message_id);
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crypto::hashes::{blake2b::Blake2b256, Digest};
+use serde::Deserialize;
+
+use std::convert::TryInto;
+
+const DEFAULT_NETWORK_NAME: &str = "chrysalis-mainnet";
+const DEFAULT_MINIMUM_POW_SCORE: f64 = 4000.0;
+const DEFAULT_CACHE_SIZE: usize = 10_000;
+
+/// A builder type for a message processing configuration.
+#[derive(Default, Deserialize)]
+pub struct MessageConfigBuilder {
+    network_name: Option<String>,
+    minimum_pow_score: Option<f64>,
+    cache_size: Option<usize>,
+}
+
+impl MessageConfigBuilder {
+    /// Creates a new `MessageConfigBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the network the messages have to belong to.
+    pub fn network_name(mut self, network_name: String) -> Self {
+        self.network_name.replace(network_name);
+        self
+    }
+
+    /// Sets the Proof of Work score below which messages are rejected.
+    pub fn minimum_pow_score(mut self, minimum_pow_score: f64) -> Self {
+        self.minimum_pow_score.replace(minimum_pow_score);
+        self
+    }
+
+    /// Sets the number of recently accepted message ids that are remembered to skip duplicates.
+    pub fn cache_size(mut self, cache_size: usize) -> Self {
+        self.cache_size.replace(cache_size);
+        self
+    }
+
+    /// Finishes building the message processing configuration, to create a `MessageConfig`.
+    pub fn finish(self) -> MessageConfig {
+        let network_name = self.network_name.unwrap_or_else(|| DEFAULT_NETWORK_NAME.to_owned());
+        // The network id is made of the first 8 bytes of the hash of the network name.
+        let network_id = u64::from_le_bytes(Blake2b256::digest(network_name.as_bytes())[0..8].try_into().unwrap());
+
+        MessageConfig {
+            network_name,
+            network_id,
+            minimum_pow_score: self.minimum_pow_score.unwrap_or(DEFAULT_MINIMUM_POW_SCORE),
+            // A cache that can't hold any id would let every duplicate through.
+            cache_size: self.cache_size.unwrap_or(DEFAULT_CACHE_SIZE).max(1),
+        }
+    }
+}
+
+/// The configuration state of the workers processing incoming messages.
+#[derive(Clone, Debug)]
+pub struct MessageConfig {
+    network_name: String,
+    network_id: u64,
+    minimum_pow_score: f64,
+    cache_size: usize,
+}
+
+impl MessageConfig {
+    /// Begins building a new `MessageConfig`.
+    pub fn build() -> MessageConfigBuilder {
+        MessageConfigBuilder::new()
+    }
+
+    /// Returns the name of the network the messages have to belong to.
+    pub fn network_name(&self) -> &str {
+        &self.network_name
+    }
+
+    /// Returns the id of the network the messages have to belong to.
+    pub fn network_id(&self) -> u64 {
+        self.network_id
+    }
+
+    /// Returns the Proof of Work score below which messages are rejected.
+    pub fn minimum_pow_score(&self) -> f64 {
+        self.minimum_pow_score
+    }
+
+    /// Returns the number of recently accepted message ids that are remembered to skip duplicates.
+    pub fn cache_size(&self) -> usize {
+        self.cache_size
+    }
+}
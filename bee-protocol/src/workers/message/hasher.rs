@@ -0,0 +1,285 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    types::{metrics::NodeMetrics, packets::MessagePacket, peer::Peer},
+    workers::{
+        message::{
+            cache::MessageCache,
+            origin,
+            spam::{PeerRateLimiters, RateLimiter},
+            MessageConfig, ProcessorWorker, ProcessorWorkerEvent, SpamConfig,
+        },
+        metrics::NodeMetricsResWorker,
+    },
+};
+
+use bee_crypto::ternary::{
+    sponge::{BatchHasher, CurlPRounds},
+    HASH_LENGTH,
+};
+use bee_message::MessageId;
+use bee_pow::score::{compute_pow_score, pow_input, pow_score};
+use bee_runtime::{node::Node, shutdown_stream::ShutdownStream, worker::Worker};
+use bee_tangle::storage::StorageBackend;
+use bee_ternary::T1B1Buf;
+
+use async_trait::async_trait;
+use crypto::hashes::{blake2b::Blake2b256, Digest};
+use futures::{FutureExt, StreamExt};
+use log::{debug, info, trace};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use std::{any::TypeId, collections::HashSet, convert::Infallible, sync::Arc, time::Instant};
+
+pub(crate) struct HasherWorkerEvent {
+    /// The peer the message was received from, `None` if it originates from the node itself.
+    pub(crate) from: Option<Arc<Peer>>,
+    pub(crate) message_packet: MessagePacket,
+    /// Told whether the message made it into the tangle.
+    pub(crate) notifier: Option<oneshot::Sender<bool>>,
+}
+
+/// A worker that drops the messages of peers exceeding their rate limits, skips the messages that were recently
+/// accepted and scores the Proof of Work of the others, before handing the ones with a sufficient score to the
+/// [`ProcessorWorker`].
+///
+/// Hashing with `CurlP81` is what incoming messages cost the most, hence messages that are queued together get hashed
+/// in batches.
+pub struct HasherWorker {
+    pub(crate) tx: mpsc::UnboundedSender<HasherWorkerEvent>,
+}
+
+#[async_trait]
+impl<N: Node> Worker<N> for HasherWorker
+where
+    N::Backend: StorageBackend,
+{
+    type Config = (MessageConfig, SpamConfig);
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        vec![TypeId::of::<ProcessorWorker>(), TypeId::of::<NodeMetricsResWorker>()].leak()
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let (config, spam_config) = config;
+        let processor = node.worker::<ProcessorWorker>().unwrap();
+        let (processor, cache) = (processor.tx.clone(), processor.cache.clone());
+        let metrics = node.resource::<NodeMetrics>();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let mut receiver = ShutdownStream::new(shutdown, UnboundedReceiverStream::new(rx));
+            let mut hasher = BatchHasher::<T1B1Buf>::new(HASH_LENGTH, CurlPRounds::Rounds81);
+            let mut batch = Vec::with_capacity(hasher.capacity());
+            let mut batch_ids = HashSet::with_capacity(hasher.capacity());
+            let mut global_limiter = RateLimiter::new(spam_config.global(), Instant::now());
+            let mut peer_limiters = PeerRateLimiters::default();
+
+            while let Some(event) = receiver.next().await {
+                batch.push(event);
+
+                // Messages that are already queued join the batch, but there is no waiting for more to arrive.
+                while batch.len() < hasher.capacity() {
+                    match receiver.next().now_or_never() {
+                        Some(Some(event)) => batch.push(event),
+                        _ => break,
+                    }
+                }
+
+                let mut candidates = Vec::with_capacity(batch.len());
+                let now = Instant::now();
+
+                for event in batch.drain(..) {
+                    // Limits are enforced before any hashing, which is what floods would make expensive.
+                    if let Some(from) = &event.from {
+                        if !peer_limiters.try_acquire(from.id(), spam_config.peer(from.relation()), now) {
+                            trace!("Rate limit of {} exceeded, dropping message.", from.alias());
+                            from.metrics().rate_limited_messages_inc();
+                            continue;
+                        }
+                        // Peers within their own limit are not to blame for exceeding the global one.
+                        if !global_limiter.try_acquire(now) {
+                            trace!("Global rate limit exceeded, dropping message from {}.", from.alias());
+                            continue;
+                        }
+                    }
+
+                    let message_id = MessageId::new(Blake2b256::digest(&event.message_packet.bytes).into());
+
+                    if let Some(from) = &event.from {
+                        // Even a duplicate tells that the peer doesn't need to be sent the message.
+                        from.set_known_message(message_id);
+                    }
+
+                    if is_known(&cache, &mut batch_ids, message_id, event.notifier.is_some()) {
+                        metrics.known_messages_inc();
+                        if let Some(from) = &event.from {
+                            from.metrics().known_messages_inc();
+                        }
+                    } else {
+                        candidates.push((message_id, event));
+                    }
+                }
+
+                batch_ids.clear();
+
+                let scores = pow_scores(
+                    &mut hasher,
+                    candidates
+                        .iter()
+                        .map(|(_, event)| event.message_packet.bytes.as_slice()),
+                );
+
+                for ((message_id, event), score) in candidates.into_iter().zip(scores) {
+                    if score < config.minimum_pow_score() {
+                        debug!(
+                            "Insufficient PoW score {} of message {} from {}.",
+                            score,
+                            message_id,
+                            origin(&event.from)
+                        );
+                        metrics.invalid_messages_inc();
+                        if let Some(from) = &event.from {
+                            from.metrics().invalid_messages_inc();
+                        }
+                        if let Some(notifier) = event.notifier {
+                            let _ = notifier.send(false);
+                        }
+                        continue;
+                    }
+
+                    if let (Some(from), Some(filter_pow_score)) = (&event.from, spam_config.filter_pow_score()) {
+                        if score < filter_pow_score {
+                            trace!(
+                                "Filtered message {} from {} with PoW score {}.",
+                                message_id,
+                                from.alias(),
+                                score
+                            );
+                            from.metrics().filtered_messages_inc();
+                            continue;
+                        }
+                    }
+
+                    cache.insert(message_id);
+
+                    if processor
+                        .send(ProcessorWorkerEvent {
+                            from: event.from,
+                            message_id,
+                            bytes: event.message_packet.bytes,
+                            notifier: event.notifier,
+                        })
+                        .is_err()
+                    {
+                        debug!("Processor is gone, dropping message {}.", message_id);
+                    }
+                }
+
+                peer_limiters.prune(now);
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self { tx })
+    }
+}
+
+/// Returns whether a message is skipped because a copy of it was recently accepted, or is already part of the batch.
+///
+/// Messages waiting for a notification are never skipped, so they are told whether they actually made it into the
+/// tangle.
+fn is_known(cache: &MessageCache, batch_ids: &mut HashSet<MessageId>, message_id: MessageId, notified: bool) -> bool {
+    let in_batch = !batch_ids.insert(message_id);
+
+    !notified && (in_batch || cache.contains(&message_id))
+}
+
+/// Returns the Proof of Work scores of messages, hashing them in a single batch unless there is only one.
+fn pow_scores<'a>(hasher: &mut BatchHasher<T1B1Buf>, messages: impl ExactSizeIterator<Item = &'a [u8]>) -> Vec<f64> {
+    if messages.len() <= 1 {
+        return messages.map(compute_pow_score).collect();
+    }
+
+    let mut lengths = Vec::with_capacity(messages.len());
+
+    for bytes in messages {
+        hasher.add(pow_input(bytes));
+        lengths.push(bytes.len());
+    }
+
+    hasher
+        .hash_batched()
+        .zip(lengths)
+        .map(|(hash, length)| pow_score(&hash, length))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batched_scores_match_single_scores() {
+        let mut hasher = BatchHasher::<T1B1Buf>::new(HASH_LENGTH, CurlPRounds::Rounds81);
+        let messages = (0..hasher.capacity())
+            .map(|i| (0..100 + i).map(|j| (i * 7 + j) as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+
+        let scores = pow_scores(&mut hasher, messages.iter().map(Vec::as_slice));
+
+        assert_eq!(scores.len(), messages.len());
+        for (bytes, score) in messages.iter().zip(scores) {
+            assert_eq!(score, compute_pow_score(bytes));
+        }
+        assert!(hasher.is_empty());
+    }
+
+    #[test]
+    fn rejected_copy_does_not_hide_later_copies() {
+        let cache = MessageCache::new(16);
+        let mut batch_ids = HashSet::new();
+        let message_id = MessageId::new([1; 32]);
+
+        // A first copy passes the hasher, but the processor filters it because its peer exceeded a limit.
+        assert!(!is_known(&cache, &mut batch_ids, message_id, false));
+        cache.insert(message_id);
+        cache.remove(&message_id);
+        batch_ids.clear();
+
+        // A valid copy of another peer is still processed, and copies after it are skipped.
+        assert!(!is_known(&cache, &mut batch_ids, message_id, false));
+        cache.insert(message_id);
+        batch_ids.clear();
+
+        assert!(is_known(&cache, &mut batch_ids, message_id, false));
+    }
+
+    #[test]
+    fn duplicates_within_a_batch_are_skipped() {
+        let cache = MessageCache::new(16);
+        let mut batch_ids = HashSet::new();
+        let message_id = MessageId::new([1; 32]);
+
+        assert!(!is_known(&cache, &mut batch_ids, message_id, false));
+        assert!(is_known(&cache, &mut batch_ids, message_id, false));
+    }
+
+    #[test]
+    fn notified_messages_are_never_skipped() {
+        let cache = MessageCache::new(16);
+        let mut batch_ids = HashSet::new();
+        let message_id = MessageId::new([1; 32]);
+
+        cache.insert(message_id);
+
+        assert!(!is_known(&cache, &mut batch_ids, message_id, true));
+        assert!(!is_known(&cache, &mut batch_ids, message_id, true));
+    }
+}
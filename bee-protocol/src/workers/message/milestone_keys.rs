@@ -0,0 +1,242 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types::{milestone_key_manager::MilestoneKeyManager, milestone_key_range::MilestoneKeyRange};
+
+use bee_message::milestone::MilestoneIndex;
+
+use serde::Deserialize;
+
+use std::{fmt, iter};
+
+const DEFAULT_MIN_THRESHOLD: usize = 1;
+const PUBLIC_KEY_LENGTH: usize = 32;
+
+/// Errors occurring while loading the milestone key ranges.
+#[derive(Debug, Eq, PartialEq)]
+pub enum MilestoneKeysError {
+    /// The minimum number of signatures of a milestone is 0.
+    InvalidMinThreshold,
+    /// There are no key ranges.
+    NoKeyRanges,
+    /// A public key is not the hex encoding of 32 bytes.
+    InvalidPublicKey(String),
+    /// A key range ends before it starts.
+    EmptyKeyRange(String),
+    /// Fewer keys than the minimum threshold apply to an index.
+    NotEnoughKeys(MilestoneIndex),
+}
+
+impl fmt::Display for MilestoneKeysError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MilestoneKeysError::InvalidMinThreshold => write!(f, "Invalid minimum threshold 0."),
+            MilestoneKeysError::NoKeyRanges => write!(f, "No milestone key ranges."),
+            MilestoneKeysError::InvalidPublicKey(public_key) => {
+                write!(f, "Invalid milestone public key {}.", public_key)
+            }
+            MilestoneKeysError::EmptyKeyRange(public_key) => {
+                write!(f, "Empty milestone key range of public key {}.", public_key)
+            }
+            MilestoneKeysError::NotEnoughKeys(index) => {
+                write!(f, "Not enough milestone public keys at index {}.", **index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MilestoneKeysError {}
+
+#[derive(Deserialize)]
+struct MilestoneKeyRangeBuilder {
+    public_key: String,
+    start: u32,
+    end: u32,
+}
+
+/// A builder type for the milestone key ranges, that can be finished again to apply new keys at runtime with
+/// [`ProcessorWorker::reload_milestone_keys`](crate::workers::ProcessorWorker::reload_milestone_keys).
+#[derive(Default, Deserialize)]
+pub struct MilestoneKeysConfigBuilder {
+    min_threshold: Option<usize>,
+    key_ranges: Option<Vec<MilestoneKeyRangeBuilder>>,
+}
+
+impl MilestoneKeysConfigBuilder {
+    /// Creates a new `MilestoneKeysConfigBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimum number of valid signatures of a milestone.
+    pub fn min_threshold(mut self, min_threshold: usize) -> Self {
+        self.min_threshold.replace(min_threshold);
+        self
+    }
+
+    /// Adds a hex encoded public key, valid from the `start` index to the `end` index, inclusive. A key whose `end`
+    /// equals its `start` never expires.
+    pub fn with_key_range(mut self, public_key: impl Into<String>, start: u32, end: u32) -> Self {
+        self.key_ranges
+            .get_or_insert_with(Vec::new)
+            .push(MilestoneKeyRangeBuilder {
+                public_key: public_key.into(),
+                start,
+                end,
+            });
+        self
+    }
+
+    /// Finishes building the milestone key ranges, to create a `MilestoneKeyManager`.
+    ///
+    /// Keys must be 32 bytes long, ranges must not end before they start, and from the first start index on, no index
+    /// covered by the ranges may have fewer applicable keys than the minimum threshold.
+    pub fn finish(self) -> Result<MilestoneKeyManager, MilestoneKeysError> {
+        let min_threshold = self.min_threshold.unwrap_or(DEFAULT_MIN_THRESHOLD);
+
+        if min_threshold == 0 {
+            return Err(MilestoneKeysError::InvalidMinThreshold);
+        }
+
+        let builders = match self.key_ranges {
+            Some(builders) if !builders.is_empty() => builders,
+            _ => return Err(MilestoneKeysError::NoKeyRanges),
+        };
+
+        let mut key_ranges = Vec::with_capacity(builders.len());
+
+        for builder in builders {
+            let bytes = match hex::decode(&builder.public_key) {
+                Ok(bytes) if bytes.len() == PUBLIC_KEY_LENGTH => bytes,
+                _ => return Err(MilestoneKeysError::InvalidPublicKey(builder.public_key)),
+            };
+            if builder.end < builder.start {
+                return Err(MilestoneKeysError::EmptyKeyRange(builder.public_key));
+            }
+
+            // Milestones are checked against lower case hex keys, whatever the case they were configured in.
+            key_ranges.push(MilestoneKeyRange::new(
+                hex::encode(bytes),
+                builder.start.into(),
+                builder.end.into(),
+            ));
+        }
+
+        // The applicable keys only change where a range starts or right after one ends, so these are the only indexes
+        // to check. Past the last end there are no keys left, unless one never expires.
+        let last = if key_ranges.iter().any(|range| range.start() == range.end()) {
+            u32::MAX
+        } else {
+            key_ranges.iter().map(|range| *range.end()).max().unwrap_or_default()
+        };
+        let mut boundaries = key_ranges
+            .iter()
+            .flat_map(|range| {
+                let after_end = if range.start() == range.end() {
+                    None
+                } else {
+                    range.end().checked_add(1)
+                };
+
+                iter::once(*range.start()).chain(after_end)
+            })
+            .filter(|index| *index <= last)
+            .collect::<Vec<_>>();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let key_manager = MilestoneKeyManager::new(min_threshold, key_ranges.into_boxed_slice());
+
+        for index in boundaries {
+            if key_manager.get_public_keys(index.into()).len() < min_threshold {
+                return Err(MilestoneKeysError::NotEnoughKeys(index.into()));
+            }
+        }
+
+        Ok(key_manager)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_1: &str = "7205c145525cee64f1c9363696811d239919d830ad964b4e29359e6475848f5a";
+    const KEY_2: &str = "e468e82df33d10dea3bd0eadcd7867946a674d207c39f5af4cc44365d268a7e6";
+    const KEY_3: &str = "0758028d34508079ba1f223907ac3bb5ce8f6bdccc6b961c7c85a2f460b30c1d";
+
+    #[test]
+    fn valid_key_ranges() {
+        let key_manager = MilestoneKeysConfigBuilder::new()
+            .min_threshold(2)
+            .with_key_range(KEY_1, 0, 1000)
+            .with_key_range(KEY_2, 0, 0)
+            .with_key_range(KEY_3, 900, 900)
+            .finish()
+            .unwrap();
+
+        assert_eq!(key_manager.min_threshold(), 2);
+        assert_eq!(key_manager.get_public_keys(950.into()).len(), 3);
+        assert_eq!(key_manager.get_public_keys(2000.into()).len(), 2);
+    }
+
+    #[test]
+    fn upper_case_public_keys() {
+        let key_manager = MilestoneKeysConfigBuilder::new()
+            .with_key_range(KEY_1.to_uppercase(), 0, 0)
+            .finish()
+            .unwrap();
+
+        assert!(key_manager.get_public_keys(0.into()).contains(KEY_1));
+    }
+
+    #[test]
+    fn invalid_public_keys() {
+        for public_key in &["kr1", "7205c145", &KEY_1[..62]] {
+            assert_eq!(
+                MilestoneKeysConfigBuilder::new()
+                    .with_key_range(*public_key, 0, 0)
+                    .finish()
+                    .unwrap_err(),
+                MilestoneKeysError::InvalidPublicKey(public_key.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn empty_key_ranges() {
+        assert_eq!(
+            MilestoneKeysConfigBuilder::new().finish().unwrap_err(),
+            MilestoneKeysError::NoKeyRanges
+        );
+        assert_eq!(
+            MilestoneKeysConfigBuilder::new()
+                .with_key_range(KEY_1, 10, 9)
+                .finish()
+                .unwrap_err(),
+            MilestoneKeysError::EmptyKeyRange(KEY_1.to_string())
+        );
+    }
+
+    #[test]
+    fn not_enough_keys() {
+        // The second key only takes over one index after the first one expired.
+        assert_eq!(
+            MilestoneKeysConfigBuilder::new()
+                .with_key_range(KEY_1, 0, 100)
+                .with_key_range(KEY_2, 102, 102)
+                .finish()
+                .unwrap_err(),
+            MilestoneKeysError::NotEnoughKeys(101.into())
+        );
+        assert_eq!(
+            MilestoneKeysConfigBuilder::new()
+                .min_threshold(2)
+                .with_key_range(KEY_1, 0, 0)
+                .with_key_range(KEY_2, 50, 50)
+                .finish()
+                .unwrap_err(),
+            MilestoneKeysError::NotEnoughKeys(0.into())
+        );
+    }
+}
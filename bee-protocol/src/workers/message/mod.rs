@@ -0,0 +1,29 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that turns the messages received from peers, or submitted by the node itself, into tangle insertions.
+
+mod cache;
+mod config;
+mod hasher;
+mod milestone_keys;
+mod processor;
+mod spam;
+
+pub use config::{MessageConfig, MessageConfigBuilder};
+pub use hasher::HasherWorker;
+pub use milestone_keys::{MilestoneKeysConfigBuilder, MilestoneKeysError};
+pub use processor::ProcessorWorker;
+pub use spam::{RateLimit, SpamConfig, SpamConfigBuilder};
+
+pub(crate) use hasher::HasherWorkerEvent;
+pub(crate) use processor::ProcessorWorkerEvent;
+
+use crate::types::peer::Peer;
+
+use std::sync::Arc;
+
+/// Returns how the origin of a message is referred to in logs.
+fn origin(from: &Option<Arc<Peer>>) -> &str {
+    from.as_ref().map_or("the node", |from| from.alias().as_str())
+}
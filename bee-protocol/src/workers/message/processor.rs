@@ -0,0 +1,255 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    event::{MessageProcessed, MilestoneProcessed},
+    types::{metrics::NodeMetrics, milestone_key_manager::MilestoneKeyManager, peer::Peer},
+    workers::{
+        broadcaster::{BroadcasterWorker, BroadcasterWorkerEvent},
+        message::{cache::MessageCache, origin, spam::PeerRateLimiters, MessageConfig, SpamConfig},
+        metrics::NodeMetricsResWorker,
+    },
+};
+
+use bee_common::packable::Packable;
+use bee_message::{
+    milestone::Milestone,
+    payload::{
+        milestone::{MilestonePayload, MilestoneValidationError},
+        Payload,
+    },
+    Message, MessageId,
+};
+use bee_runtime::{node::Node, shutdown_stream::ShutdownStream, worker::Worker};
+use bee_tangle::{
+    event::LatestMilestoneChanged, metadata::MessageMetadata, storage::StorageBackend, MsTangle, TangleWorker,
+};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use log::{debug, info, trace};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use std::{
+    any::TypeId,
+    convert::Infallible,
+    sync::{Arc, RwLock},
+    time::Instant,
+};
+
+pub(crate) struct ProcessorWorkerEvent {
+    /// The peer the message was received from, `None` if it originates from the node itself.
+    pub(crate) from: Option<Arc<Peer>>,
+    pub(crate) message_id: MessageId,
+    pub(crate) bytes: Vec<u8>,
+    /// Told whether the message made it into the tangle.
+    pub(crate) notifier: Option<oneshot::Sender<bool>>,
+}
+
+/// A worker that deserializes the messages handed by the [`HasherWorker`](crate::workers::HasherWorker), checks that
+/// they belong to the network of the node, inserts them into the tangle and hands the new ones to the
+/// [`BroadcasterWorker`].
+///
+/// Milestones are only accepted if they are signed by enough of the keys applicable to their index, and are then
+/// registered in the tangle. Peers sending more indexation messages than allowed get them filtered out.
+pub struct ProcessorWorker {
+    pub(crate) tx: mpsc::UnboundedSender<ProcessorWorkerEvent>,
+    pub(crate) cache: MessageCache,
+    key_manager: Arc<RwLock<MilestoneKeyManager>>,
+}
+
+impl ProcessorWorker {
+    /// Replaces the key ranges that milestones are validated against, so that coordinator keys can be rolled without
+    /// a restart. Milestones that were already processed are not validated again.
+    pub fn reload_milestone_keys(&self, key_manager: MilestoneKeyManager) {
+        *self.key_manager.write().unwrap() = key_manager;
+
+        info!("Reloaded milestone key ranges.");
+    }
+}
+
+#[async_trait]
+impl<N: Node> Worker<N> for ProcessorWorker
+where
+    N::Backend: StorageBackend,
+{
+    type Config = (MessageConfig, SpamConfig, MilestoneKeyManager);
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        vec![
+            TypeId::of::<TangleWorker>(),
+            TypeId::of::<BroadcasterWorker>(),
+            TypeId::of::<NodeMetricsResWorker>(),
+        ]
+        .leak()
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let (config, spam_config, key_manager) = config;
+        let shared_key_manager = Arc::new(RwLock::new(key_manager));
+        let key_manager = shared_key_manager.clone();
+        let shared_cache = MessageCache::new(config.cache_size());
+        let cache = shared_cache.clone();
+        let tangle = node.resource::<MsTangle<N::Backend>>();
+        let broadcaster = node.worker::<BroadcasterWorker>().unwrap().tx.clone();
+        let metrics = node.resource::<NodeMetrics>();
+        let bus = node.bus();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let mut receiver = ShutdownStream::new(shutdown, UnboundedReceiverStream::new(rx));
+            let mut indexation_limiters = PeerRateLimiters::default();
+
+            while let Some(ProcessorWorkerEvent {
+                from,
+                message_id,
+                bytes,
+                notifier,
+            }) = receiver.next().await
+            {
+                let reject = |from: &Option<Arc<Peer>>, notifier: Option<oneshot::Sender<bool>>| {
+                    // Other copies of the message get another chance.
+                    cache.remove(&message_id);
+                    metrics.invalid_messages_inc();
+                    if let Some(from) = from {
+                        from.metrics().invalid_messages_inc();
+                    }
+                    if let Some(notifier) = notifier {
+                        let _ = notifier.send(false);
+                    }
+                };
+
+                let message = match Message::unpack(&mut &bytes[..]) {
+                    Ok(message) => message,
+                    Err(e) => {
+                        debug!("Invalid message {} from {}: {}.", message_id, origin(&from), e);
+                        reject(&from, notifier);
+                        continue;
+                    }
+                };
+
+                if message.network_id() != config.network_id() {
+                    debug!(
+                        "Message {} from {} belongs to network {} instead of {}.",
+                        message_id,
+                        origin(&from),
+                        message.network_id(),
+                        config.network_id()
+                    );
+                    reject(&from, notifier);
+                    continue;
+                }
+
+                let milestone = match message.payload() {
+                    Some(Payload::Milestone(payload)) => match validate_milestone(&key_manager, payload) {
+                        Ok(()) => Some((
+                            payload.essence().index(),
+                            Milestone::new(message_id, payload.essence().timestamp()),
+                        )),
+                        Err(e) => {
+                            debug!("Invalid milestone {} from {}: {:?}.", message_id, origin(&from), e);
+                            reject(&from, notifier);
+                            continue;
+                        }
+                    },
+                    _ => None,
+                };
+
+                if let (Some(from), Some(Payload::Indexation(_)), Some(limit)) =
+                    (&from, message.payload(), spam_config.indexation())
+                {
+                    let now = Instant::now();
+                    let allowed = indexation_limiters.try_acquire(from.id(), limit, now);
+
+                    indexation_limiters.prune(now);
+
+                    if !allowed {
+                        trace!("Filtered indexation message {} from {}.", message_id, from.alias());
+                        from.metrics().filtered_messages_inc();
+                        // Only this peer exceeded its limit, the copies of other peers are still welcome.
+                        cache.remove(&message_id);
+                        continue;
+                    }
+                }
+
+                // The message may already be in the tangle if its id was evicted from the cache of the hasher.
+                if tangle
+                    .insert(message, message_id, MessageMetadata::arrived())
+                    .await
+                    .is_none()
+                {
+                    metrics.known_messages_inc();
+                    if let Some(from) = &from {
+                        from.metrics().known_messages_inc();
+                    }
+                    if let Some(notifier) = notifier {
+                        let _ = notifier.send(true);
+                    }
+                    continue;
+                }
+
+                trace!("Processed message {} from {}.", message_id, origin(&from));
+                metrics.new_messages_inc();
+                if let Some(from) = &from {
+                    from.metrics().new_messages_inc();
+                }
+                if let Some(notifier) = notifier {
+                    let _ = notifier.send(true);
+                }
+
+                bus.dispatch(MessageProcessed { message_id });
+
+                if let Some((index, milestone)) = milestone {
+                    debug!("New milestone {} {}.", *index, message_id);
+                    tangle.add_milestone(index, milestone.clone()).await;
+
+                    if index > tangle.get_latest_milestone_index() {
+                        tangle.update_latest_milestone_index(index);
+                        bus.dispatch(LatestMilestoneChanged {
+                            index,
+                            milestone: milestone.clone(),
+                        });
+                    }
+
+                    bus.dispatch(MilestoneProcessed { index, milestone });
+                }
+
+                if broadcaster
+                    .send(BroadcasterWorkerEvent {
+                        source: from.map(|from| *from.id()),
+                        message_id,
+                        bytes,
+                    })
+                    .is_err()
+                {
+                    debug!("Broadcaster is gone, not gossiping message {}.", message_id);
+                }
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self {
+            tx,
+            cache: shared_cache,
+            key_manager: shared_key_manager,
+        })
+    }
+}
+
+fn validate_milestone(
+    key_manager: &RwLock<MilestoneKeyManager>,
+    payload: &MilestonePayload,
+) -> Result<(), MilestoneValidationError> {
+    let key_manager = key_manager.read().unwrap();
+    let public_keys = key_manager
+        .get_public_keys(payload.essence().index())
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    payload.validate(&public_keys, key_manager.min_threshold())
+}
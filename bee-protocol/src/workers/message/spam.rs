@@ -0,0 +1,314 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_network::{PeerId, PeerRelation};
+
+use hashbrown::HashMap;
+use serde::{de::Error, Deserialize, Deserializer};
+
+use std::time::Instant;
+
+const DEFAULT_GLOBAL: RateLimit = RateLimit::new(1_000, 500.0);
+const DEFAULT_KNOWN: RateLimit = RateLimit::new(500, 200.0);
+const DEFAULT_UNKNOWN: RateLimit = RateLimit::new(200, 50.0);
+const DEFAULT_DISCOVERED: RateLimit = RateLimit::new(200, 50.0);
+
+/// A number of messages allowed at once, refilled at a sustained rate.
+#[derive(Clone, Copy, Debug)]
+pub struct RateLimit {
+    burst: u32,
+    per_second: f64,
+}
+
+impl RateLimit {
+    /// Creates a new `RateLimit`.
+    pub const fn new(burst: u32, per_second: f64) -> Self {
+        Self { burst, per_second }
+    }
+
+    /// Returns the number of messages allowed at once.
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+
+    /// Returns the number of messages allowed per second in the long run.
+    pub fn per_second(&self) -> f64 {
+        self.per_second
+    }
+
+    /// Checks that the limit lets messages through at all.
+    fn validate(&self) -> Result<(), &'static str> {
+        if self.burst == 0 {
+            return Err("the burst of a rate limit must be at least 1");
+        }
+        if self.per_second.is_nan() || self.per_second <= 0.0 {
+            return Err("the rate of a rate limit must be positive");
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct RawRateLimit {
+    burst: u32,
+    per_second: f64,
+}
+
+impl<'de> Deserialize<'de> for RateLimit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RawRateLimit { burst, per_second } = RawRateLimit::deserialize(deserializer)?;
+        let limit = RateLimit::new(burst, per_second);
+
+        limit.validate().map_err(D::Error::custom)?;
+
+        Ok(limit)
+    }
+}
+
+/// A builder type for a spam configuration.
+#[derive(Default, Deserialize)]
+pub struct SpamConfigBuilder {
+    global: Option<RateLimit>,
+    known: Option<RateLimit>,
+    unknown: Option<RateLimit>,
+    discovered: Option<RateLimit>,
+    indexation: Option<RateLimit>,
+    filter_pow_score: Option<f64>,
+}
+
+impl SpamConfigBuilder {
+    /// Creates a new `SpamConfigBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the rate limit of the messages received from all peers together.
+    pub fn global(mut self, global: RateLimit) -> Self {
+        self.global.replace(global);
+        self
+    }
+
+    /// Sets the rate limit of the messages received from each known peer.
+    pub fn known(mut self, known: RateLimit) -> Self {
+        self.known.replace(known);
+        self
+    }
+
+    /// Sets the rate limit of the messages received from each unknown peer.
+    pub fn unknown(mut self, unknown: RateLimit) -> Self {
+        self.unknown.replace(unknown);
+        self
+    }
+
+    /// Sets the rate limit of the messages received from each discovered peer.
+    pub fn discovered(mut self, discovered: RateLimit) -> Self {
+        self.discovered.replace(discovered);
+        self
+    }
+
+    /// Sets the rate limit of the messages carrying an indexation payload received from each peer.
+    pub fn indexation(mut self, indexation: RateLimit) -> Self {
+        self.indexation.replace(indexation);
+        self
+    }
+
+    /// Sets the PoW score below which the messages received from peers are filtered out, even though they reach the
+    /// minimum PoW score of the network.
+    pub fn filter_pow_score(mut self, filter_pow_score: f64) -> Self {
+        self.filter_pow_score.replace(filter_pow_score);
+        self
+    }
+
+    /// Finishes building the spam configuration, to create a `SpamConfig`.
+    pub fn finish(self) -> SpamConfig {
+        SpamConfig {
+            global: self.global.unwrap_or(DEFAULT_GLOBAL),
+            known: self.known.unwrap_or(DEFAULT_KNOWN),
+            unknown: self.unknown.unwrap_or(DEFAULT_UNKNOWN),
+            discovered: self.discovered.unwrap_or(DEFAULT_DISCOVERED),
+            indexation: self.indexation,
+            filter_pow_score: self.filter_pow_score,
+        }
+    }
+}
+
+/// The configuration state of the protection against message floods from peers.
+#[derive(Clone, Debug)]
+pub struct SpamConfig {
+    global: RateLimit,
+    known: RateLimit,
+    unknown: RateLimit,
+    discovered: RateLimit,
+    indexation: Option<RateLimit>,
+    filter_pow_score: Option<f64>,
+}
+
+impl SpamConfig {
+    /// Begins building a new `SpamConfig`.
+    pub fn build() -> SpamConfigBuilder {
+        SpamConfigBuilder::new()
+    }
+
+    /// Returns the rate limit of the messages received from all peers together.
+    pub fn global(&self) -> RateLimit {
+        self.global
+    }
+
+    /// Returns the rate limit of the messages received from each peer with the given relation.
+    pub fn peer(&self, relation: PeerRelation) -> RateLimit {
+        match relation {
+            PeerRelation::Known => self.known,
+            PeerRelation::Unknown => self.unknown,
+            PeerRelation::Discovered => self.discovered,
+        }
+    }
+
+    /// Returns the rate limit, if any, of the messages carrying an indexation payload received from each peer.
+    pub fn indexation(&self) -> Option<RateLimit> {
+        self.indexation
+    }
+
+    /// Returns the PoW score, if any, below which the messages received from peers are filtered out.
+    pub fn filter_pow_score(&self) -> Option<f64> {
+        self.filter_pow_score
+    }
+}
+
+/// A token bucket enforcing a [`RateLimit`].
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: limit.burst as f64,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * self.limit.per_second).min(self.limit.burst as f64);
+        self.updated = now;
+    }
+
+    /// Switches to another limit, e.g. because the relation to the peer changed, keeping the tokens left within the
+    /// new burst.
+    fn set_limit(&mut self, limit: RateLimit, now: Instant) {
+        self.refill(now);
+
+        self.limit = limit;
+        self.tokens = self.tokens.min(limit.burst as f64);
+    }
+
+    /// Takes a token if there is one left.
+    pub(crate) fn try_acquire(&mut self, now: Instant) -> bool {
+        self.refill(now);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+
+        self.tokens >= self.limit.burst as f64
+    }
+}
+
+/// The token buckets of the peers that recently sent messages.
+#[derive(Default)]
+pub(crate) struct PeerRateLimiters(HashMap<PeerId, RateLimiter>);
+
+impl PeerRateLimiters {
+    /// Takes a token from the bucket of a peer, creating it with the given limit if needed. The limit of an existing
+    /// bucket is updated, as it depends on the relation to the peer which may have changed.
+    pub(crate) fn try_acquire(&mut self, peer_id: &PeerId, limit: RateLimit, now: Instant) -> bool {
+        let limiter = self.0.entry(*peer_id).or_insert_with(|| RateLimiter::new(limit, now));
+
+        limiter.set_limit(limit, now);
+        limiter.try_acquire(now)
+    }
+
+    /// Forgets the buckets that refilled, since they behave like new ones. This keeps the buckets of disconnected peers
+    /// from piling up.
+    pub(crate) fn prune(&mut self, now: Instant) {
+        self.0.retain(|_, limiter| !limiter.is_full(now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[test]
+    fn burst_then_sustained_rate() {
+        let now = Instant::now();
+        let mut limiter = RateLimiter::new(RateLimit::new(3, 2.0), now);
+
+        assert!((0..3).all(|_| limiter.try_acquire(now)));
+        assert!(!limiter.try_acquire(now));
+        assert!(limiter.try_acquire(now + Duration::from_secs(1)));
+        assert!(limiter.try_acquire(now + Duration::from_secs(1)));
+        assert!(!limiter.try_acquire(now + Duration::from_secs(1)));
+        // Refilling never exceeds the burst.
+        assert!((0..3).all(|_| limiter.try_acquire(now + Duration::from_secs(60))));
+        assert!(!limiter.try_acquire(now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn refilled_peer_buckets_are_pruned() {
+        let now = Instant::now();
+        let limit = RateLimit::new(2, 1.0);
+        let mut limiters = PeerRateLimiters::default();
+        let busy = PeerId::random();
+        let idle = PeerId::random();
+
+        assert!(limiters.try_acquire(&busy, limit, now));
+        assert!(limiters.try_acquire(&idle, limit, now));
+        assert!(limiters.try_acquire(&busy, limit, now + Duration::from_secs(1)));
+        assert!(limiters.try_acquire(&busy, limit, now + Duration::from_secs(1)));
+
+        limiters.prune(now + Duration::from_secs(1));
+
+        assert_eq!(limiters.0.len(), 1);
+        assert!(!limiters.try_acquire(&busy, limit, now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn peer_buckets_follow_the_limit() {
+        let now = Instant::now();
+        let mut limiters = PeerRateLimiters::default();
+        let peer = PeerId::random();
+
+        assert!((0..5).all(|_| limiters.try_acquire(&peer, RateLimit::new(10, 1.0), now)));
+        // Tokens left beyond the new burst are dropped.
+        assert!((0..2).all(|_| limiters.try_acquire(&peer, RateLimit::new(2, 1.0), now)));
+        assert!(!limiters.try_acquire(&peer, RateLimit::new(2, 1.0), now));
+        // The bucket refills at the new rate.
+        assert!(!limiters.try_acquire(&peer, RateLimit::new(2, 4.0), now));
+        assert!((0..2).all(|_| limiters.try_acquire(&peer, RateLimit::new(2, 4.0), now + Duration::from_millis(500))));
+        assert!(!limiters.try_acquire(&peer, RateLimit::new(2, 4.0), now + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn invalid_limits_are_rejected() {
+        assert!(RateLimit::new(1, 0.1).validate().is_ok());
+        assert!(RateLimit::new(0, 10.0).validate().is_err());
+        assert!(RateLimit::new(10, 0.0).validate().is_err());
+        assert!(RateLimit::new(10, -1.0).validate().is_err());
+        assert!(RateLimit::new(10, f64::NAN).validate().is_err());
+    }
+}
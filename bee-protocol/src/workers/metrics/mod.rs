@@ -0,0 +1,8 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that provides the resource worker of the node metrics.
+
+mod node_metrics_res;
+
+pub use node_metrics_res::NodeMetricsResWorker;
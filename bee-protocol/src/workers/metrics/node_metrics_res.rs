@@ -0,0 +1,31 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types::metrics::NodeMetrics;
+
+use bee_runtime::{node::Node, worker::Worker};
+
+use async_trait::async_trait;
+
+use std::convert::Infallible;
+
+/// A worker that registers the [`NodeMetrics`] as a node resource.
+pub struct NodeMetricsResWorker {}
+
+#[async_trait]
+impl<N: Node> Worker<N> for NodeMetricsResWorker {
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        node.register_resource(NodeMetrics::new());
+
+        Ok(Self {})
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        node.remove_resource::<NodeMetrics>();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that provides the workers of the IOTA protocol.
+
+pub mod broadcaster;
+pub mod heartbeat;
+pub mod message;
+pub mod metrics;
+pub mod peer;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+pub mod reputation;
+pub mod responder;
+pub mod submitter;
+pub mod warp_sync;
+
+pub use broadcaster::BroadcasterWorker;
+pub use heartbeat::HeartbeatWorker;
+pub use message::{
+    HasherWorker, MessageConfig, MessageConfigBuilder, MilestoneKeysConfigBuilder, MilestoneKeysError, ProcessorWorker,
+    RateLimit, SpamConfig, SpamConfigBuilder,
+};
+pub use metrics::NodeMetricsResWorker;
+pub use peer::{PeerManager, PeerManagerResWorker, PeerManagerWorker};
+#[cfg(feature = "prometheus")]
+pub use prometheus::{PrometheusConfig, PrometheusConfigBuilder, PrometheusWorker};
+pub use reputation::{ReputationConfig, ReputationConfigBuilder, ReputationWorker};
+pub use responder::ResponderWorker;
+pub use submitter::{MessageSubmitter, SubmitterConfig, SubmitterConfigBuilder, SubmitterError, SubmitterWorker};
+pub use warp_sync::{WarpSyncConfig, WarpSyncConfigBuilder, WarpSyncWorker};
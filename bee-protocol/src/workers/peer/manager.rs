@@ -0,0 +1,109 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types::peer::Peer;
+
+use bee_message::milestone::MilestoneIndex;
+use bee_network::{GossipSender, PeerId};
+
+use hashbrown::HashMap;
+use tokio::sync::RwLock;
+
+use std::sync::Arc;
+
+/// Keeps track of the peers known to the protocol, and of the gossip senders of the connected ones.
+#[derive(Default)]
+pub struct PeerManager {
+    peers: RwLock<HashMap<PeerId, (Arc<Peer>, Option<GossipSender>)>>,
+}
+
+impl PeerManager {
+    /// Creates a new `PeerManager`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a peer to the `PeerManager`, replacing a previous peer with the same id.
+    pub async fn add(&self, peer: Arc<Peer>) {
+        self.peers.write().await.insert(*peer.id(), (peer, None));
+    }
+
+    /// Removes a peer from the `PeerManager`.
+    pub async fn remove(&self, id: &PeerId) -> Option<Arc<Peer>> {
+        self.peers.write().await.remove(id).map(|(peer, _)| peer)
+    }
+
+    /// Returns a peer of the `PeerManager`.
+    pub async fn get(&self, id: &PeerId) -> Option<Arc<Peer>> {
+        self.peers.read().await.get(id).map(|(peer, _)| peer.clone())
+    }
+
+    /// Returns all peers of the `PeerManager`.
+    pub async fn get_all(&self) -> Vec<Arc<Peer>> {
+        self.peers.read().await.values().map(|(peer, _)| peer.clone()).collect()
+    }
+
+    /// Marks a peer as connected and stores the sender of its gossip messages.
+    pub async fn connect(&self, id: &PeerId, gossip_sender: GossipSender) {
+        if let Some((peer, sender)) = self.peers.write().await.get_mut(id) {
+            peer.set_connected(true);
+            sender.replace(gossip_sender);
+        }
+    }
+
+    /// Marks a peer as disconnected and drops the sender of its gossip messages.
+    pub async fn disconnect(&self, id: &PeerId) {
+        if let Some((peer, sender)) = self.peers.write().await.get_mut(id) {
+            peer.set_connected(false);
+            sender.take();
+        }
+    }
+
+    /// Returns the gossip sender of a connected peer.
+    pub async fn sender(&self, id: &PeerId) -> Option<GossipSender> {
+        self.peers.read().await.get(id).and_then(|(_, sender)| sender.clone())
+    }
+
+    /// Returns the connected peers together with their gossip senders.
+    pub async fn connected(&self) -> Vec<(Arc<Peer>, GossipSender)> {
+        self.peers
+            .read()
+            .await
+            .values()
+            .filter_map(|(peer, sender)| sender.as_ref().map(|sender| (peer.clone(), sender.clone())))
+            .collect()
+    }
+
+    /// Calls `f` with every connected peer and its gossip sender, while holding the lock of the `PeerManager`, which is
+    /// cheaper than collecting them with `connected` when done for every message.
+    pub async fn for_each_connected(&self, mut f: impl FnMut(&Arc<Peer>, &GossipSender)) {
+        for (peer, sender) in self.peers.read().await.values() {
+            if let Some(sender) = sender {
+                f(peer, sender);
+            }
+        }
+    }
+
+    /// Returns the number of connected peers, and how many of them are synced with the given latest milestone index.
+    pub async fn connected_and_synced(&self, latest_milestone_index: MilestoneIndex) -> (usize, usize) {
+        let peers = self.peers.read().await;
+        let connected = peers.values().filter(|(_, sender)| sender.is_some());
+
+        connected.fold((0, 0), |(connected, synced), (peer, _)| {
+            (
+                connected + 1,
+                synced + peer.is_synced_with(latest_milestone_index) as usize,
+            )
+        })
+    }
+
+    /// Returns the number of peers of the `PeerManager`.
+    pub async fn len(&self) -> usize {
+        self.peers.read().await.len()
+    }
+
+    /// Returns whether the `PeerManager` is empty.
+    pub async fn is_empty(&self) -> bool {
+        self.peers.read().await.is_empty()
+    }
+}
@@ -0,0 +1,15 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that provides the peer manager, its resource worker, and the worker processing the packets of the
+//! connected peers.
+
+mod manager;
+mod manager_res;
+mod packet_handler;
+mod peer_worker;
+mod worker;
+
+pub use manager::PeerManager;
+pub use manager_res::PeerManagerResWorker;
+pub use worker::PeerManagerWorker;
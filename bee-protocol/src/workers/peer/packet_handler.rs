@@ -0,0 +1,97 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types::packets::{HeaderPacket, HEADER_SIZE};
+
+use futures::{Stream, StreamExt};
+
+use std::convert::TryInto;
+
+/// Reassembles packets from the chunks of bytes received from a peer, which don't necessarily align with packet
+/// boundaries.
+pub(crate) struct PacketHandler<S> {
+    chunks: S,
+    buffer: Vec<u8>,
+    offset: usize,
+}
+
+impl<S: Stream<Item = Vec<u8>> + Unpin> PacketHandler<S> {
+    pub(crate) fn new(chunks: S) -> Self {
+        Self {
+            chunks,
+            buffer: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    /// Returns the next packet, or `None` once the stream of chunks ended.
+    pub(crate) async fn fetch_packet(&mut self) -> Option<(HeaderPacket, &[u8])> {
+        let header = loop {
+            if let Some(header) = self.header() {
+                if self.available() >= HEADER_SIZE + header.packet_length as usize {
+                    break header;
+                }
+            }
+
+            self.receive().await?;
+        };
+
+        let start = self.offset + HEADER_SIZE;
+        let end = start + header.packet_length as usize;
+        self.offset = end;
+
+        Some((header, &self.buffer[start..end]))
+    }
+
+    fn header(&self) -> Option<HeaderPacket> {
+        self.buffer
+            .get(self.offset..self.offset + HEADER_SIZE)
+            .map(|bytes| HeaderPacket::from_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn available(&self) -> usize {
+        self.buffer.len() - self.offset
+    }
+
+    async fn receive(&mut self) -> Option<()> {
+        let chunk = self.chunks.next().await?;
+
+        // Drop the bytes of the packets that were already handed out before appending new ones.
+        self.buffer.drain(..self.offset);
+        self.offset = 0;
+        self.buffer.extend_from_slice(&chunk);
+
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::packets::{tlv_to_bytes, HeartbeatPacket, Packet};
+
+    use futures::{executor::block_on, stream};
+
+    #[test]
+    fn reassemble_split_and_merged_packets() {
+        let heartbeat = HeartbeatPacket {
+            latest_milestone_index: 42,
+            ..Default::default()
+        };
+        let mut bytes = tlv_to_bytes(&heartbeat);
+        bytes.extend(tlv_to_bytes(&heartbeat));
+
+        // The first packet is split across two chunks, the second one shares a chunk with the end of the first one.
+        let chunks = vec![bytes[..5].to_vec(), bytes[5..20].to_vec(), bytes[20..].to_vec()];
+        let mut handler = PacketHandler::new(stream::iter(chunks));
+
+        for _ in 0..2 {
+            let (header, payload) = block_on(handler.fetch_packet()).unwrap();
+
+            assert_eq!(header.packet_type, HeartbeatPacket::ID);
+            assert_eq!(HeartbeatPacket::from_bytes(payload), heartbeat);
+        }
+
+        assert!(block_on(handler.fetch_packet()).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    types::{
+        metrics::NodeMetrics,
+        packets::{
+            tlv_from_bytes, HeartbeatPacket, MessagePacket, MessageRequestPacket, MilestoneRequestPacket, Packet,
+        },
+        peer::Peer,
+    },
+    workers::{message::HasherWorkerEvent, peer::packet_handler::PacketHandler, responder::ResponderWorkerEvent},
+};
+
+use bee_network::GossipReceiver;
+use bee_runtime::resource::ResourceHandle;
+
+use log::{debug, trace};
+use tokio::sync::mpsc;
+
+use std::sync::Arc;
+
+/// Processes the packets received from a connected peer until it disconnects.
+pub(crate) struct PeerWorker {
+    peer: Arc<Peer>,
+    metrics: ResourceHandle<NodeMetrics>,
+    hasher: mpsc::UnboundedSender<HasherWorkerEvent>,
+    responder: mpsc::UnboundedSender<ResponderWorkerEvent>,
+}
+
+impl PeerWorker {
+    pub(crate) fn new(
+        peer: Arc<Peer>,
+        metrics: ResourceHandle<NodeMetrics>,
+        hasher: mpsc::UnboundedSender<HasherWorkerEvent>,
+        responder: mpsc::UnboundedSender<ResponderWorkerEvent>,
+    ) -> Self {
+        Self {
+            peer,
+            metrics,
+            hasher,
+            responder,
+        }
+    }
+
+    pub(crate) async fn run(self, gossip_in: GossipReceiver) {
+        debug!("Processing packets of {}.", self.peer.alias());
+
+        let mut packet_handler = PacketHandler::new(gossip_in);
+
+        while let Some((header, bytes)) = packet_handler.fetch_packet().await {
+            match header.packet_type {
+                MilestoneRequestPacket::ID => match tlv_from_bytes::<MilestoneRequestPacket>(&header, bytes) {
+                    Ok(request) => self.respond(ResponderWorkerEvent::Milestone {
+                        from: self.peer.clone(),
+                        request,
+                    }),
+                    Err(e) => {
+                        debug!("Invalid milestone request from {}: {}", self.peer.alias(), e);
+                        self.metrics.invalid_packets_inc();
+                        self.peer.metrics().invalid_packets_inc();
+                    }
+                },
+                MessagePacket::ID => match tlv_from_bytes::<MessagePacket>(&header, bytes) {
+                    Ok(message) => self.process_message(message),
+                    Err(e) => {
+                        debug!("Invalid message from {}: {}", self.peer.alias(), e);
+                        self.metrics.invalid_messages_inc();
+                        self.peer.metrics().invalid_messages_inc();
+                    }
+                },
+                MessageRequestPacket::ID => match tlv_from_bytes::<MessageRequestPacket>(&header, bytes) {
+                    Ok(request) => self.respond(ResponderWorkerEvent::Message {
+                        from: self.peer.clone(),
+                        request,
+                    }),
+                    Err(e) => {
+                        debug!("Invalid message request from {}: {}", self.peer.alias(), e);
+                        self.metrics.invalid_packets_inc();
+                        self.peer.metrics().invalid_packets_inc();
+                    }
+                },
+                HeartbeatPacket::ID => match tlv_from_bytes::<HeartbeatPacket>(&header, bytes) {
+                    Ok(heartbeat) => self.process_heartbeat(heartbeat),
+                    Err(e) => {
+                        debug!("Invalid heartbeat from {}: {}", self.peer.alias(), e);
+                        self.metrics.invalid_packets_inc();
+                        self.peer.metrics().invalid_packets_inc();
+                    }
+                },
+                packet_type => {
+                    trace!("Unsupported packet type {} from {}.", packet_type, self.peer.alias());
+                    self.metrics.invalid_packets_inc();
+                    self.peer.metrics().invalid_packets_inc();
+                }
+            }
+        }
+
+        debug!("Stopped processing packets of {}.", self.peer.alias());
+    }
+
+    fn process_message(&self, message_packet: MessagePacket) {
+        self.metrics.messages_received_inc();
+        self.peer.metrics().messages_received_inc();
+
+        if self
+            .hasher
+            .send(HasherWorkerEvent {
+                from: Some(self.peer.clone()),
+                message_packet,
+                notifier: None,
+            })
+            .is_err()
+        {
+            debug!("Hasher is gone, dropping message from {}.", self.peer.alias());
+        }
+    }
+
+    fn respond(&self, event: ResponderWorkerEvent) {
+        if self.responder.send(event).is_err() {
+            debug!("Responder is gone, dropping request from {}.", self.peer.alias());
+        }
+    }
+
+    fn process_heartbeat(&self, heartbeat: HeartbeatPacket) {
+        trace!("Received heartbeat from {}.", self.peer.alias());
+
+        self.metrics.heartbeats_received_inc();
+        self.peer.metrics().heartbeats_received_inc();
+        self.peer.set_heartbeat_received_timestamp();
+
+        self.peer
+            .set_solid_milestone_index(heartbeat.solid_milestone_index.into());
+        self.peer.set_pruned_index(heartbeat.pruned_index.into());
+        self.peer
+            .set_latest_milestone_index(heartbeat.latest_milestone_index.into());
+        self.peer.set_connected_peers(heartbeat.connected_peers);
+        self.peer.set_synced_peers(heartbeat.synced_peers);
+    }
+}
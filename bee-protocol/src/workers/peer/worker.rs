@@ -0,0 +1,110 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    event::{PeerConnected, PeerDisconnected},
+    types::{metrics::NodeMetrics, peer::Peer},
+    workers::{
+        message::HasherWorker,
+        metrics::NodeMetricsResWorker,
+        peer::{peer_worker::PeerWorker, PeerManager, PeerManagerResWorker},
+        responder::ResponderWorker,
+    },
+};
+
+use bee_network::{Event, NetworkEventReceiver};
+use bee_runtime::{node::Node, shutdown_stream::ShutdownStream, worker::Worker};
+use bee_tangle::storage::StorageBackend;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use log::info;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use std::{any::TypeId, convert::Infallible, sync::Arc};
+
+/// A worker that keeps the [`PeerManager`] in sync with the events of the network layer, and processes the packets
+/// received from every connected peer.
+#[derive(Default)]
+pub struct PeerManagerWorker {}
+
+#[async_trait]
+impl<N: Node> Worker<N> for PeerManagerWorker
+where
+    N::Backend: StorageBackend,
+{
+    type Config = NetworkEventReceiver;
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        vec![
+            TypeId::of::<PeerManagerResWorker>(),
+            TypeId::of::<NodeMetricsResWorker>(),
+            TypeId::of::<HasherWorker>(),
+            TypeId::of::<ResponderWorker>(),
+        ]
+        .leak()
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let peer_manager = node.resource::<PeerManager>();
+        let metrics = node.resource::<NodeMetrics>();
+        let hasher = node.worker::<HasherWorker>().unwrap().tx.clone();
+        let responder = node.worker::<ResponderWorker>().unwrap().tx.clone();
+        let bus = node.bus();
+        let events: mpsc::UnboundedReceiver<Event> = config.into();
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let mut events = ShutdownStream::new(shutdown, UnboundedReceiverStream::new(events));
+
+            while let Some(event) = events.next().await {
+                match event {
+                    Event::PeerAdded { peer_id, info } => {
+                        // Keep the connection state of peers that were re-added.
+                        if peer_manager.get(&peer_id).await.is_none() {
+                            peer_manager.add(Arc::new(Peer::new(peer_id, info))).await;
+                        }
+                    }
+                    Event::PeerRemoved { peer_id } => {
+                        peer_manager.remove(&peer_id).await;
+                    }
+                    Event::PeerConnected {
+                        peer_id,
+                        info,
+                        gossip_in,
+                        gossip_out,
+                    } => {
+                        let peer = match peer_manager.get(&peer_id).await {
+                            Some(peer) => peer,
+                            None => {
+                                let peer = Arc::new(Peer::new(peer_id, info));
+                                peer_manager.add(peer.clone()).await;
+                                peer
+                            }
+                        };
+
+                        peer_manager.connect(&peer_id, gossip_out).await;
+                        tokio::spawn(
+                            PeerWorker::new(peer, metrics.clone(), hasher.clone(), responder.clone()).run(gossip_in),
+                        );
+
+                        bus.dispatch(PeerConnected { peer_id });
+                    }
+                    Event::PeerDisconnected { peer_id } => {
+                        peer_manager.disconnect(&peer_id).await;
+
+                        bus.dispatch(PeerDisconnected { peer_id });
+                    }
+                    _ => {}
+                }
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self::default())
+    }
+}
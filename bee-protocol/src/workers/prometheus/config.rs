@@ -0,0 +1,55 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+
+use std::net::SocketAddr;
+
+const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1:9311";
+
+/// A builder type for a Prometheus exporter configuration.
+#[derive(Default, Deserialize)]
+pub struct PrometheusConfigBuilder {
+    bind_address: Option<SocketAddr>,
+}
+
+impl PrometheusConfigBuilder {
+    /// Creates a new `PrometheusConfigBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the address the metrics are served on.
+    pub fn bind_address(mut self, bind_address: SocketAddr) -> Self {
+        self.bind_address.replace(bind_address);
+        self
+    }
+
+    /// Finishes building the Prometheus exporter configuration, to create a `PrometheusConfig`.
+    pub fn finish(self) -> PrometheusConfig {
+        PrometheusConfig {
+            // Panicking is fine, the default address is a valid constant.
+            bind_address: self
+                .bind_address
+                .unwrap_or_else(|| DEFAULT_BIND_ADDRESS.parse().unwrap()),
+        }
+    }
+}
+
+/// The configuration state of the Prometheus exporter.
+#[derive(Clone, Debug)]
+pub struct PrometheusConfig {
+    bind_address: SocketAddr,
+}
+
+impl PrometheusConfig {
+    /// Begins building a new `PrometheusConfig`.
+    pub fn build() -> PrometheusConfigBuilder {
+        PrometheusConfigBuilder::new()
+    }
+
+    /// Returns the address the metrics are served on.
+    pub fn bind_address(&self) -> SocketAddr {
+        self.bind_address
+    }
+}
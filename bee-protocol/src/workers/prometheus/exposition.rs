@@ -0,0 +1,100 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{Display, Write};
+
+/// The type of a metric family.
+#[derive(Clone, Copy)]
+pub(crate) enum MetricType {
+    Counter,
+    Gauge,
+}
+
+impl MetricType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+        }
+    }
+}
+
+/// Renders metrics in the Prometheus text exposition format.
+#[derive(Default)]
+pub(crate) struct Exposition(String);
+
+impl Exposition {
+    /// Starts a metric family, whose samples have to follow.
+    pub(crate) fn family(&mut self, name: &str, metric_type: MetricType, help: &str) -> &mut Self {
+        // Writing to a `String` never fails.
+        let _ = writeln!(self.0, "# HELP {} {}", name, help);
+        let _ = writeln!(self.0, "# TYPE {} {}", name, metric_type.as_str());
+        self
+    }
+
+    /// Adds a sample to the current metric family.
+    pub(crate) fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) -> &mut Self {
+        self.0.push_str(name);
+
+        if !labels.is_empty() {
+            self.0.push('{');
+            for (i, (label, label_value)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.0.push(',');
+                }
+                let _ = write!(self.0, "{}=\"{}\"", label, escape(label_value));
+            }
+            self.0.push('}');
+        }
+
+        let _ = writeln!(self.0, " {}", value);
+        self
+    }
+
+    /// Returns the rendered metrics.
+    pub(crate) fn finish(self) -> String {
+        self.0
+    }
+}
+
+fn escape(label_value: &str) -> String {
+    label_value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_format() {
+        let mut exposition = Exposition::default();
+
+        exposition
+            .family("bee_messages_total", MetricType::Counter, "Messages.")
+            .sample("bee_messages_total", &[], 42)
+            .family("bee_peer_messages_total", MetricType::Counter, "Messages per peer.")
+            .sample(
+                "bee_peer_messages_total",
+                &[("peer_id", "12D3"), ("alias", "a \"b\"\\c\n")],
+                7,
+            )
+            .family("bee_index", MetricType::Gauge, "Index.")
+            .sample("bee_index", &[], 3);
+
+        assert_eq!(
+            exposition.finish(),
+            "# HELP bee_messages_total Messages.\n\
+             # TYPE bee_messages_total counter\n\
+             bee_messages_total 42\n\
+             # HELP bee_peer_messages_total Messages per peer.\n\
+             # TYPE bee_peer_messages_total counter\n\
+             bee_peer_messages_total{peer_id=\"12D3\",alias=\"a \\\"b\\\"\\\\c\\n\"} 7\n\
+             # HELP bee_index Index.\n\
+             # TYPE bee_index gauge\n\
+             bee_index 3\n"
+        );
+    }
+}
@@ -0,0 +1,11 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that exposes the node, peer, tangle and storage metrics to Prometheus over HTTP.
+
+mod config;
+mod exposition;
+mod worker;
+
+pub use config::{PrometheusConfig, PrometheusConfigBuilder};
+pub use worker::PrometheusWorker;
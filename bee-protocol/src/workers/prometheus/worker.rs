@@ -0,0 +1,421 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    types::metrics::{NodeMetrics, PeerMetrics},
+    workers::{
+        metrics::NodeMetricsResWorker,
+        peer::{PeerManager, PeerManagerResWorker},
+        prometheus::{
+            exposition::{Exposition, MetricType},
+            PrometheusConfig,
+        },
+    },
+};
+
+use bee_runtime::{node::Node, resource::ResourceHandle, shutdown_stream::ShutdownStream, worker::Worker};
+use bee_tangle::{storage::StorageBackend, MsTangle, TangleWorker};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use log::{debug, info};
+use tokio::{
+    io::{self, AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task,
+    time::timeout,
+};
+use tokio_stream::wrappers::TcpListenerStream;
+
+use std::{any::TypeId, time::Duration};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST_HEAD_SIZE: usize = 8 * 1024;
+
+type Counter<M> = (&'static str, &'static str, fn(&M) -> u64);
+
+const NODE_COUNTERS: &[Counter<NodeMetrics>] = &[
+    (
+        "invalid_packets",
+        "Invalid packets received.",
+        NodeMetrics::invalid_packets,
+    ),
+    (
+        "milestone_requests_received",
+        "Milestone requests received.",
+        NodeMetrics::milestone_requests_received,
+    ),
+    (
+        "messages_received",
+        "Messages received.",
+        NodeMetrics::messages_received,
+    ),
+    (
+        "message_requests_received",
+        "Message requests received.",
+        NodeMetrics::message_requests_received,
+    ),
+    (
+        "heartbeats_received",
+        "Heartbeats received.",
+        NodeMetrics::heartbeats_received,
+    ),
+    (
+        "milestone_requests_sent",
+        "Milestone requests sent.",
+        NodeMetrics::milestone_requests_sent,
+    ),
+    ("messages_sent", "Messages sent.", NodeMetrics::messages_sent),
+    (
+        "message_requests_sent",
+        "Message requests sent.",
+        NodeMetrics::message_requests_sent,
+    ),
+    ("heartbeats_sent", "Heartbeats sent.", NodeMetrics::heartbeats_sent),
+    (
+        "invalid_messages",
+        "Invalid messages received.",
+        NodeMetrics::invalid_messages,
+    ),
+    ("new_messages", "New messages received.", NodeMetrics::new_messages),
+    (
+        "known_messages",
+        "Already known messages received.",
+        NodeMetrics::known_messages,
+    ),
+    (
+        "referenced_messages",
+        "Messages referenced by milestones.",
+        NodeMetrics::referenced_messages,
+    ),
+    (
+        "excluded_no_transaction_messages",
+        "Referenced messages excluded for not carrying a transaction.",
+        NodeMetrics::excluded_no_transaction_messages,
+    ),
+    (
+        "excluded_conflicting_messages",
+        "Referenced messages excluded for carrying a conflicting transaction.",
+        NodeMetrics::excluded_conflicting_messages,
+    ),
+    (
+        "included_messages",
+        "Referenced messages included in the ledger.",
+        NodeMetrics::included_messages,
+    ),
+    ("created_outputs", "Outputs created.", NodeMetrics::created_outputs),
+    ("consumed_outputs", "Outputs consumed.", NodeMetrics::consumed_outputs),
+    ("receipts", "Receipts.", NodeMetrics::receipts),
+    (
+        "transaction_payloads",
+        "Transaction payloads.",
+        NodeMetrics::transaction_payloads,
+    ),
+    (
+        "milestone_payloads",
+        "Milestone payloads.",
+        NodeMetrics::milestone_payloads,
+    ),
+    (
+        "indexation_payloads",
+        "Indexation payloads.",
+        NodeMetrics::indexation_payloads,
+    ),
+    ("snapshots", "Snapshots taken.", NodeMetrics::snapshots),
+    ("prunings", "Prunings done.", NodeMetrics::prunings),
+];
+
+const PEER_COUNTERS: &[Counter<PeerMetrics>] = &[
+    (
+        "invalid_messages",
+        "Invalid messages received from the peer.",
+        PeerMetrics::invalid_messages,
+    ),
+    (
+        "new_messages",
+        "New messages received from the peer.",
+        PeerMetrics::new_messages,
+    ),
+    (
+        "known_messages",
+        "Already known messages received from the peer.",
+        PeerMetrics::known_messages,
+    ),
+    (
+        "rate_limited_messages",
+        "Messages from the peer dropped by its rate limit.",
+        PeerMetrics::rate_limited_messages,
+    ),
+    (
+        "filtered_messages",
+        "Messages from the peer dropped by the spam filters.",
+        PeerMetrics::filtered_messages,
+    ),
+    (
+        "invalid_packets",
+        "Invalid packets received from the peer.",
+        PeerMetrics::invalid_packets,
+    ),
+    (
+        "milestone_requests_received",
+        "Milestone requests received from the peer.",
+        PeerMetrics::milestone_requests_received,
+    ),
+    (
+        "messages_received",
+        "Messages received from the peer.",
+        PeerMetrics::messages_received,
+    ),
+    (
+        "message_requests_received",
+        "Message requests received from the peer.",
+        PeerMetrics::message_requests_received,
+    ),
+    (
+        "heartbeats_received",
+        "Heartbeats received from the peer.",
+        PeerMetrics::heartbeats_received,
+    ),
+    (
+        "milestone_requests_sent",
+        "Milestone requests sent to the peer.",
+        PeerMetrics::milestone_requests_sent,
+    ),
+    (
+        "messages_sent",
+        "Messages sent to the peer.",
+        PeerMetrics::messages_sent,
+    ),
+    (
+        "message_requests_sent",
+        "Message requests sent to the peer.",
+        PeerMetrics::message_requests_sent,
+    ),
+    (
+        "heartbeats_sent",
+        "Heartbeats sent to the peer.",
+        PeerMetrics::heartbeats_sent,
+    ),
+];
+
+/// A worker that serves the node, peer, tangle and storage metrics in the Prometheus text format over HTTP.
+pub struct PrometheusWorker {}
+
+#[async_trait]
+impl<N: Node> Worker<N> for PrometheusWorker
+where
+    N::Backend: StorageBackend,
+{
+    type Config = PrometheusConfig;
+    type Error = io::Error;
+
+    fn dependencies() -> &'static [TypeId] {
+        vec![
+            TypeId::of::<TangleWorker>(),
+            TypeId::of::<PeerManagerResWorker>(),
+            TypeId::of::<NodeMetricsResWorker>(),
+        ]
+        .leak()
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let tangle = node.resource::<MsTangle<N::Backend>>();
+        let storage = node.storage();
+        let peer_manager = node.resource::<PeerManager>();
+        let node_metrics = node.resource::<NodeMetrics>();
+        let listener = TcpListener::bind(config.bind_address()).await?;
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running on {}.", config.bind_address());
+
+            let mut incoming = ShutdownStream::new(shutdown, TcpListenerStream::new(listener));
+
+            while let Some(stream) = incoming.next().await {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        debug!("Accepting a connection failed: {}.", e);
+                        continue;
+                    }
+                };
+
+                // Scrapes are handled one at a time, a slow client is only waited for up to the timeout.
+                let served = timeout(REQUEST_TIMEOUT, async {
+                    let head = read_request_head(&mut stream).await?;
+
+                    match parse_request_line(&head) {
+                        Some(("GET", "/metrics")) => {
+                            let body = render(&tangle, &storage, &peer_manager, &node_metrics).await;
+                            respond(&mut stream, "200 OK", &body).await
+                        }
+                        _ => respond(&mut stream, "404 Not Found", "").await,
+                    }
+                })
+                .await;
+
+                match served {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => debug!("Serving metrics failed: {}.", e),
+                    Err(_) => debug!("Serving metrics timed out."),
+                }
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self {})
+    }
+}
+
+async fn render<B: StorageBackend>(
+    tangle: &MsTangle<B>,
+    storage: &ResourceHandle<B>,
+    peer_manager: &PeerManager,
+    node_metrics: &NodeMetrics,
+) -> String {
+    let mut exposition = Exposition::default();
+
+    for (name, help, value) in NODE_COUNTERS {
+        let name = format!("bee_{}_total", name);
+        exposition
+            .family(&name, MetricType::Counter, help)
+            .sample(&name, &[], value(node_metrics));
+    }
+    exposition
+        .family(
+            "bee_messages_average_latency",
+            MetricType::Gauge,
+            "Average latency of the messages.",
+        )
+        .sample(
+            "bee_messages_average_latency",
+            &[],
+            node_metrics.messages_average_latency(),
+        );
+
+    let peers = peer_manager
+        .get_all()
+        .await
+        .into_iter()
+        .map(|peer| (peer.id().to_string(), peer))
+        .collect::<Vec<_>>();
+
+    for (name, help, value) in PEER_COUNTERS {
+        let name = format!("bee_peer_{}_total", name);
+        exposition.family(&name, MetricType::Counter, help);
+        for (id, peer) in &peers {
+            exposition.sample(
+                &name,
+                &[("peer_id", id.as_str()), ("alias", peer.alias().as_str())],
+                value(peer.metrics()),
+            );
+        }
+    }
+    exposition.family(
+        "bee_peer_connected",
+        MetricType::Gauge,
+        "Whether the peer is connected.",
+    );
+    for (id, peer) in &peers {
+        exposition.sample(
+            "bee_peer_connected",
+            &[("peer_id", id.as_str()), ("alias", peer.alias().as_str())],
+            peer.is_connected() as u8,
+        );
+    }
+
+    let indexes = [
+        (
+            "latest_milestone_index",
+            "Index of the latest milestone.",
+            tangle.get_latest_milestone_index(),
+        ),
+        (
+            "solid_milestone_index",
+            "Index of the solid milestone.",
+            tangle.get_solid_milestone_index(),
+        ),
+        (
+            "confirmed_milestone_index",
+            "Index of the confirmed milestone.",
+            tangle.get_confirmed_milestone_index(),
+        ),
+        ("snapshot_index", "Index of the snapshot.", tangle.get_snapshot_index()),
+        (
+            "pruning_index",
+            "Index up to which the tangle is pruned.",
+            tangle.get_pruning_index(),
+        ),
+    ];
+
+    for (name, help, index) in &indexes {
+        let name = format!("bee_tangle_{}", name);
+        exposition
+            .family(&name, MetricType::Gauge, help)
+            .sample(&name, &[], **index);
+    }
+    exposition
+        .family("bee_tangle_tips", MetricType::Gauge, "Non-lazy tips in the tip pool.")
+        .sample("bee_tangle_tips", &[], tangle.non_lazy_tips_num().await);
+
+    // Not all backends can tell their size, and those that can may have to go through their files.
+    let storage = storage.clone();
+    if let Ok(Some(size)) = task::spawn_blocking(move || storage.size().ok().flatten()).await {
+        exposition
+            .family("bee_storage_size_bytes", MetricType::Gauge, "Size of the database.")
+            .sample("bee_storage_size_bytes", &[], size);
+    }
+
+    exposition.finish()
+}
+
+/// Reads an HTTP request up to the end of its head, which is all there is to a scrape.
+async fn read_request_head(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD_SIZE {
+        match stream.read(&mut buf).await? {
+            0 => break,
+            read => head.extend_from_slice(&buf[..read]),
+        }
+    }
+
+    Ok(head)
+}
+
+/// Returns the method and path of an HTTP request.
+fn parse_request_line(head: &[u8]) -> Option<(&str, &str)> {
+    let line = head.split(|byte| *byte == b'\n').next()?;
+    let mut parts = std::str::from_utf8(line).ok()?.split_whitespace();
+
+    Some((parts.next()?, parts.next()?))
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: \
+         close\r\n\r\n",
+        status,
+        body.len()
+    );
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_line() {
+        assert_eq!(
+            parse_request_line(b"GET /metrics HTTP/1.1\r\nHost: localhost:9311\r\n\r\n"),
+            Some(("GET", "/metrics"))
+        );
+        assert_eq!(parse_request_line(b"GET\r\n\r\n"), None);
+        assert_eq!(parse_request_line(b""), None);
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+
+use std::time::Duration;
+
+const DEFAULT_INTERVAL_SECS: u64 = 10;
+const DEFAULT_BAN_THRESHOLD: i32 = -50;
+const DEFAULT_BAN_DURATION_SECS: u64 = 600;
+const DEFAULT_MAX_IDLE_INTERVALS: u32 = 30;
+const DEFAULT_MAX_HEARTBEAT_AGE_SECS: u64 = 60;
+
+/// A builder type for a reputation configuration.
+#[derive(Default, Deserialize)]
+pub struct ReputationConfigBuilder {
+    interval_secs: Option<u64>,
+    ban_threshold: Option<i32>,
+    ban_duration_secs: Option<u64>,
+    max_idle_intervals: Option<u32>,
+    max_heartbeat_age_secs: Option<u64>,
+}
+
+impl ReputationConfigBuilder {
+    /// Creates a new `ReputationConfigBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the interval, in seconds, at which peers are evaluated.
+    pub fn interval_secs(mut self, interval_secs: u64) -> Self {
+        self.interval_secs.replace(interval_secs);
+        self
+    }
+
+    /// Sets the score at or below which a peer gets banned.
+    pub fn ban_threshold(mut self, ban_threshold: i32) -> Self {
+        self.ban_threshold.replace(ban_threshold);
+        self
+    }
+
+    /// Sets the duration, in seconds, of a ban.
+    pub fn ban_duration_secs(mut self, ban_duration_secs: u64) -> Self {
+        self.ban_duration_secs.replace(ban_duration_secs);
+        self
+    }
+
+    /// Sets the number of consecutive intervals without new messages after which a peer gets disconnected.
+    pub fn max_idle_intervals(mut self, max_idle_intervals: u32) -> Self {
+        self.max_idle_intervals.replace(max_idle_intervals);
+        self
+    }
+
+    /// Sets the age, in seconds, above which the last heartbeat of a peer is considered stale.
+    pub fn max_heartbeat_age_secs(mut self, max_heartbeat_age_secs: u64) -> Self {
+        self.max_heartbeat_age_secs.replace(max_heartbeat_age_secs);
+        self
+    }
+
+    /// Finishes building the reputation configuration, to create a `ReputationConfig`.
+    pub fn finish(self) -> ReputationConfig {
+        ReputationConfig {
+            // An interval of 0 would make the ticker panic.
+            interval: Duration::from_secs(self.interval_secs.unwrap_or(DEFAULT_INTERVAL_SECS).max(1)),
+            ban_threshold: self.ban_threshold.unwrap_or(DEFAULT_BAN_THRESHOLD),
+            ban_duration: Duration::from_secs(self.ban_duration_secs.unwrap_or(DEFAULT_BAN_DURATION_SECS)),
+            max_idle_intervals: self.max_idle_intervals.unwrap_or(DEFAULT_MAX_IDLE_INTERVALS),
+            max_heartbeat_age: Duration::from_secs(
+                self.max_heartbeat_age_secs.unwrap_or(DEFAULT_MAX_HEARTBEAT_AGE_SECS),
+            ),
+        }
+    }
+}
+
+/// The configuration state of the reputation worker.
+#[derive(Clone, Debug)]
+pub struct ReputationConfig {
+    interval: Duration,
+    ban_threshold: i32,
+    ban_duration: Duration,
+    max_idle_intervals: u32,
+    max_heartbeat_age: Duration,
+}
+
+impl ReputationConfig {
+    /// Begins building a new `ReputationConfig`.
+    pub fn build() -> ReputationConfigBuilder {
+        ReputationConfigBuilder::new()
+    }
+
+    /// Returns the interval at which peers are evaluated.
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Returns the score at or below which a peer gets banned.
+    pub fn ban_threshold(&self) -> i32 {
+        self.ban_threshold
+    }
+
+    /// Returns the duration of a ban.
+    pub fn ban_duration(&self) -> Duration {
+        self.ban_duration
+    }
+
+    /// Returns the number of consecutive intervals without new messages after which a peer gets disconnected.
+    pub fn max_idle_intervals(&self) -> u32 {
+        self.max_idle_intervals
+    }
+
+    /// Returns the age above which the last heartbeat of a peer is considered stale.
+    pub fn max_heartbeat_age(&self) -> Duration {
+        self.max_heartbeat_age
+    }
+}
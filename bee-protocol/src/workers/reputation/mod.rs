@@ -0,0 +1,12 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that scores peers by their behaviour and gets rid of misbehaving or useless ones.
+
+mod config;
+mod score;
+mod worker;
+
+pub use config::{ReputationConfig, ReputationConfigBuilder};
+pub use score::{PeerReputation, Verdict};
+pub use worker::ReputationWorker;
@@ -0,0 +1,234 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{types::metrics::PeerMetrics, workers::reputation::ReputationConfig};
+
+use bee_network::PeerRelation;
+
+use std::time::Duration;
+
+const MIN_SCORE: i32 = -100;
+const MAX_SCORE: i32 = 100;
+const NEW_MESSAGE_REWARD: i64 = 1;
+const MAX_NEW_MESSAGES_REWARD: i64 = 10;
+const INVALID_MESSAGE_PENALTY: i64 = 10;
+const INVALID_PACKET_PENALTY: i64 = 20;
+const RATE_LIMITED_MESSAGE_PENALTY: i64 = 1;
+const FILTERED_MESSAGE_PENALTY: i64 = 1;
+const STALE_HEARTBEAT_PENALTY: i64 = 5;
+
+/// Snapshot of the metrics counters relevant for scoring.
+#[derive(Clone, Copy, Default)]
+struct Counters {
+    invalid_packets: u64,
+    invalid_messages: u64,
+    known_messages: u64,
+    new_messages: u64,
+    rate_limited_messages: u64,
+    filtered_messages: u64,
+}
+
+impl Counters {
+    fn from_metrics(metrics: &PeerMetrics) -> Self {
+        Self {
+            invalid_packets: metrics.invalid_packets(),
+            invalid_messages: metrics.invalid_messages(),
+            known_messages: metrics.known_messages(),
+            new_messages: metrics.new_messages(),
+            rate_limited_messages: metrics.rate_limited_messages(),
+            filtered_messages: metrics.filtered_messages(),
+        }
+    }
+}
+
+/// What should happen to a peer after its evaluation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    /// The peer is kept.
+    Keep,
+    /// The peer is disconnected because it didn't provide anything useful for too long.
+    Disconnect,
+    /// The peer is disconnected and temporarily banned because it misbehaved.
+    Ban,
+}
+
+/// Keeps track of the reputation of a peer across evaluations.
+#[derive(Default)]
+pub struct PeerReputation {
+    counters: Counters,
+    score: i32,
+    idle_intervals: u32,
+}
+
+impl PeerReputation {
+    /// Returns the current score of the `PeerReputation`.
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    /// Returns the number of consecutive intervals without new messages of the `PeerReputation`.
+    pub fn idle_intervals(&self) -> u32 {
+        self.idle_intervals
+    }
+
+    /// Updates the score with the metrics accumulated since the last evaluation and returns it.
+    ///
+    /// The previous score decays by 10% each evaluation, so old (mis)behaviour is eventually forgotten. New messages
+    /// are rewarded up to a cap, invalid messages and packets are penalized, and so are a stale heartbeat and, more
+    /// lightly, messages dropped by the rate limits or spam filters.
+    pub fn evaluate(
+        &mut self,
+        metrics: &PeerMetrics,
+        heartbeat_age: Option<Duration>,
+        config: &ReputationConfig,
+    ) -> i32 {
+        let counters = Counters::from_metrics(metrics);
+        // Counters only grow, but saturate anyway in case the metrics were reset.
+        let new_messages = counters.new_messages.saturating_sub(self.counters.new_messages) as i64;
+        let invalid_messages = counters.invalid_messages.saturating_sub(self.counters.invalid_messages) as i64;
+        let invalid_packets = counters.invalid_packets.saturating_sub(self.counters.invalid_packets) as i64;
+        let known_messages = counters.known_messages.saturating_sub(self.counters.known_messages);
+        let rate_limited_messages = counters
+            .rate_limited_messages
+            .saturating_sub(self.counters.rate_limited_messages) as i64;
+        let filtered_messages = counters
+            .filtered_messages
+            .saturating_sub(self.counters.filtered_messages) as i64;
+        self.counters = counters;
+
+        let mut delta = (new_messages * NEW_MESSAGE_REWARD).min(MAX_NEW_MESSAGES_REWARD)
+            - invalid_messages * INVALID_MESSAGE_PENALTY
+            - invalid_packets * INVALID_PACKET_PENALTY
+            - rate_limited_messages * RATE_LIMITED_MESSAGE_PENALTY
+            - filtered_messages * FILTERED_MESSAGE_PENALTY;
+
+        if heartbeat_age.map_or(false, |age| age > config.max_heartbeat_age()) {
+            delta -= STALE_HEARTBEAT_PENALTY;
+        }
+
+        // Known messages show the peer is participating, even if it is not the first to deliver.
+        if new_messages == 0 && known_messages == 0 {
+            self.idle_intervals = self.idle_intervals.saturating_add(1);
+        } else {
+            self.idle_intervals = 0;
+        }
+
+        let decayed = self.score as i64 * 9 / 10;
+        self.score = (decayed + delta).clamp(MIN_SCORE as i64, MAX_SCORE as i64) as i32;
+
+        self.score
+    }
+
+    /// Decides what should happen to a peer with the given relation. Known peers are never disconnected or banned.
+    pub fn verdict(&self, relation: PeerRelation, config: &ReputationConfig) -> Verdict {
+        if relation.is_known() {
+            Verdict::Keep
+        } else if self.score <= config.ban_threshold() {
+            Verdict::Ban
+        } else if self.idle_intervals >= config.max_idle_intervals() {
+            Verdict::Disconnect
+        } else {
+            Verdict::Keep
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ReputationConfig {
+        ReputationConfig::build()
+            .ban_threshold(-50)
+            .max_idle_intervals(3)
+            .finish()
+    }
+
+    #[test]
+    fn useful_peer_is_kept() {
+        let config = config();
+        let metrics = PeerMetrics::default();
+        let mut reputation = PeerReputation::default();
+
+        for _ in 0..20 {
+            metrics.new_messages_inc();
+        }
+
+        assert_eq!(
+            reputation.evaluate(&metrics, None, &config),
+            MAX_NEW_MESSAGES_REWARD as i32
+        );
+        assert_eq!(reputation.verdict(PeerRelation::Unknown, &config), Verdict::Keep);
+    }
+
+    #[test]
+    fn misbehaving_peer_is_banned() {
+        let config = config();
+        let metrics = PeerMetrics::default();
+        let mut reputation = PeerReputation::default();
+
+        for _ in 0..3 {
+            metrics.invalid_packets_inc();
+        }
+
+        assert_eq!(reputation.evaluate(&metrics, None, &config), -60);
+        assert_eq!(reputation.verdict(PeerRelation::Unknown, &config), Verdict::Ban);
+        assert_eq!(reputation.verdict(PeerRelation::Discovered, &config), Verdict::Ban);
+        assert_eq!(reputation.verdict(PeerRelation::Known, &config), Verdict::Keep);
+    }
+
+    #[test]
+    fn flooding_peer_is_banned() {
+        let config = config();
+        let metrics = PeerMetrics::default();
+        let mut reputation = PeerReputation::default();
+
+        for _ in 0..20 {
+            metrics.new_messages_inc();
+        }
+        for _ in 0..50 {
+            metrics.rate_limited_messages_inc();
+        }
+        for _ in 0..10 {
+            metrics.filtered_messages_inc();
+        }
+
+        assert_eq!(reputation.evaluate(&metrics, None, &config), -50);
+        assert_eq!(reputation.verdict(PeerRelation::Unknown, &config), Verdict::Ban);
+    }
+
+    #[test]
+    fn idle_peer_is_disconnected() {
+        let config = config();
+        let metrics = PeerMetrics::default();
+        let mut reputation = PeerReputation::default();
+
+        for _ in 0..3 {
+            reputation.evaluate(&metrics, Some(Duration::from_secs(120)), &config);
+        }
+
+        assert!(reputation.score() < 0);
+        assert_eq!(reputation.idle_intervals(), 3);
+        assert_eq!(
+            reputation.verdict(PeerRelation::Discovered, &config),
+            Verdict::Disconnect
+        );
+
+        metrics.known_messages_inc();
+        reputation.evaluate(&metrics, None, &config);
+
+        assert_eq!(reputation.idle_intervals(), 0);
+    }
+
+    #[test]
+    fn score_decays() {
+        let config = config();
+        let metrics = PeerMetrics::default();
+        let mut reputation = PeerReputation::default();
+
+        metrics.invalid_messages_inc();
+
+        assert_eq!(reputation.evaluate(&metrics, None, &config), -10);
+        assert_eq!(reputation.evaluate(&metrics, None, &config), -9);
+    }
+}
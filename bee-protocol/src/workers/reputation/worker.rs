@@ -0,0 +1,119 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    event::PeerScoreUpdated,
+    workers::{
+        peer::{PeerManager, PeerManagerResWorker},
+        reputation::{PeerReputation, ReputationConfig, Verdict},
+    },
+};
+
+use bee_network::{Command, NetworkCommandSender, PeerId};
+use bee_runtime::{node::Node, shutdown_stream::ShutdownStream, worker::Worker};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use hashbrown::HashMap;
+use log::{info, warn};
+use tokio::time::interval;
+use tokio_stream::wrappers::IntervalStream;
+
+use std::{
+    any::TypeId,
+    convert::Infallible,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A worker that periodically scores the connected peers and publishes the scores on the event bus.
+///
+/// Unknown and discovered peers whose score drops to the ban threshold are removed and temporarily banned, those that
+/// stay idle for too long are removed to free their slot.
+#[derive(Default)]
+pub struct ReputationWorker {}
+
+#[async_trait]
+impl<N: Node> Worker<N> for ReputationWorker {
+    type Config = ReputationConfig;
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        vec![TypeId::of::<PeerManagerResWorker>()].leak()
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let peer_manager = node.resource::<PeerManager>();
+        let network = node.resource::<NetworkCommandSender>();
+        let bus = node.bus();
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let mut ticker = ShutdownStream::new(shutdown, IntervalStream::new(interval(config.interval())));
+            let mut reputations = HashMap::<PeerId, PeerReputation>::new();
+
+            while ticker.next().await.is_some() {
+                let peers = peer_manager.get_all().await;
+
+                reputations.retain(|peer_id, _| peers.iter().any(|peer| peer.id() == peer_id && peer.is_connected()));
+
+                for peer in peers.iter().filter(|peer| peer.is_connected()) {
+                    let peer_id = *peer.id();
+                    let reputation = reputations.entry(peer_id).or_default();
+                    let score = reputation.evaluate(
+                        peer.metrics(),
+                        heartbeat_age(peer.heartbeat_received_timestamp()),
+                        &config,
+                    );
+
+                    bus.dispatch(PeerScoreUpdated { peer_id, score });
+
+                    let verdict = reputation.verdict(peer.relation(), &config);
+
+                    if verdict == Verdict::Keep {
+                        continue;
+                    }
+
+                    if verdict == Verdict::Ban {
+                        info!("Banning {} with score {}.", peer.alias(), score);
+
+                        if let Err(e) = network.send(Command::BanPeerFor {
+                            peer_id,
+                            duration: config.ban_duration(),
+                        }) {
+                            warn!("Banning {} failed: {:?}.", peer.alias(), e);
+                        }
+                    } else {
+                        info!(
+                            "Removing {} after {} idle intervals.",
+                            peer.alias(),
+                            reputation.idle_intervals()
+                        );
+                    }
+
+                    // Removing instead of merely disconnecting frees the slot for another unknown or discovered peer.
+                    if let Err(e) = network.send(Command::RemovePeer { peer_id }) {
+                        warn!("Removing {} failed: {:?}.", peer.alias(), e);
+                    }
+
+                    reputations.remove(&peer_id);
+                }
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self::default())
+    }
+}
+
+/// Returns the age of the last received heartbeat, or `None` if no heartbeat was received yet.
+fn heartbeat_age(timestamp_millis: u64) -> Option<Duration> {
+    if timestamp_millis == 0 {
+        return None;
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+
+    Some(now.saturating_sub(Duration::from_millis(timestamp_millis)))
+}
@@ -0,0 +1,10 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that answers the requests of peers for milestones and messages.
+
+mod worker;
+
+pub use worker::ResponderWorker;
+
+pub(crate) use worker::ResponderWorkerEvent;
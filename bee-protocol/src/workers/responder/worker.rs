@@ -0,0 +1,128 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    types::{
+        metrics::NodeMetrics,
+        packets::{tlv_to_bytes, MessagePacket, MessageRequestPacket, MilestoneRequestPacket},
+        peer::Peer,
+    },
+    workers::{
+        metrics::NodeMetricsResWorker,
+        peer::{PeerManager, PeerManagerResWorker},
+    },
+};
+
+use bee_common::packable::Packable;
+use bee_message::milestone::MilestoneIndex;
+use bee_runtime::{node::Node, shutdown_stream::ShutdownStream, worker::Worker};
+use bee_tangle::{storage::StorageBackend, MsTangle, TangleWorker};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use log::{debug, info, trace};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+use std::{any::TypeId, convert::Infallible, sync::Arc};
+
+pub(crate) enum ResponderWorkerEvent {
+    Milestone {
+        from: Arc<Peer>,
+        request: MilestoneRequestPacket,
+    },
+    Message {
+        from: Arc<Peer>,
+        request: MessageRequestPacket,
+    },
+}
+
+/// A worker that sends the requested milestones and messages that are in the tangle to the peers requesting them.
+pub struct ResponderWorker {
+    pub(crate) tx: mpsc::UnboundedSender<ResponderWorkerEvent>,
+}
+
+#[async_trait]
+impl<N: Node> Worker<N> for ResponderWorker
+where
+    N::Backend: StorageBackend,
+{
+    type Config = ();
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        vec![
+            TypeId::of::<TangleWorker>(),
+            TypeId::of::<PeerManagerResWorker>(),
+            TypeId::of::<NodeMetricsResWorker>(),
+        ]
+        .leak()
+    }
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        let tangle = node.resource::<MsTangle<N::Backend>>();
+        let peer_manager = node.resource::<PeerManager>();
+        let metrics = node.resource::<NodeMetrics>();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let mut receiver = ShutdownStream::new(shutdown, UnboundedReceiverStream::new(rx));
+
+            while let Some(event) = receiver.next().await {
+                let (from, message_id) = match event {
+                    ResponderWorkerEvent::Milestone { from, request } => {
+                        metrics.milestone_requests_received_inc();
+                        from.metrics().milestone_requests_received_inc();
+
+                        let index = match request.index {
+                            0 => tangle.get_latest_milestone_index(),
+                            index => MilestoneIndex(index),
+                        };
+
+                        match tangle.get_milestone_message_id(index).await {
+                            Some(message_id) => (from, message_id),
+                            None => {
+                                trace!("Milestone {} requested by {} is unknown.", *index, from.alias());
+                                continue;
+                            }
+                        }
+                    }
+                    ResponderWorkerEvent::Message { from, request } => {
+                        metrics.message_requests_received_inc();
+                        from.metrics().message_requests_received_inc();
+
+                        (from, request.message_id)
+                    }
+                };
+
+                let message = match tangle.get(&message_id).await {
+                    Some(message) => message,
+                    None => {
+                        trace!("Message {} requested by {} is unknown.", message_id, from.alias());
+                        continue;
+                    }
+                };
+
+                let sender = match peer_manager.sender(from.id()).await {
+                    Some(sender) => sender,
+                    None => continue,
+                };
+
+                match sender.try_send(tlv_to_bytes(&MessagePacket::new(message.pack_new()))) {
+                    Ok(()) => {
+                        from.set_known_message(message_id);
+                        metrics.messages_sent_inc();
+                        from.metrics().messages_sent_inc();
+                    }
+                    Err(e) => debug!("Sending message {} to {} failed: {}.", message_id, from.alias(), e),
+                }
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self { tx })
+    }
+}
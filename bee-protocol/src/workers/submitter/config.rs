@@ -0,0 +1,67 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+
+use std::time::Duration;
+
+const DEFAULT_NUM_WORKERS: usize = 1;
+const DEFAULT_TIMEOUT_MS: u64 = 60_000;
+
+/// A builder type for a submitter configuration.
+#[derive(Default, Deserialize)]
+pub struct SubmitterConfigBuilder {
+    num_workers: Option<usize>,
+    timeout_ms: Option<u64>,
+}
+
+impl SubmitterConfigBuilder {
+    /// Creates a new `SubmitterConfigBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of threads doing the Proof of Work of a submitted message.
+    pub fn num_workers(mut self, num_workers: usize) -> Self {
+        self.num_workers.replace(num_workers);
+        self
+    }
+
+    /// Sets the time, in milliseconds, after which the Proof of Work of a submitted message is given up.
+    pub fn timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms.replace(timeout_ms);
+        self
+    }
+
+    /// Finishes building the submitter configuration, to create a `SubmitterConfig`.
+    pub fn finish(self) -> SubmitterConfig {
+        SubmitterConfig {
+            num_workers: self.num_workers.unwrap_or(DEFAULT_NUM_WORKERS).max(1),
+            timeout: Duration::from_millis(self.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS)),
+        }
+    }
+}
+
+/// The configuration state of the submitter.
+#[derive(Clone, Debug)]
+pub struct SubmitterConfig {
+    num_workers: usize,
+    timeout: Duration,
+}
+
+impl SubmitterConfig {
+    /// Begins building a new `SubmitterConfig`.
+    pub fn build() -> SubmitterConfigBuilder {
+        SubmitterConfigBuilder::new()
+    }
+
+    /// Returns the number of threads doing the Proof of Work of a submitted message.
+    pub fn num_workers(&self) -> usize {
+        self.num_workers
+    }
+
+    /// Returns the time after which the Proof of Work of a submitted message is given up.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
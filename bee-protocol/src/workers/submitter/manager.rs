@@ -0,0 +1,270 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    types::packets::MessagePacket,
+    workers::{
+        message::{HasherWorkerEvent, MessageConfig},
+        submitter::SubmitterConfig,
+    },
+};
+
+use bee_message::{parents::Parents, payload::Payload, Message, MessageBuilder, MessageId};
+use bee_pow::{
+    providers::{
+        miner::{Miner, MinerBuilder, MinerCancel},
+        NonceProviderBuilder,
+    },
+    score::compute_pow_score,
+};
+use bee_runtime::resource::ResourceHandle;
+use bee_tangle::{storage::StorageBackend, MsTangle};
+
+use log::debug;
+use tokio::{
+    sync::{mpsc, oneshot},
+    task,
+    time::timeout,
+};
+
+use std::{fmt, time::Duration};
+
+/// Errors occurring while submitting a message.
+#[derive(Debug)]
+pub enum SubmitterError {
+    /// There are no tips to attach the message to.
+    NoTips,
+    /// The message could not be built.
+    InvalidMessage(bee_message::Error),
+    /// The Proof of Work didn't complete within the configured timeout.
+    PowTimeout,
+    /// The Proof of Work was aborted before reaching the minimum score.
+    PowAborted,
+    /// The message was rejected on its way to the tangle.
+    Rejected,
+    /// The message pipeline isn't running.
+    Unavailable,
+}
+
+impl fmt::Display for SubmitterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitterError::NoTips => write!(f, "No tips to attach the message to."),
+            SubmitterError::InvalidMessage(e) => write!(f, "Invalid message: {}.", e),
+            SubmitterError::PowTimeout => write!(f, "Proof of Work timed out."),
+            SubmitterError::PowAborted => write!(f, "Proof of Work aborted."),
+            SubmitterError::Rejected => write!(f, "Message rejected."),
+            SubmitterError::Unavailable => write!(f, "Message pipeline unavailable."),
+        }
+    }
+}
+
+impl std::error::Error for SubmitterError {}
+
+/// Builds the messages issued by the node itself, does their Proof of Work and hands them to the
+/// [`HasherWorker`](crate::workers::HasherWorker), so that they are validated, inserted and gossiped like the
+/// messages received from peers.
+pub struct MessageSubmitter<B> {
+    tangle: ResourceHandle<MsTangle<B>>,
+    hasher: mpsc::UnboundedSender<HasherWorkerEvent>,
+    message_config: MessageConfig,
+    config: SubmitterConfig,
+}
+
+impl<B: StorageBackend> MessageSubmitter<B> {
+    pub(crate) fn new(
+        tangle: ResourceHandle<MsTangle<B>>,
+        hasher: mpsc::UnboundedSender<HasherWorkerEvent>,
+        message_config: MessageConfig,
+        config: SubmitterConfig,
+    ) -> Self {
+        Self {
+            tangle,
+            hasher,
+            message_config,
+            config,
+        }
+    }
+
+    /// Submits a message carrying an optional payload and returns its id once it is in the tangle.
+    ///
+    /// The message approves the given parents or, if there are none, tips selected from the tangle.
+    pub async fn submit(
+        &self,
+        payload: Option<Payload>,
+        parents: Option<Vec<MessageId>>,
+    ) -> Result<MessageId, SubmitterError> {
+        let parents = match parents {
+            Some(parents) => parents,
+            None => self
+                .tangle
+                .get_messages_to_approve()
+                .await
+                .ok_or(SubmitterError::NoTips)?,
+        };
+
+        let mut builder = Message::builder()
+            .with_network_id(self.message_config.network_id())
+            .with_parents(sorted_parents(parents)?);
+        if let Some(payload) = payload {
+            builder = builder.with_payload(payload);
+        }
+
+        let target_score = self.message_config.minimum_pow_score();
+        let message = mine(builder, self.config.num_workers(), target_score, self.config.timeout()).await?;
+        let (message_id, bytes) = message.id();
+
+        // The builder falls back to a default nonce instead of failing when the miner gives up.
+        if compute_pow_score(&bytes) < target_score {
+            return Err(SubmitterError::PowAborted);
+        }
+
+        hand_over(&self.hasher, bytes).await?;
+
+        debug!("Submitted message {}.", message_id);
+
+        Ok(message_id)
+    }
+}
+
+/// Triggers a [`MinerCancel`] when dropped, so the miners stop once the submission is given up on, e.g. because its
+/// future was dropped.
+struct CancelGuard(MinerCancel);
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        self.0.trigger();
+    }
+}
+
+/// Returns the parents sorted and without duplicates, as a message requires them.
+fn sorted_parents(mut parents: Vec<MessageId>) -> Result<Parents, SubmitterError> {
+    parents.sort_unstable();
+    parents.dedup();
+
+    Parents::new(parents).map_err(SubmitterError::InvalidMessage)
+}
+
+/// Does the Proof of Work of a message on blocking threads, giving up after `pow_timeout`.
+async fn mine(
+    builder: MessageBuilder<Miner>,
+    num_workers: usize,
+    target_score: f64,
+    pow_timeout: Duration,
+) -> Result<Message, SubmitterError> {
+    let cancel = CancelGuard(MinerCancel::new());
+    let miner = MinerBuilder::default()
+        .with_num_workers(num_workers)
+        .with_cancel(cancel.0.clone())
+        .finish();
+    let mut pow = task::spawn_blocking(move || builder.with_nonce_provider(miner, target_score).finish());
+
+    match timeout(pow_timeout, &mut pow).await {
+        Ok(Ok(message)) => message.map_err(SubmitterError::InvalidMessage),
+        Ok(Err(_)) => Err(SubmitterError::PowAborted),
+        Err(_) => {
+            drop(cancel);
+            // Waiting for the miner threads to wind down so that they don't outlive the submission.
+            let _ = pow.await;
+            Err(SubmitterError::PowTimeout)
+        }
+    }
+}
+
+/// Hands a message to the hasher, and waits until it tells whether the message made it into the tangle.
+async fn hand_over(hasher: &mpsc::UnboundedSender<HasherWorkerEvent>, bytes: Vec<u8>) -> Result<(), SubmitterError> {
+    let (notifier, accepted) = oneshot::channel();
+
+    hasher
+        .send(HasherWorkerEvent {
+            from: None,
+            message_packet: MessagePacket::new(bytes),
+            notifier: Some(notifier),
+        })
+        .map_err(|_| SubmitterError::Unavailable)?;
+
+    match accepted.await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(SubmitterError::Rejected),
+        Err(_) => Err(SubmitterError::Unavailable),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_id(byte: u8) -> MessageId {
+        MessageId::new([byte; 32])
+    }
+
+    #[test]
+    fn parents_are_sorted_and_deduplicated() {
+        let parents = sorted_parents(vec![message_id(3), message_id(1), message_id(3), message_id(2)]).unwrap();
+
+        assert_eq!(
+            parents.iter().copied().collect::<Vec<_>>(),
+            vec![message_id(1), message_id(2), message_id(3)]
+        );
+        assert!(matches!(
+            sorted_parents(Vec::new()),
+            Err(SubmitterError::InvalidMessage(_))
+        ));
+    }
+
+    #[test]
+    fn dropped_guard_cancels_the_miners() {
+        let cancel = MinerCancel::new();
+
+        drop(CancelGuard(cancel.clone()));
+
+        assert!(cancel.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn pow_times_out() {
+        let builder = Message::builder()
+            .with_network_id(0)
+            .with_parents(sorted_parents(vec![message_id(1)]).unwrap());
+
+        // A score that takes far longer than the timeout to reach.
+        let result = mine(builder, 1, 1e25, Duration::from_millis(50)).await;
+
+        assert!(matches!(result, Err(SubmitterError::PowTimeout)));
+    }
+
+    #[tokio::test]
+    async fn acceptance_is_told_by_the_hasher() {
+        let (hasher, mut events) = mpsc::unbounded_channel::<HasherWorkerEvent>();
+
+        let answers = tokio::spawn(async move {
+            for accepted in vec![Some(true), Some(false), None] {
+                let event = events.recv().await.unwrap();
+
+                assert!(event.from.is_none());
+                if let Some(accepted) = accepted {
+                    event.notifier.unwrap().send(accepted).unwrap();
+                }
+            }
+        });
+
+        assert!(hand_over(&hasher, vec![1]).await.is_ok());
+        assert!(matches!(
+            hand_over(&hasher, vec![2]).await,
+            Err(SubmitterError::Rejected)
+        ));
+        // The notifier is dropped without an answer.
+        assert!(matches!(
+            hand_over(&hasher, vec![3]).await,
+            Err(SubmitterError::Unavailable)
+        ));
+
+        answers.await.unwrap();
+
+        // The hasher is gone.
+        assert!(matches!(
+            hand_over(&hasher, vec![4]).await,
+            Err(SubmitterError::Unavailable)
+        ));
+    }
+}
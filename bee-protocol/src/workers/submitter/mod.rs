@@ -0,0 +1,13 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that lets the node issue its own messages, attaching them to tips and doing their Proof of Work before
+//! handing them to the same pipeline as the messages received from peers.
+
+mod config;
+mod manager;
+mod worker;
+
+pub use config::{SubmitterConfig, SubmitterConfigBuilder};
+pub use manager::{MessageSubmitter, SubmitterError};
+pub use worker::SubmitterWorker;
@@ -0,0 +1,46 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::workers::{
+    message::{HasherWorker, MessageConfig},
+    submitter::{MessageSubmitter, SubmitterConfig},
+};
+
+use bee_runtime::{node::Node, worker::Worker};
+use bee_tangle::{storage::StorageBackend, MsTangle, TangleWorker};
+
+use async_trait::async_trait;
+
+use std::{any::TypeId, convert::Infallible};
+
+/// A worker that registers the [`MessageSubmitter`] as a node resource.
+pub struct SubmitterWorker {}
+
+#[async_trait]
+impl<N: Node> Worker<N> for SubmitterWorker
+where
+    N::Backend: StorageBackend,
+{
+    type Config = (MessageConfig, SubmitterConfig);
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        vec![TypeId::of::<TangleWorker>(), TypeId::of::<HasherWorker>()].leak()
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let (message_config, config) = config;
+        let tangle = node.resource::<MsTangle<N::Backend>>();
+        let hasher = node.worker::<HasherWorker>().unwrap().tx.clone();
+
+        node.register_resource(MessageSubmitter::new(tangle, hasher, message_config, config));
+
+        Ok(Self {})
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        node.remove_resource::<MessageSubmitter<N::Backend>>();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+
+use std::time::Duration;
+
+const DEFAULT_WINDOW_SIZE: u32 = 50;
+const DEFAULT_RETRY_INTERVAL_MS: u64 = 5_000;
+const DEFAULT_THRESHOLD: u32 = 5;
+
+/// A builder type for a warp sync configuration.
+#[derive(Default, Deserialize)]
+pub struct WarpSyncConfigBuilder {
+    window_size: Option<u32>,
+    retry_interval_ms: Option<u64>,
+    threshold: Option<u32>,
+}
+
+impl WarpSyncConfigBuilder {
+    /// Creates a new `WarpSyncConfigBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of milestones following the solid one that are requested and solidified at the same time.
+    pub fn window_size(mut self, window_size: u32) -> Self {
+        self.window_size.replace(window_size);
+        self
+    }
+
+    /// Sets the delay, in milliseconds, after which a milestone or message that didn't arrive is requested again.
+    pub fn retry_interval_ms(mut self, retry_interval_ms: u64) -> Self {
+        self.retry_interval_ms.replace(retry_interval_ms);
+        self
+    }
+
+    /// Sets the number of milestones the node has to be behind for a warp sync to be reported.
+    pub fn threshold(mut self, threshold: u32) -> Self {
+        self.threshold.replace(threshold);
+        self
+    }
+
+    /// Finishes building the warp sync configuration, to create a `WarpSyncConfig`.
+    pub fn finish(self) -> WarpSyncConfig {
+        WarpSyncConfig {
+            window_size: self.window_size.unwrap_or(DEFAULT_WINDOW_SIZE).max(1),
+            // An interval of 0 would make the ticker panic.
+            retry_interval: Duration::from_millis(self.retry_interval_ms.unwrap_or(DEFAULT_RETRY_INTERVAL_MS).max(1)),
+            threshold: self.threshold.unwrap_or(DEFAULT_THRESHOLD).max(1),
+        }
+    }
+}
+
+/// The configuration state of the warp sync worker.
+#[derive(Clone, Debug)]
+pub struct WarpSyncConfig {
+    window_size: u32,
+    retry_interval: Duration,
+    threshold: u32,
+}
+
+impl WarpSyncConfig {
+    /// Begins building a new `WarpSyncConfig`.
+    pub fn build() -> WarpSyncConfigBuilder {
+        WarpSyncConfigBuilder::new()
+    }
+
+    /// Returns the number of milestones following the solid one that are requested and solidified at the same time.
+    pub fn window_size(&self) -> u32 {
+        self.window_size
+    }
+
+    /// Returns the delay after which a milestone or message that didn't arrive is requested again.
+    pub fn retry_interval(&self) -> Duration {
+        self.retry_interval
+    }
+
+    /// Returns the number of milestones the node has to be behind for a warp sync to be reported.
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+}
@@ -0,0 +1,11 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that brings the solid milestone of the node up to its latest milestone, by requesting the missing
+//! milestones and their past cones from peers and confirming them in order.
+
+mod config;
+mod worker;
+
+pub use config::{WarpSyncConfig, WarpSyncConfigBuilder};
+pub use worker::WarpSyncWorker;
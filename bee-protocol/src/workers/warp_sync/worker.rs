@@ -0,0 +1,764 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    event::{MessageProcessed, MilestoneProcessed, WarpSyncCompleted, WarpSyncProgress, WarpSyncStarted},
+    types::{
+        metrics::NodeMetrics,
+        packets::{tlv_to_bytes, MessageRequestPacket, MilestoneRequestPacket, Packet},
+        peer::Peer,
+    },
+    workers::{
+        metrics::NodeMetricsResWorker,
+        peer::{PeerManager, PeerManagerResWorker},
+        warp_sync::WarpSyncConfig,
+    },
+};
+
+use bee_ledger::workers::{
+    consensus::{ConsensusWorker, ConsensusWorkerCommand},
+    StorageBackend,
+};
+use bee_message::{milestone::MilestoneIndex, MessageId};
+use bee_runtime::{event::Bus, node::Node, resource::ResourceHandle, shutdown_stream::ShutdownStream, worker::Worker};
+use bee_tangle::{event::SolidMilestoneChanged, traversal::visit_parents_depth_first, MsTangle, TangleWorker};
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use hashbrown::{hash_map::Entry, HashMap, HashSet};
+use log::{debug, info, trace, warn};
+use tokio::{
+    sync::mpsc,
+    time::{interval, Instant},
+};
+use tokio_stream::wrappers::{IntervalStream, UnboundedReceiverStream};
+
+use std::{any::TypeId, collections::BTreeMap, convert::Infallible, sync::Arc, time::Duration};
+
+enum WarpSyncInput {
+    Tick,
+    Milestone(MilestoneIndex, MessageId),
+    Message(MessageId),
+}
+
+enum MilestoneState {
+    /// The milestone was requested at the given instant.
+    Requested(Instant),
+    /// The milestone is known and its past cone is being requested, until no message is missing anymore.
+    Solidifying {
+        message_id: MessageId,
+        missing: HashSet<MessageId>,
+    },
+}
+
+struct MissingMessage {
+    /// The milestones whose past cone contains the message.
+    milestones: HashSet<MilestoneIndex>,
+    requested: Instant,
+}
+
+/// The milestones following the solid milestone that are being synchronized, and the messages their past cones miss.
+#[derive(Default)]
+struct Window {
+    milestones: BTreeMap<MilestoneIndex, MilestoneState>,
+    missing: HashMap<MessageId, MissingMessage>,
+}
+
+impl Window {
+    fn contains(&self, index: MilestoneIndex) -> bool {
+        self.milestones.contains_key(&index)
+    }
+
+    fn is_solidifying(&self, index: MilestoneIndex) -> bool {
+        matches!(self.milestones.get(&index), Some(MilestoneState::Solidifying { .. }))
+    }
+
+    fn is_missing(&self, message_id: &MessageId) -> bool {
+        self.missing.contains_key(message_id)
+    }
+
+    fn requested(&mut self, index: MilestoneIndex, now: Instant) {
+        self.milestones.insert(index, MilestoneState::Requested(now));
+    }
+
+    /// Records the messages missing from the past cone of a milestone. Returns the ones that weren't missing yet, and
+    /// need to be requested.
+    fn solidifying(
+        &mut self,
+        index: MilestoneIndex,
+        message_id: MessageId,
+        missing: &[MessageId],
+        now: Instant,
+    ) -> Vec<MessageId> {
+        let requests = missing
+            .iter()
+            .filter(|message_id| self.wait_for(**message_id, index, now))
+            .copied()
+            .collect();
+
+        self.milestones.insert(
+            index,
+            MilestoneState::Solidifying {
+                message_id,
+                missing: missing.iter().copied().collect(),
+            },
+        );
+
+        requests
+    }
+
+    /// Records the arrival of a missing message, whose past cone still misses `missing_parents`. Returns the messages
+    /// that need to be requested, together with the milestone they are needed for.
+    fn arrived(
+        &mut self,
+        message_id: MessageId,
+        missing_parents: &[MessageId],
+        now: Instant,
+    ) -> Vec<(MessageId, MilestoneIndex)> {
+        let missing_message = match self.missing.remove(&message_id) {
+            Some(missing_message) => missing_message,
+            None => return Vec::new(),
+        };
+        let mut requests = Vec::new();
+
+        for index in missing_message.milestones {
+            if let Some(MilestoneState::Solidifying { missing, .. }) = self.milestones.get_mut(&index) {
+                missing.remove(&message_id);
+                missing.extend(missing_parents.iter().copied());
+            } else {
+                continue;
+            }
+
+            for parent in missing_parents {
+                if self.wait_for(*parent, index, now) {
+                    requests.push((*parent, index));
+                }
+            }
+        }
+
+        requests
+    }
+
+    /// Returns whether the message wasn't missing yet.
+    fn wait_for(&mut self, message_id: MessageId, index: MilestoneIndex, now: Instant) -> bool {
+        match self.missing.entry(message_id) {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().milestones.insert(index);
+                false
+            }
+            Entry::Vacant(entry) => {
+                let mut milestones = HashSet::new();
+                milestones.insert(index);
+                entry.insert(MissingMessage {
+                    milestones,
+                    requested: now,
+                });
+                true
+            }
+        }
+    }
+
+    /// Removes the milestone following the solid one if its past cone is complete, and returns its message id.
+    fn pop_complete(&mut self, solid_index: MilestoneIndex) -> Option<(MilestoneIndex, MessageId)> {
+        let index = solid_index + MilestoneIndex(1);
+
+        match self.milestones.get(&index) {
+            Some(MilestoneState::Solidifying { message_id, missing }) if missing.is_empty() => {
+                let message_id = *message_id;
+                self.milestones.remove(&index);
+                Some((index, message_id))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the milestones and messages that were requested at least `retry_interval` ago, and marks them as
+    /// requested again.
+    fn due(
+        &mut self,
+        retry_interval: Duration,
+        now: Instant,
+    ) -> (Vec<MilestoneIndex>, Vec<(MessageId, MilestoneIndex)>) {
+        let milestones = self
+            .milestones
+            .iter_mut()
+            .filter_map(|(index, state)| match state {
+                MilestoneState::Requested(requested) if now.saturating_duration_since(*requested) >= retry_interval => {
+                    *requested = now;
+                    Some(*index)
+                }
+                _ => None,
+            })
+            .collect();
+        let messages = self
+            .missing
+            .iter_mut()
+            .filter(|(_, missing)| now.saturating_duration_since(missing.requested) >= retry_interval)
+            .filter_map(|(message_id, missing)| {
+                missing.requested = now;
+                // The peers having the oldest milestone are the most likely to have the message.
+                missing.milestones.iter().min().map(|index| (*message_id, *index))
+            })
+            .collect();
+
+        (milestones, messages)
+    }
+
+    /// Forgets the milestones up to the solid one, and the messages only they were missing.
+    fn prune(&mut self, solid_index: MilestoneIndex) {
+        self.milestones = self.milestones.split_off(&(solid_index + MilestoneIndex(1)));
+        self.missing.retain(|_, missing| {
+            missing.milestones.retain(|index| *index > solid_index);
+            !missing.milestones.is_empty()
+        });
+    }
+}
+
+/// A worker that catches up with the latest milestone whenever the solid milestone is behind.
+///
+/// Milestones are requested in windows following the solid milestone, from peers whose heartbeat tells they have them.
+/// The past cones of the milestones of a window are requested in parallel, but milestones get solid and are confirmed
+/// strictly in order.
+///
+/// This is also the solidifier of the node: once synced, it keeps marking the past cones of the new milestones as solid
+/// and confirming them, hence the warp sync events only tell whether the node is far behind.
+#[derive(Default)]
+pub struct WarpSyncWorker {}
+
+#[async_trait]
+impl<N: Node> Worker<N> for WarpSyncWorker
+where
+    N::Backend: StorageBackend,
+{
+    type Config = WarpSyncConfig;
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        vec![
+            TypeId::of::<TangleWorker>(),
+            TypeId::of::<PeerManagerResWorker>(),
+            TypeId::of::<NodeMetricsResWorker>(),
+            TypeId::of::<ConsensusWorker>(),
+        ]
+        .leak()
+    }
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let tangle = node.resource::<MsTangle<N::Backend>>();
+        let peer_manager = node.resource::<PeerManager>();
+        let metrics = node.resource::<NodeMetrics>();
+        let consensus = node.worker::<ConsensusWorker>().unwrap().tx.clone();
+        let bus = node.bus();
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        {
+            let tx = tx.clone();
+            node.bus().add_listener::<Self, MilestoneProcessed, _>(move |event| {
+                let _ = tx.send(WarpSyncInput::Milestone(event.index, *event.milestone.message_id()));
+            });
+        }
+        node.bus().add_listener::<Self, MessageProcessed, _>(move |event| {
+            let _ = tx.send(WarpSyncInput::Message(event.message_id));
+        });
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            info!("Running.");
+
+            let ticker = IntervalStream::new(interval(config.retry_interval())).map(|_| WarpSyncInput::Tick);
+            let mut inputs = ShutdownStream::new(shutdown, stream::select(ticker, UnboundedReceiverStream::new(rx)));
+            let mut warp_sync = WarpSync {
+                solid_index: tangle.get_solid_milestone_index(),
+                config,
+                tangle,
+                peer_manager,
+                metrics,
+                bus,
+                consensus,
+                window: Window::default(),
+                session: None,
+                next_peer: 0,
+            };
+
+            while let Some(input) = inputs.next().await {
+                warp_sync.sync_solid_index();
+
+                match input {
+                    WarpSyncInput::Tick => warp_sync.retry().await,
+                    WarpSyncInput::Milestone(index, message_id) => warp_sync.on_milestone(index, message_id).await,
+                    WarpSyncInput::Message(message_id) => warp_sync.on_message(message_id).await,
+                }
+
+                warp_sync.confirm().await;
+                warp_sync.fill().await;
+            }
+
+            info!("Stopped.");
+        });
+
+        Ok(Self::default())
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        node.bus().remove_listeners_by_id(TypeId::of::<Self>());
+
+        Ok(())
+    }
+}
+
+/// The solid milestone index and the instant a warp sync started at.
+struct Session {
+    from: MilestoneIndex,
+    started: Instant,
+}
+
+struct WarpSync<B: StorageBackend> {
+    config: WarpSyncConfig,
+    tangle: ResourceHandle<MsTangle<B>>,
+    peer_manager: ResourceHandle<PeerManager>,
+    metrics: ResourceHandle<NodeMetrics>,
+    bus: ResourceHandle<Bus<'static>>,
+    consensus: mpsc::UnboundedSender<ConsensusWorkerCommand>,
+    solid_index: MilestoneIndex,
+    window: Window,
+    session: Option<Session>,
+    next_peer: usize,
+}
+
+impl<B: StorageBackend> WarpSync<B> {
+    fn in_window(&self, index: MilestoneIndex) -> bool {
+        index > self.solid_index && *index <= *self.solid_index + self.config.window_size()
+    }
+
+    /// Catches up with a solid milestone index that was moved forward by someone else, e.g. by a snapshot import.
+    fn sync_solid_index(&mut self) {
+        let solid_index = self.tangle.get_solid_milestone_index();
+
+        if solid_index > self.solid_index {
+            debug!("Solid milestone moved from {} to {}.", *self.solid_index, *solid_index);
+            self.solid_index = solid_index;
+            self.window.prune(solid_index);
+        }
+    }
+
+    /// Requests the milestones of the window that are neither known nor requested yet.
+    async fn fill(&mut self) {
+        let latest_index = self.tangle.get_latest_milestone_index();
+        let end = (*self.solid_index + self.config.window_size()).min(*latest_index);
+
+        self.report(latest_index, false);
+
+        for index in (*self.solid_index + 1..=end).map(MilestoneIndex) {
+            if self.window.contains(index) {
+                continue;
+            }
+
+            match self.tangle.get_milestone_message_id(index).await {
+                Some(message_id) => self.solidify(index, message_id).await,
+                None => {
+                    self.request_milestone(index).await;
+                    self.window.requested(index, Instant::now());
+                }
+            }
+        }
+    }
+
+    async fn on_milestone(&mut self, index: MilestoneIndex, message_id: MessageId) {
+        if !self.in_window(index) || self.window.is_solidifying(index) {
+            return;
+        }
+
+        self.solidify(index, message_id).await;
+    }
+
+    async fn on_message(&mut self, message_id: MessageId) {
+        if !self.window.is_missing(&message_id) {
+            return;
+        }
+
+        let missing_parents = self.missing_past_cone(message_id).await;
+
+        for (parent, index) in self.window.arrived(message_id, &missing_parents, Instant::now()) {
+            self.request_message(parent, index).await;
+        }
+    }
+
+    /// Starts requesting the past cone of a milestone.
+    async fn solidify(&mut self, index: MilestoneIndex, message_id: MessageId) {
+        let missing = self.missing_past_cone(message_id).await;
+
+        trace!("Milestone {} misses {} messages.", *index, missing.len());
+
+        for message_id in self.window.solidifying(index, message_id, &missing, Instant::now()) {
+            self.request_message(message_id, index).await;
+        }
+    }
+
+    /// Marks the milestones following the solid one as solid and confirms them, as long as their past cones are
+    /// complete.
+    async fn confirm(&mut self) {
+        let solid_index = self.solid_index;
+
+        while let Some((index, message_id)) = self.window.pop_complete(self.solid_index) {
+            for message_id in self.past_cone(message_id).await {
+                self.tangle
+                    .update_metadata(&message_id, |metadata| metadata.flags_mut().set_solid(true))
+                    .await;
+            }
+
+            debug!("Milestone {} is solid.", *index);
+            self.solid_index = index;
+            self.tangle.update_solid_milestone_index(index);
+
+            if let Some(milestone) = self.tangle.get_milestone(index).await {
+                self.bus.dispatch(SolidMilestoneChanged { index, milestone });
+            }
+
+            // The consensus worker processes its commands in order, hence the milestones get confirmed in order.
+            if self
+                .consensus
+                .send(ConsensusWorkerCommand::ConfirmMilestone(message_id))
+                .is_err()
+            {
+                warn!("Consensus worker is gone, milestone {} won't be confirmed.", *index);
+            }
+        }
+
+        if self.solid_index > solid_index {
+            self.report(self.tangle.get_latest_milestone_index(), true);
+        }
+    }
+
+    /// Requests again the milestones and messages that didn't arrive in time.
+    async fn retry(&mut self) {
+        let (milestones, messages) = self.window.due(self.config.retry_interval(), Instant::now());
+
+        if !milestones.is_empty() || !messages.is_empty() {
+            debug!(
+                "Requesting {} milestones and {} messages again.",
+                milestones.len(),
+                messages.len()
+            );
+        }
+
+        for index in milestones {
+            self.request_milestone(index).await;
+        }
+        for (message_id, index) in messages {
+            self.request_message(message_id, index).await;
+        }
+    }
+
+    /// Dispatches the warp sync events, depending on how far behind the solid milestone is, and whether it just
+    /// advanced.
+    fn report(&mut self, latest_index: MilestoneIndex, advanced: bool) {
+        match &self.session {
+            None if *latest_index >= *self.solid_index + self.config.threshold() => {
+                info!("Warp syncing from {} to {}.", *self.solid_index, *latest_index);
+                self.session = Some(Session {
+                    from: self.solid_index,
+                    started: Instant::now(),
+                });
+                self.bus.dispatch(WarpSyncStarted {
+                    from: self.solid_index,
+                    to: latest_index,
+                });
+            }
+            Some(session) if self.solid_index >= latest_index => {
+                let duration = session.started.elapsed();
+
+                info!(
+                    "Warp synced from {} to {} in {:.2?}.",
+                    *session.from, *self.solid_index, duration
+                );
+                self.bus.dispatch(WarpSyncCompleted {
+                    from: session.from,
+                    to: self.solid_index,
+                    duration,
+                });
+                self.session = None;
+            }
+            Some(session) if advanced => {
+                let eta = eta(
+                    session.started.elapsed(),
+                    *self.solid_index - *session.from,
+                    *latest_index - *self.solid_index,
+                );
+
+                self.bus.dispatch(WarpSyncProgress {
+                    index: self.solid_index,
+                    target: latest_index,
+                    eta,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the messages of the past cone of a message that aren't solid yet.
+    async fn past_cone(&self, root: MessageId) -> Vec<MessageId> {
+        let tangle = &*self.tangle;
+        let mut cone = Vec::new();
+
+        visit_parents_depth_first(
+            &**tangle,
+            root,
+            |message_id, _, metadata| async move {
+                !metadata.flags().is_solid() && !tangle.is_solid_entry_point(&message_id).await
+            },
+            |message_id, _, _| cone.push(*message_id),
+            |_, _, _| {},
+            |_| {},
+        )
+        .await;
+
+        cone
+    }
+
+    /// Returns the messages of the past cone of a message that are not in the tangle.
+    async fn missing_past_cone(&self, root: MessageId) -> Vec<MessageId> {
+        let tangle = &*self.tangle;
+        let mut missing = Vec::new();
+
+        visit_parents_depth_first(
+            &**tangle,
+            root,
+            |message_id, _, metadata| async move {
+                !metadata.flags().is_solid() && !tangle.is_solid_entry_point(&message_id).await
+            },
+            |_, _, _| {},
+            |_, _, _| {},
+            |message_id| missing.push(*message_id),
+        )
+        .await;
+
+        // Solid entry points are usually not in the tangle, but don't need to be requested.
+        let mut result = Vec::with_capacity(missing.len());
+
+        for message_id in missing {
+            if !tangle.is_solid_entry_point(&message_id).await {
+                result.push(message_id);
+            }
+        }
+
+        result
+    }
+
+    async fn request_milestone(&mut self, index: MilestoneIndex) {
+        if let Some(peer) = self.request(index, &MilestoneRequestPacket::new(*index)).await {
+            trace!("Requested milestone {} from {}.", *index, peer.alias());
+            self.metrics.milestone_requests_sent_inc();
+            peer.metrics().milestone_requests_sent_inc();
+        }
+    }
+
+    async fn request_message(&mut self, message_id: MessageId, index: MilestoneIndex) {
+        if let Some(peer) = self.request(index, &MessageRequestPacket::new(message_id)).await {
+            trace!("Requested message {} from {}.", message_id, peer.alias());
+            self.metrics.message_requests_sent_inc();
+            peer.metrics().message_requests_sent_inc();
+        }
+    }
+
+    /// Sends a request to one of the peers having the data of a milestone, in turn.
+    async fn request<P: Packet>(&mut self, index: MilestoneIndex, request: &P) -> Option<Arc<Peer>> {
+        let peers = self.peer_manager.connected().await;
+
+        let (peer, sender) = match select(&peers, index, &mut self.next_peer) {
+            Some(candidate) => candidate,
+            None => {
+                trace!("No peer has the data of milestone {}.", *index);
+                return None;
+            }
+        };
+
+        match sender.try_send(tlv_to_bytes(request)) {
+            Ok(()) => Some(peer.clone()),
+            Err(e) => {
+                debug!("Sending request to {} failed: {}.", peer.alias(), e);
+                None
+            }
+        }
+    }
+}
+
+/// Picks one of the peers having the data of a milestone according to their last heartbeat, in turn.
+fn select<'a, T>(
+    peers: &'a [(Arc<Peer>, T)],
+    index: MilestoneIndex,
+    next_peer: &mut usize,
+) -> Option<&'a (Arc<Peer>, T)> {
+    let candidates = peers
+        .iter()
+        .filter(|(peer, _)| peer.has_data(index))
+        .collect::<Vec<_>>();
+
+    if candidates.is_empty() {
+        return None;
+    }
+
+    *next_peer = next_peer.wrapping_add(1);
+
+    Some(candidates[*next_peer % candidates.len()])
+}
+
+/// Extrapolates the time needed for the `remaining` milestones from the time it took for the `done` ones.
+fn eta(elapsed: Duration, done: u32, remaining: u32) -> Option<Duration> {
+    if done == 0 {
+        return None;
+    }
+
+    Some(elapsed.mul_f64(remaining as f64 / done as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bee_network::{PeerId, PeerInfo, PeerRelation};
+
+    fn message_id(byte: u8) -> MessageId {
+        MessageId::new([byte; 32])
+    }
+
+    fn peer(pruned_index: u32, solid_index: u32) -> Arc<Peer> {
+        let peer = Peer::new(
+            PeerId::random(),
+            PeerInfo::new(
+                "/ip4/127.0.0.1/tcp/15600".parse().unwrap(),
+                String::from("peer"),
+                PeerRelation::Known,
+            ),
+        );
+
+        peer.set_pruned_index(MilestoneIndex(pruned_index));
+        peer.set_solid_milestone_index(MilestoneIndex(solid_index));
+
+        Arc::new(peer)
+    }
+
+    #[test]
+    fn milestones_complete_in_order() {
+        let mut window = Window::default();
+        let now = Instant::now();
+
+        for i in 1..=3 {
+            assert_eq!(
+                window.solidifying(MilestoneIndex(i), message_id(i as u8), &[message_id(10 + i as u8)], now),
+                vec![message_id(10 + i as u8)]
+            );
+        }
+
+        // The cones of the later milestones complete first, but they have to wait for the first one.
+        assert!(window.arrived(message_id(13), &[], now).is_empty());
+        assert!(window.arrived(message_id(12), &[], now).is_empty());
+        assert_eq!(window.pop_complete(MilestoneIndex(0)), None);
+
+        // A message that arrives with a missing parent of its own leaves the milestone incomplete.
+        assert_eq!(
+            window.arrived(message_id(11), &[message_id(20)], now),
+            vec![(message_id(20), MilestoneIndex(1))]
+        );
+        assert_eq!(window.pop_complete(MilestoneIndex(0)), None);
+
+        window.arrived(message_id(20), &[], now);
+
+        assert_eq!(
+            window.pop_complete(MilestoneIndex(0)),
+            Some((MilestoneIndex(1), message_id(1)))
+        );
+        assert_eq!(
+            window.pop_complete(MilestoneIndex(1)),
+            Some((MilestoneIndex(2), message_id(2)))
+        );
+        assert_eq!(
+            window.pop_complete(MilestoneIndex(2)),
+            Some((MilestoneIndex(3), message_id(3)))
+        );
+        assert_eq!(window.pop_complete(MilestoneIndex(3)), None);
+    }
+
+    #[test]
+    fn shared_messages_are_requested_once() {
+        let mut window = Window::default();
+        let now = Instant::now();
+
+        assert_eq!(
+            window.solidifying(MilestoneIndex(1), message_id(1), &[message_id(10)], now),
+            vec![message_id(10)]
+        );
+        assert!(window
+            .solidifying(MilestoneIndex(2), message_id(2), &[message_id(10)], now)
+            .is_empty());
+
+        window.arrived(message_id(10), &[], now);
+
+        assert!(window.pop_complete(MilestoneIndex(0)).is_some());
+        assert!(window.pop_complete(MilestoneIndex(1)).is_some());
+    }
+
+    #[test]
+    fn requests_are_retried() {
+        let mut window = Window::default();
+        let retry_interval = Duration::from_secs(5);
+        let now = Instant::now();
+
+        window.requested(MilestoneIndex(1), now);
+        window.solidifying(MilestoneIndex(2), message_id(2), &[message_id(10), message_id(11)], now);
+        window.arrived(message_id(11), &[], now);
+
+        let (milestones, messages) = window.due(retry_interval, now + Duration::from_secs(1));
+        assert!(milestones.is_empty() && messages.is_empty());
+
+        let later = now + retry_interval;
+        let (milestones, messages) = window.due(retry_interval, later);
+        assert_eq!(milestones, vec![MilestoneIndex(1)]);
+        assert_eq!(messages, vec![(message_id(10), MilestoneIndex(2))]);
+
+        // Requests are only retried once they are due again.
+        let (milestones, messages) = window.due(retry_interval, later + Duration::from_secs(1));
+        assert!(milestones.is_empty() && messages.is_empty());
+    }
+
+    #[test]
+    fn pruned_milestones_are_forgotten() {
+        let mut window = Window::default();
+        let now = Instant::now();
+
+        window.solidifying(MilestoneIndex(1), message_id(1), &[message_id(10)], now);
+        window.solidifying(MilestoneIndex(2), message_id(2), &[message_id(10), message_id(11)], now);
+        window.solidifying(MilestoneIndex(3), message_id(3), &[message_id(12)], now);
+
+        window.prune(MilestoneIndex(2));
+
+        assert!(!window.contains(MilestoneIndex(1)) && !window.contains(MilestoneIndex(2)));
+        assert!(window.contains(MilestoneIndex(3)));
+        assert!(!window.is_missing(&message_id(10)) && !window.is_missing(&message_id(11)));
+        assert!(window.is_missing(&message_id(12)));
+    }
+
+    #[test]
+    fn selected_peers_have_the_data() {
+        // The first peer pruned the milestone, the second isn't synced up to it.
+        let peers = vec![
+            (peer(10, 100), 0),
+            (peer(0, 4), 1),
+            (peer(0, 100), 2),
+            (peer(5, 100), 3),
+        ];
+        let mut next_peer = 0;
+
+        let selected = (0..4)
+            .map(|_| select(&peers, MilestoneIndex(8), &mut next_peer).unwrap().1)
+            .collect::<Vec<_>>();
+
+        assert_eq!(selected, vec![3, 2, 3, 2]);
+        assert!(select(&peers, MilestoneIndex(200), &mut next_peer).is_none());
+    }
+
+    #[test]
+    fn eta_extrapolates_pace() {
+        assert_eq!(eta(Duration::from_secs(10), 0, 100), None);
+        assert_eq!(eta(Duration::from_secs(10), 5, 0), Some(Duration::from_secs(0)));
+        assert_eq!(eta(Duration::from_secs(10), 5, 20), Some(Duration::from_secs(40)));
+    }
+}
@@ -4,11 +4,13 @@
 use serde::Deserialize;
 
 const DEFAULT_BELOW_MAX_DEPTH: u32 = 15;
+pub(crate) const DEFAULT_MAX_CACHE_SIZE: usize = 512 * 1024 * 1024;
 
 /// A builder type for a tangle configuration.
 #[derive(Default, Deserialize)]
 pub struct TangleConfigBuilder {
     below_max_depth: Option<u32>,
+    max_cache_size: Option<usize>,
 }
 
 impl TangleConfigBuilder {
@@ -21,6 +23,7 @@ impl TangleConfigBuilder {
     pub fn finish(self) -> TangleConfig {
         TangleConfig {
             below_max_depth: self.below_max_depth.unwrap_or(DEFAULT_BELOW_MAX_DEPTH),
+            max_cache_size: self.max_cache_size.unwrap_or(DEFAULT_MAX_CACHE_SIZE),
         }
     }
 }
@@ -29,6 +32,7 @@ impl TangleConfigBuilder {
 #[derive(Clone)]
 pub struct TangleConfig {
     below_max_depth: u32,
+    max_cache_size: usize,
 }
 
 impl TangleConfig {
@@ -41,4 +45,9 @@ impl TangleConfig {
     pub fn below_max_depth(&self) -> u32 {
         self.below_max_depth
     }
+
+    /// Get the value of `max_cache_size`, in bytes.
+    pub fn max_cache_size(&self) -> usize {
+        self.max_cache_size
+    }
 }
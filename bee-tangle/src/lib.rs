@@ -13,6 +13,8 @@ pub mod event;
 pub mod flags;
 /// Message data, including message flags.
 pub mod metadata;
+/// Types used to track tangle cache metrics.
+pub mod metrics;
 /// Milestone-enabled tangle type.
 pub mod ms_tangle;
 /// Types used to represent SEPs (Solid Entry Points).
@@ -0,0 +1,62 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Holds metrics related to the tangle cache.
+#[derive(Default, Debug)]
+pub struct CacheMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+    storage_fallbacks: AtomicU64,
+}
+
+impl CacheMetrics {
+    /// Creates a new `CacheMetrics`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CacheMetrics {
+    /// Returns the number of cache hits of the `CacheMetrics`.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Increments the number of cache hits of the `CacheMetrics`.
+    pub fn hits_inc(&self) -> u64 {
+        self.hits.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Returns the number of cache misses of the `CacheMetrics`.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Increments the number of cache misses of the `CacheMetrics`.
+    pub fn misses_inc(&self) -> u64 {
+        self.misses.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Returns the number of evictions of the `CacheMetrics`.
+    pub fn evictions(&self) -> u64 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    /// Increments the number of evictions of the `CacheMetrics`.
+    pub fn evictions_inc(&self) -> u64 {
+        self.evictions.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Returns the number of cache misses that were served by the storage of the `CacheMetrics`.
+    pub fn storage_fallbacks(&self) -> u64 {
+        self.storage_fallbacks.load(Ordering::Relaxed)
+    }
+
+    /// Increments the number of cache misses that were served by the storage of the `CacheMetrics`.
+    pub fn storage_fallbacks_inc(&self) -> u64 {
+        self.storage_fallbacks.fetch_add(1, Ordering::SeqCst)
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     solid_entry_point::SolidEntryPoint,
     storage::StorageBackend,
     tangle::{Hooks, Tangle, DEFAULT_CACHE_LEN},
+    unreferenced_message::UnreferencedMessage,
     urts::UrtsTipPool,
     MessageRef,
 };
@@ -17,7 +18,7 @@ use bee_message::{
 };
 use bee_runtime::resource::ResourceHandle;
 
-use hashbrown::HashMap;
+use hashbrown::{hash_map::Entry, HashMap};
 use log::{info, trace};
 use ref_cast::RefCast;
 use tokio::sync::Mutex;
@@ -83,6 +84,11 @@ impl<B: StorageBackend> StorageHooks<B> {
         self.storage.insert(&idx, milestone)?;
         Ok(())
     }
+
+    fn insert_unreferenced_message(&self, idx: MilestoneIndex, id: MessageId) -> Result<(), B::Error> {
+        trace!("Attempted to insert unreferenced message {:?} at {:?}", id, idx);
+        self.storage.insert(&(idx, UnreferencedMessage::new(id)), &())
+    }
 }
 
 /// A Tangle wrapper designed to encapsulate milestone state.
@@ -91,6 +97,7 @@ pub struct MsTangle<B> {
     inner: Tangle<MessageMetadata, StorageHooks<B>>,
     milestones: Mutex<HashMap<MilestoneIndex, Milestone>>,
     solid_entry_points: Mutex<HashMap<SolidEntryPoint, MilestoneIndex>>,
+    unreferenced_messages: Mutex<HashMap<MessageId, MilestoneIndex>>,
     latest_milestone_index: AtomicU32,
     solid_milestone_index: AtomicU32,
     confirmed_milestone_index: AtomicU32,
@@ -111,10 +118,14 @@ impl<B> Deref for MsTangle<B> {
 impl<B: StorageBackend> MsTangle<B> {
     /// Create a new `MsTangle` instance with the given configuration and storage handle.
     pub fn new(config: TangleConfig, storage: ResourceHandle<B>) -> Self {
+        let inner = Tangle::new(StorageHooks { storage });
+        inner.resize_bytes(config.max_cache_size());
+
         Self {
-            inner: Tangle::new(StorageHooks { storage }),
+            inner,
             milestones: Default::default(),
             solid_entry_points: Default::default(),
+            unreferenced_messages: Default::default(),
             latest_milestone_index: Default::default(),
             solid_milestone_index: Default::default(),
             confirmed_milestone_index: Default::default(),
@@ -137,8 +148,28 @@ impl<B: StorageBackend> MsTangle<B> {
     }
 
     /// Insert a message into the tangle.
+    /// A new message that is not referenced yet is recorded as unreferenced at the latest milestone index.
     pub async fn insert(&self, message: Message, hash: MessageId, metadata: MessageMetadata) -> Option<MessageRef> {
-        self.inner.insert(hash, message, metadata).await
+        let referenced = metadata.flags().is_referenced();
+        let msg = self.inner.insert(hash, message, metadata).await;
+
+        if msg.is_some() && !referenced {
+            let index = self.get_latest_milestone_index();
+
+            self.inner
+                .hooks()
+                .insert_unreferenced_message(index, hash)
+                .unwrap_or_else(|e| info!("Failed to insert unreferenced message {:?}", e));
+            self.unreferenced_messages.lock().await.insert(hash, index);
+        }
+
+        msg
+    }
+
+    /// Stops tracking the given message as unreferenced and returns the milestone index it was recorded at, if known.
+    /// Messages recorded before the node was restarted are not known anymore and are handled by pruning.
+    pub async fn remove_unreferenced_message(&self, id: &MessageId) -> Option<MilestoneIndex> {
+        self.unreferenced_messages.lock().await.remove(id)
     }
 
     /// Add a milestone to the tangle.
@@ -156,12 +187,19 @@ impl<B: StorageBackend> MsTangle<B> {
             .hooks()
             .insert_milestone(idx, &milestone)
             .unwrap_or_else(|e| info!("Failed to insert message {:?}", e));
-        self.milestones.lock().await.insert(idx, milestone);
+        let message_id = *milestone.message_id();
+        // Milestones are kept in the cache for as long as they are known to the tangle.
+        if let Some(old) = self.milestones.lock().await.insert(idx, milestone) {
+            self.inner.unprotect(old.message_id()).await;
+        }
+        self.inner.protect(message_id).await;
     }
 
     /// Remove a milestone from the tangle.
     pub async fn remove_milestone(&self, index: MilestoneIndex) {
-        self.milestones.lock().await.remove(&index);
+        if let Some(milestone) = self.milestones.lock().await.remove(&index) {
+            self.inner.unprotect(milestone.message_id()).await;
+        }
     }
 
     async fn pull_milestone(&self, idx: MilestoneIndex) -> Option<MessageId> {
@@ -169,13 +207,14 @@ impl<B: StorageBackend> MsTangle<B> {
             info!("Failed to insert message {:?}", e);
             None
         }) {
-            let message_id = *self
-                .milestones
-                .lock()
-                .await
-                .entry(idx)
-                .or_insert(milestone)
-                .message_id();
+            let message_id = match self.milestones.lock().await.entry(idx) {
+                Entry::Occupied(entry) => *entry.get().message_id(),
+                Entry::Vacant(entry) => {
+                    let message_id = *entry.insert(milestone).message_id();
+                    self.inner.protect(message_id).await;
+                    message_id
+                }
+            };
 
             Some(message_id)
         } else {
@@ -322,7 +361,11 @@ impl<B: StorageBackend> MsTangle<B> {
 
     /// Add the given solid entry point to the given milestone index.
     pub async fn add_solid_entry_point(&self, sep: SolidEntryPoint, index: MilestoneIndex) {
-        self.solid_entry_points.lock().await.insert(sep, index);
+        let message_id = *sep;
+
+        if self.solid_entry_points.lock().await.insert(sep, index).is_none() {
+            self.inner.protect(message_id).await;
+        }
     }
 
     /// Returns a copy of all solid entry points.
@@ -332,12 +375,18 @@ impl<B: StorageBackend> MsTangle<B> {
 
     /// Removes the given solid entry point from the set of solid entry points.
     pub async fn remove_solid_entry_point(&self, sep: &SolidEntryPoint) {
-        self.solid_entry_points.lock().await.remove(sep);
+        if self.solid_entry_points.lock().await.remove(sep).is_some() {
+            self.inner.unprotect(sep).await;
+        }
     }
 
     /// Clear all solid entry points.
     pub async fn clear_solid_entry_points(&self) {
-        self.solid_entry_points.lock().await.clear();
+        let seps = self.solid_entry_points.lock().await.drain().collect::<Vec<_>>();
+
+        for (sep, _) in seps {
+            self.inner.unprotect(&sep).await;
+        }
     }
 
     /// Replaces all solid entry points.
@@ -346,8 +395,17 @@ impl<B: StorageBackend> MsTangle<B> {
         new_seps: impl IntoIterator<Item = (SolidEntryPoint, MilestoneIndex)>,
     ) {
         let mut seps = self.solid_entry_points.lock().await;
-        seps.clear();
+        let old_seps = seps.drain().map(|(sep, _)| sep).collect::<Vec<_>>();
         seps.extend(new_seps);
+        let new_seps = seps.keys().map(|sep| **sep).collect::<Vec<_>>();
+        drop(seps);
+
+        for sep in old_seps {
+            self.inner.unprotect(&sep).await;
+        }
+        for sep in new_seps {
+            self.inner.protect(sep).await;
+        }
     }
 
     /// Returns whether the message associated with given solid entry point is a solid entry point.
@@ -395,12 +453,29 @@ impl<B: StorageBackend> MsTangle<B> {
 
     /// Insert the given message ID and parents as a tip.
     pub async fn insert_tip(&self, message_id: MessageId, parents: Vec<MessageId>) {
-        self.tip_pool.lock().await.insert(&self, message_id, parents).await;
+        let mut tip_pool = self.tip_pool.lock().await;
+        let was_tip = tip_pool.non_lazy_tips().contains(&message_id);
+        let removed = tip_pool.insert(&self, message_id, parents).await;
+        let is_tip = tip_pool.non_lazy_tips().contains(&message_id);
+        drop(tip_pool);
+
+        // Non-lazy tips are kept in the cache since they are about to be approved.
+        if !was_tip && is_tip {
+            self.inner.protect(message_id).await;
+        }
+        self.unprotect_tips(removed).await;
     }
 
     /// Update tip scores.
     pub async fn update_tip_scores(&self) {
-        self.tip_pool.lock().await.update_scores(&self).await;
+        let removed = self.tip_pool.lock().await.update_scores(&self).await;
+        self.unprotect_tips(removed).await;
+    }
+
+    async fn unprotect_tips(&self, tips: Vec<MessageId>) {
+        for tip in tips {
+            self.inner.unprotect(&tip).await;
+        }
     }
 
     /// Return messages that require approving.
@@ -410,7 +485,8 @@ impl<B: StorageBackend> MsTangle<B> {
 
     /// Reduce tips.
     pub async fn reduce_tips(&self) {
-        self.tip_pool.lock().await.reduce_tips();
+        let removed = self.tip_pool.lock().await.reduce_tips();
+        self.unprotect_tips(removed).await;
     }
 
     /// Return the number of non-lazy tips.
@@ -1,7 +1,7 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{metadata::MessageMetadata, solid_entry_point::SolidEntryPoint};
+use crate::{metadata::MessageMetadata, solid_entry_point::SolidEntryPoint, unreferenced_message::UnreferencedMessage};
 
 use bee_message::{
     milestone::{Milestone, MilestoneIndex},
@@ -21,6 +21,7 @@ pub trait StorageBackend:
     + Insert<(MessageId, MessageId), ()>
     + Insert<MilestoneIndex, Milestone>
     + Insert<SolidEntryPoint, MilestoneIndex>
+    + Insert<(MilestoneIndex, UnreferencedMessage), ()>
     + Fetch<MessageId, Message>
     + Fetch<MessageId, MessageMetadata>
     + Fetch<MessageId, Vec<MessageId>>
@@ -35,6 +36,7 @@ impl<T> StorageBackend for T where
         + Insert<(MessageId, MessageId), ()>
         + Insert<MilestoneIndex, Milestone>
         + Insert<SolidEntryPoint, MilestoneIndex>
+        + Insert<(MilestoneIndex, UnreferencedMessage), ()>
         + Fetch<MessageId, Message>
         + Fetch<MessageId, MessageMetadata>
         + Fetch<MessageId, Vec<MessageId>>
@@ -1,11 +1,14 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{vertex::Vertex, MessageRef};
+use crate::{config::DEFAULT_MAX_CACHE_SIZE, metrics::CacheMetrics, vertex::Vertex, MessageRef};
 
 use bee_message::{Message, MessageId};
 
-use hashbrown::{hash_map::DefaultHashBuilder, HashMap};
+use hashbrown::{
+    hash_map::{DefaultHashBuilder, Entry},
+    HashMap,
+};
 use log::info;
 use lru::LruCache;
 use tokio::sync::{Mutex, RwLock as TRwLock, RwLockWriteGuard as TRwLockWriteGuard};
@@ -81,6 +84,11 @@ where
 
     cache_queue: Mutex<LruCache<MessageId, (), DefaultHashBuilder>>,
     max_len: AtomicUsize,
+    size: AtomicUsize,
+    max_size: AtomicUsize,
+    // Messages that must stay in the cache, with the number of times they were protected.
+    protected: Mutex<HashMap<MessageId, usize>>,
+    metrics: CacheMetrics,
 
     hooks: H,
 }
@@ -107,6 +115,10 @@ where
 
             cache_queue: Mutex::new(LruCache::unbounded_with_hasher(DefaultHashBuilder::default())),
             max_len: AtomicUsize::new(DEFAULT_CACHE_LEN),
+            size: AtomicUsize::new(0),
+            max_size: AtomicUsize::new(DEFAULT_MAX_CACHE_SIZE),
+            protected: Mutex::new(HashMap::new()),
+            metrics: CacheMetrics::new(),
 
             hooks,
         }
@@ -125,11 +137,74 @@ where
         self.max_len.store(len, Ordering::Relaxed);
     }
 
+    /// Change the maximum approximate number of bytes the cache may occupy.
+    pub fn resize_bytes(&self, size: usize) {
+        self.max_size.store(size, Ordering::Relaxed);
+    }
+
+    /// Returns the approximate number of bytes currently occupied by the cache.
+    pub fn size(&self) -> usize {
+        self.size.load(Ordering::Relaxed)
+    }
+
+    /// Return a reference to the metrics of the cache.
+    pub fn cache_metrics(&self) -> &CacheMetrics {
+        &self.metrics
+    }
+
+    /// Prevents the message from being evicted from the cache until it is unprotected as many times as it was
+    /// protected.
+    pub async fn protect(&self, message_id: MessageId) {
+        *self.protected.lock().await.entry(message_id).or_insert(0) += 1;
+    }
+
+    /// Revokes a previous protection of the message against cache eviction.
+    pub async fn unprotect(&self, message_id: &MessageId) {
+        let mut protected = self.protected.lock().await;
+
+        if let Some(count) = protected.get_mut(message_id) {
+            *count -= 1;
+            if *count == 0 {
+                protected.remove(message_id);
+            }
+        }
+    }
+
+    /// Returns whether the message is protected against cache eviction.
+    pub async fn is_protected(&self, message_id: &MessageId) -> bool {
+        self.protected.lock().await.contains_key(message_id)
+    }
+
     /// Return a reference to the storage hooks used by this tangle.
     pub fn hooks(&self) -> &H {
         &self.hooks
     }
 
+    // Applies `f` to the vertex of `message_id`, creating it if needed, and accounts for its change in size.
+    fn update_vertex<R>(
+        &self,
+        vertices: &mut HashMap<MessageId, Vertex<T>>,
+        message_id: MessageId,
+        f: impl FnOnce(&mut Vertex<T>) -> R,
+    ) -> R {
+        let (vertex, before) = match vertices.entry(message_id) {
+            Entry::Occupied(entry) => {
+                let vertex = entry.into_mut();
+                let size = vertex.size();
+                (vertex, size)
+            }
+            Entry::Vacant(entry) => (entry.insert(Vertex::empty()), 0),
+        };
+
+        let r = f(vertex);
+
+        // Vertices only ever grow while they are in the cache.
+        self.size
+            .fetch_add(vertex.size().saturating_sub(before), Ordering::Relaxed);
+
+        r
+    }
+
     async fn insert_inner(
         &self,
         message_id: MessageId,
@@ -138,26 +213,27 @@ where
         prevent_eviction: bool,
     ) -> Option<MessageRef> {
         let mut vertices = self.vertices.write().await;
-        let vertex = vertices.entry(message_id).or_insert_with(Vertex::empty);
-
-        if prevent_eviction {
-            vertex.prevent_eviction();
-        }
+        let parents = message.parents().clone();
 
-        let msg = if vertex.message().is_some() {
-            None
-        } else {
-            let parents = message.parents().clone();
+        let msg = self.update_vertex(&mut vertices, message_id, |vertex| {
+            if prevent_eviction {
+                vertex.prevent_eviction();
+            }
 
-            vertex.insert_message_and_metadata(message, metadata);
-            let msg = vertex.message().cloned();
+            if vertex.message().is_some() {
+                None
+            } else {
+                vertex.insert_message_and_metadata(message, metadata);
+                vertex.message().cloned()
+            }
+        });
 
+        if msg.is_some() {
             let mut cache_queue = self.cache_queue.lock().await;
 
             // Insert children for parents
             for &parent in parents.iter() {
-                let children = vertices.entry(parent).or_insert_with(Vertex::empty);
-                children.add_child(message_id);
+                self.update_vertex(&mut vertices, parent, |children| children.add_child(message_id));
 
                 // Insert cache queue entry to track eviction priority
                 cache_queue.put(parent, ());
@@ -165,9 +241,7 @@ where
 
             // Insert cache queue entry to track eviction priority
             cache_queue.put(message_id, ());
-
-            msg
-        };
+        }
 
         drop(vertices);
 
@@ -310,6 +384,18 @@ where
         }
     }
 
+    /// Removes a message from the cache, unless it is currently in use. The storage is left untouched.
+    pub async fn remove_from_cache(&self, message_id: &MessageId) {
+        let mut vertices = self.vertices.write().await;
+
+        if vertices.get(message_id).map_or(false, |v| v.can_evict()) {
+            if let Some(v) = vertices.remove(message_id) {
+                self.size.fetch_sub(v.size(), Ordering::Relaxed);
+                self.cache_queue.lock().await.pop(message_id);
+            }
+        }
+    }
+
     /// Returns the number of messages in the Tangle.
     pub async fn len(&self) -> usize {
         // Does not take GTL because this is effectively atomic
@@ -363,17 +449,18 @@ where
                 };
 
                 let mut vertices = self.vertices.write().await;
-                let v = vertices.entry(*message_id).or_insert_with(Vertex::empty);
 
-                // We've just fetched approvers from the database, so we have all the information available to us now.
-                // Therefore, the approvers list is exhaustive (i.e: it contains all knowledge we have).
-                v.set_exhaustive();
+                self.update_vertex(&mut vertices, *message_id, |v| {
+                    // We've just fetched approvers from the database, so we have all the information available to us
+                    // now. Therefore, the approvers list is exhaustive (i.e: it contains all knowledge we have).
+                    v.set_exhaustive();
 
-                for child in to_insert {
-                    v.add_child(child);
-                }
+                    for child in to_insert {
+                        v.add_child(child);
+                    }
 
-                v.children().to_vec()
+                    v.children().to_vec()
+                })
             }
         };
 
@@ -400,6 +487,7 @@ where
     #[cfg(test)]
     pub async fn clear(&mut self) {
         self.vertices.write().await.clear();
+        self.size.store(0, Ordering::Relaxed);
     }
 
     // Attempts to pull the message from the storage, returns true if successful.
@@ -419,11 +507,19 @@ where
 
         // If the tangle already contains the message, do no more work
         if contains_now {
+            self.metrics.hits_inc();
+
             // Insert cache queue entry to track eviction priority
             self.cache_queue.lock().await.put(*message_id, ());
 
-            true
-        } else if let Ok(Some((msg, metadata))) = self.hooks.get(message_id) {
+            return true;
+        }
+
+        self.metrics.misses_inc();
+
+        if let Ok(Some((msg, metadata))) = self.hooks.get(message_id) {
+            self.metrics.storage_fallbacks_inc();
+
             // Insert cache queue entry to track eviction priority
             self.cache_queue.lock().await.put(*message_id, ());
 
@@ -437,25 +533,42 @@ where
 
     async fn perform_eviction(&self) {
         let max_len = self.max_len.load(Ordering::Relaxed);
+        let max_size = self.max_size.load(Ordering::Relaxed);
         let len = self.vertices.read().await.len();
-        if len > max_len {
+
+        if len > max_len || self.size() > max_size {
+            let target_len = ((1.0 - CACHE_THRESHOLD_FACTOR) * max_len as f64) as usize;
+            let target_size = ((1.0 - CACHE_THRESHOLD_FACTOR) * max_size as f64) as usize;
+
             let mut vertices = self.vertices.write().await;
             let mut cache_queue = self.cache_queue.lock().await;
-            while vertices.len() > ((1.0 - CACHE_THRESHOLD_FACTOR) * max_len as f64) as usize {
-                let remove = cache_queue.pop_lru().map(|(id, _)| id);
-
-                if let Some(message_id) = remove {
-                    if let Some(v) = vertices.remove(&message_id) {
-                        if !v.can_evict() {
-                            // Reinsert it if we're not permitted to evict it yet (because something is using it)
-                            vertices.insert(message_id, v);
-                            cache_queue.put(message_id, ());
-                        }
-                    }
-                } else {
-                    break;
+            let protected = self.protected.lock().await;
+
+            // Every entry is visited at most once so that a cache full of protected or in-use vertices can't make us
+            // loop forever.
+            let mut remaining = cache_queue.len();
+            let mut kept = Vec::new();
+
+            while (vertices.len() > target_len || self.size() > target_size) && remaining > 0 {
+                remaining -= 1;
+
+                let message_id = match cache_queue.pop_lru() {
+                    Some((message_id, _)) => message_id,
+                    None => break,
+                };
+
+                // Keep it if we're not permitted to evict it (because something is using or protecting it)
+                if protected.contains_key(&message_id) || vertices.get(&message_id).map_or(false, |v| !v.can_evict()) {
+                    kept.push(message_id);
+                } else if let Some(v) = vertices.remove(&message_id) {
+                    self.size.fetch_sub(v.size(), Ordering::Relaxed);
+                    self.metrics.evictions_inc();
                 }
             }
+
+            for message_id in kept {
+                cache_queue.put(message_id, ());
+            }
         }
     }
 }
@@ -64,20 +64,27 @@ impl UrtsTipPool {
         &self.non_lazy_tips
     }
 
+    /// Inserts a tip and returns the non-lazy tips that were removed in the process.
     pub(crate) async fn insert<B: StorageBackend>(
         &mut self,
         tangle: &MsTangle<B>,
         message_id: MessageId,
         parents: Vec<MessageId>,
-    ) {
+    ) -> Vec<MessageId> {
+        let mut removed = Vec::new();
+
         if let Score::NonLazy = self.tip_score::<B>(tangle, &message_id).await {
             self.non_lazy_tips.insert(message_id);
             self.tips.insert(message_id, TipMetadata::new());
             for parent in &parents {
                 self.add_child(*parent, message_id);
-                self.check_retention_rules_for_parent(parent);
+                if self.check_retention_rules_for_parent(parent) {
+                    removed.push(*parent);
+                }
             }
         }
+
+        removed
     }
 
     fn add_child(&mut self, parent: MessageId, child: MessageId) {
@@ -98,7 +105,8 @@ impl UrtsTipPool {
         }
     }
 
-    fn check_retention_rules_for_parent(&mut self, parent: &MessageId) {
+    // Returns whether the parent was removed from the non-lazy tips.
+    fn check_retention_rules_for_parent(&mut self, parent: &MessageId) -> bool {
         // For every tip we add to the pool we call `add_child()`. `add_child()` makes sure that the parents of the tip
         // are present in the pool. Since `check_retention_rules_for_parent()` will be called after `add_child()` we
         // can be sure that the parents do exist. Therefore, unwrapping the parents here is fine.
@@ -115,12 +123,16 @@ impl UrtsTipPool {
                 > MAX_AGE_SECONDS_AFTER_FIRST_CHILD as u64
         {
             self.tips.remove(parent);
-            self.non_lazy_tips.remove(parent);
+            self.non_lazy_tips.remove(parent)
+        } else {
+            false
         }
     }
 
-    pub(crate) async fn update_scores<B: StorageBackend>(&mut self, tangle: &MsTangle<B>) {
+    /// Updates the scores of the tips and returns the non-lazy tips that were removed in the process.
+    pub(crate) async fn update_scores<B: StorageBackend>(&mut self, tangle: &MsTangle<B>) -> Vec<MessageId> {
         let mut to_remove = Vec::new();
+        let mut removed = Vec::new();
 
         for tip in self.tips.keys() {
             match self.tip_score::<B>(tangle, &tip).await {
@@ -133,10 +145,14 @@ impl UrtsTipPool {
 
         for tip in to_remove {
             self.tips.remove(&tip);
-            self.non_lazy_tips.remove(&tip);
+            if self.non_lazy_tips.remove(&tip) {
+                removed.push(tip);
+            }
         }
 
         debug!("Non-lazy tips {}", self.non_lazy_tips.len());
+
+        removed
     }
 
     async fn tip_score<B: StorageBackend>(&self, tangle: &MsTangle<B>, message_id: &MessageId) -> Score {
@@ -184,14 +200,23 @@ impl UrtsTipPool {
         4
     }
 
-    pub(crate) fn reduce_tips(&mut self) {
+    /// Removes the tips that are too old and returns the non-lazy tips that were removed in the process.
+    pub(crate) fn reduce_tips(&mut self) -> Vec<MessageId> {
         let non_lazy_tips = &mut self.non_lazy_tips;
+        let mut removed = Vec::new();
+
         self.tips.retain(|tip, metadata| {
             metadata
                 .time_first_child
                 .filter(|age| age.elapsed().as_secs() > MAX_AGE_SECONDS_AFTER_FIRST_CHILD as u64)
-                .map(|_| non_lazy_tips.remove(&tip))
+                .map(|_| {
+                    if non_lazy_tips.remove(&tip) {
+                        removed.push(*tip);
+                    }
+                })
                 .is_none()
         });
+
+        removed
     }
 }
@@ -3,9 +3,13 @@
 
 use crate::{MessageRef, VecSet};
 
-use bee_message::{Message, MessageId};
+use bee_common::packable::Packable;
+use bee_message::{Message, MessageId, MESSAGE_ID_LENGTH};
 
-use std::sync::Arc;
+use std::{mem::size_of, sync::Arc};
+
+// Approximate fixed cost of a vertex once stored in the cache, i.e. its map entry, key and LRU queue entry.
+const VERTEX_OVERHEAD: usize = size_of::<MessageId>() * 2 + size_of::<usize>() * 8;
 
 #[derive(Clone)]
 pub struct Vertex<T>
@@ -13,6 +17,7 @@ where
     T: Clone,
 {
     message: Option<(MessageRef, T)>,
+    message_len: usize,
     children: (VecSet<MessageId>, bool), // Exhaustive flag
     eviction_blocks: isize,
 }
@@ -24,6 +29,7 @@ where
     pub fn empty() -> Self {
         Self {
             message: None,
+            message_len: 0,
             children: (VecSet::default(), false),
             eviction_blocks: 0,
         }
@@ -31,6 +37,7 @@ where
 
     pub fn new(message: Message, metadata: T) -> Self {
         Self {
+            message_len: message.packed_len(),
             message: Some((MessageRef(Arc::new(message)), metadata)),
             children: (VecSet::default(), false),
             eviction_blocks: 0,
@@ -75,6 +82,7 @@ where
     }
 
     pub(crate) fn insert_message_and_metadata(&mut self, msg: Message, meta: T) {
+        self.message_len = msg.packed_len();
         self.message = Some((MessageRef(Arc::new(msg)), meta));
     }
 
@@ -90,4 +98,15 @@ where
     pub(crate) fn can_evict(&self) -> bool {
         self.eviction_blocks == 0
     }
+
+    /// Returns the approximate number of bytes this vertex occupies in the cache.
+    pub(crate) fn size(&self) -> usize {
+        let message_size = if self.message.is_some() {
+            size_of::<Message>() + self.message_len + size_of::<T>()
+        } else {
+            0
+        };
+
+        VERTEX_OVERHEAD + message_size + self.children.0.len() * MESSAGE_ID_LENGTH
+    }
 }
@@ -0,0 +1,168 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_message::{
+    milestone::{Milestone, MilestoneIndex},
+    parents::Parents,
+    MessageId,
+};
+use bee_runtime::resource::ResourceHandle;
+use bee_storage::{access::Fetch, backend::StorageBackend};
+use bee_storage_sled::{config::SledConfigBuilder, storage::Storage};
+use bee_tangle::{
+    config::TangleConfig,
+    metadata::{IndexId, MessageMetadata},
+    solid_entry_point::SolidEntryPoint,
+    unreferenced_message::UnreferencedMessage,
+    MsTangle,
+};
+use bee_test::rand::message::{rand_message, rand_message_id, rand_message_with_parents};
+
+fn storage(name: &str) -> ResourceHandle<Storage> {
+    let path = String::from("./tests/database/") + name;
+    let _ = std::fs::remove_dir_all(&path);
+
+    let config = SledConfigBuilder::default()
+        .with_path(path)
+        .with_temporary(true)
+        .finish();
+
+    ResourceHandle::new(Storage::start(config).unwrap())
+}
+
+fn ms_tangle(storage: &ResourceHandle<Storage>) -> MsTangle<Storage> {
+    MsTangle::new(TangleConfig::build().finish(), storage.clone())
+}
+
+fn fetch_unreferenced_messages(storage: &Storage, index: MilestoneIndex) -> Vec<UnreferencedMessage> {
+    Fetch::<MilestoneIndex, Vec<UnreferencedMessage>>::fetch(storage, &index)
+        .unwrap()
+        .unwrap_or_default()
+}
+
+// Inserts a solid message that is a non-lazy tip candidate and returns its id.
+async fn insert_solid_message(tangle: &MsTangle<Storage>, parents: Vec<MessageId>) -> MessageId {
+    let message_id = rand_message_id();
+    let mut metadata = MessageMetadata::arrived();
+    metadata.mark_solid();
+    metadata.set_omrsi(IndexId::new(MilestoneIndex(0), message_id));
+    metadata.set_ymrsi(IndexId::new(MilestoneIndex(0), message_id));
+
+    tangle
+        .insert(
+            rand_message_with_parents(Parents::new(parents).unwrap()),
+            message_id,
+            metadata,
+        )
+        .await;
+
+    message_id
+}
+
+#[tokio::test]
+async fn milestones_are_protected() {
+    let tangle = ms_tangle(&storage("milestones_are_protected"));
+    let (first, second) = (rand_message_id(), rand_message_id());
+
+    tangle.add_milestone(MilestoneIndex(1), Milestone::new(first, 0)).await;
+    assert!(tangle.is_protected(&first).await);
+
+    // A milestone replacing another one at the same index releases it.
+    tangle.add_milestone(MilestoneIndex(1), Milestone::new(second, 0)).await;
+    assert!(!tangle.is_protected(&first).await);
+    assert!(tangle.is_protected(&second).await);
+
+    tangle.remove_milestone(MilestoneIndex(1)).await;
+    assert!(!tangle.is_protected(&second).await);
+}
+
+#[tokio::test]
+async fn solid_entry_points_are_protected() {
+    let tangle = ms_tangle(&storage("solid_entry_points_are_protected"));
+    let (first, second) = (rand_message_id(), rand_message_id());
+
+    tangle
+        .add_solid_entry_point(SolidEntryPoint::new(first), MilestoneIndex(1))
+        .await;
+    assert!(tangle.is_protected(&first).await);
+
+    tangle
+        .replace_solid_entry_points(vec![(SolidEntryPoint::new(second), MilestoneIndex(2))])
+        .await;
+    assert!(!tangle.is_protected(&first).await);
+    assert!(tangle.is_protected(&second).await);
+
+    tangle.clear_solid_entry_points().await;
+    assert!(!tangle.is_protected(&second).await);
+}
+
+#[tokio::test]
+async fn tips_are_protected() {
+    let tangle = ms_tangle(&storage("tips_are_protected"));
+
+    let tip = insert_solid_message(&tangle, vec![rand_message_id()]).await;
+    tangle.insert_tip(tip, Vec::new()).await;
+    assert!(tangle.is_protected(&tip).await);
+
+    // A tip is removed from the non-lazy tips, and released, once it has more than two children.
+    let mut children = Vec::new();
+    for _ in 0..3 {
+        let child = insert_solid_message(&tangle, vec![tip]).await;
+        tangle.insert_tip(child, vec![tip]).await;
+        children.push(child);
+    }
+
+    assert!(!tangle.is_protected(&tip).await);
+    for child in children {
+        assert!(tangle.is_protected(&child).await);
+    }
+}
+
+#[tokio::test]
+async fn unreferenced_messages_are_recorded_on_arrival() {
+    let storage = storage("unreferenced_messages_are_recorded_on_arrival");
+    let tangle = ms_tangle(&storage);
+    tangle.update_latest_milestone_index(MilestoneIndex(1));
+
+    let unreferenced = rand_message_id();
+    tangle
+        .insert(rand_message(), unreferenced, MessageMetadata::arrived())
+        .await;
+
+    // Messages that are already referenced when they arrive, e.g. during a synchronization, are not recorded.
+    let referenced = rand_message_id();
+    let mut metadata = MessageMetadata::arrived();
+    metadata.reference(0);
+    tangle.insert(rand_message(), referenced, metadata).await;
+
+    assert_eq!(
+        fetch_unreferenced_messages(&storage, MilestoneIndex(1)),
+        vec![UnreferencedMessage::new(unreferenced)]
+    );
+
+    // A message is only recorded once, when it is new to the tangle.
+    tangle.update_latest_milestone_index(MilestoneIndex(2));
+    tangle
+        .insert(rand_message(), unreferenced, MessageMetadata::arrived())
+        .await;
+
+    assert!(fetch_unreferenced_messages(&storage, MilestoneIndex(2)).is_empty());
+}
+
+#[tokio::test]
+async fn unreferenced_messages_are_removed_once() {
+    let storage = storage("unreferenced_messages_are_removed_once");
+    let tangle = ms_tangle(&storage);
+    tangle.update_latest_milestone_index(MilestoneIndex(3));
+
+    let message_id = rand_message_id();
+    tangle
+        .insert(rand_message(), message_id, MessageMetadata::arrived())
+        .await;
+
+    assert_eq!(
+        tangle.remove_unreferenced_message(&message_id).await,
+        Some(MilestoneIndex(3))
+    );
+    assert_eq!(tangle.remove_unreferenced_message(&message_id).await, None);
+}
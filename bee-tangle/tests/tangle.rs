@@ -0,0 +1,116 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_message::{Message, MessageId};
+use bee_tangle::{Hooks, Tangle};
+use bee_test::rand::message::{rand_message, rand_message_id};
+
+use std::{collections::HashMap, sync::Mutex};
+
+const BUDGET: usize = 64 * 1024;
+
+/// Hooks keeping the messages in memory, in place of a storage.
+#[derive(Default)]
+struct MemoryHooks {
+    messages: Mutex<HashMap<MessageId, Message>>,
+}
+
+impl Hooks<()> for MemoryHooks {
+    type Error = ();
+
+    fn get(&self, message_id: &MessageId) -> Result<Option<(Message, ())>, Self::Error> {
+        Ok(self
+            .messages
+            .lock()
+            .unwrap()
+            .get(message_id)
+            .cloned()
+            .map(|message| (message, ())))
+    }
+
+    fn insert(&self, message_id: MessageId, msg: Message, _metadata: ()) -> Result<(), Self::Error> {
+        self.messages.lock().unwrap().insert(message_id, msg);
+        Ok(())
+    }
+
+    fn fetch_approvers(&self, _message_id: &MessageId) -> Result<Option<Vec<MessageId>>, Self::Error> {
+        Ok(None)
+    }
+
+    fn insert_approver(&self, _message_id: MessageId, _approver: MessageId) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn update_approvers(&self, _message_id: MessageId, _approvers: &[MessageId]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+async fn insert_random_messages(tangle: &Tangle<(), MemoryHooks>, count: usize) {
+    for _ in 0..count {
+        tangle.insert(rand_message_id(), rand_message(), ()).await;
+    }
+}
+
+#[tokio::test]
+async fn cache_stays_within_byte_budget() {
+    let tangle = Tangle::new(MemoryHooks::default());
+    tangle.resize_bytes(BUDGET);
+
+    insert_random_messages(&tangle, 500).await;
+
+    assert!(tangle.size() <= BUDGET);
+    assert!(tangle.cache_metrics().evictions() > 0);
+}
+
+#[tokio::test]
+async fn protected_messages_are_not_evicted() {
+    let tangle = Tangle::new(MemoryHooks::default());
+    tangle.resize_bytes(BUDGET);
+
+    let message_id = rand_message_id();
+    tangle.insert(message_id, rand_message(), ()).await;
+    // Protections are counted, the message stays protected until it is unprotected as many times.
+    tangle.protect(message_id).await;
+    tangle.protect(message_id).await;
+    tangle.unprotect(&message_id).await;
+    assert!(tangle.is_protected(&message_id).await);
+
+    insert_random_messages(&tangle, 500).await;
+
+    assert!(tangle.cache_metrics().evictions() > 0);
+    assert!(tangle.get_metadata_maybe(&message_id).await.is_some());
+
+    tangle.unprotect(&message_id).await;
+    assert!(!tangle.is_protected(&message_id).await);
+
+    insert_random_messages(&tangle, 500).await;
+
+    assert!(tangle.get_metadata_maybe(&message_id).await.is_none());
+}
+
+#[tokio::test]
+async fn cache_metrics() {
+    let tangle = Tangle::new(MemoryHooks::default());
+    let message_id = rand_message_id();
+
+    // Inserting a new message first looks for it in the cache and in the storage.
+    tangle.insert(message_id, rand_message(), ()).await;
+    assert_eq!(tangle.cache_metrics().hits(), 0);
+    assert_eq!(tangle.cache_metrics().misses(), 1);
+
+    assert!(tangle.get(&message_id).await.is_some());
+    assert_eq!(tangle.cache_metrics().hits(), 1);
+
+    assert!(tangle.get(&rand_message_id()).await.is_none());
+    assert_eq!(tangle.cache_metrics().misses(), 2);
+    assert_eq!(tangle.cache_metrics().storage_fallbacks(), 0);
+
+    tangle.remove_from_cache(&message_id).await;
+
+    assert!(tangle.get(&message_id).await.is_some());
+    assert_eq!(tangle.cache_metrics().hits(), 1);
+    assert_eq!(tangle.cache_metrics().misses(), 3);
+    assert_eq!(tangle.cache_metrics().storage_fallbacks(), 1);
+    assert_eq!(tangle.cache_metrics().evictions(), 0);
+}